@@ -0,0 +1,238 @@
+use crate::timezone::{from_minutes, to_minutes, TimeZoneChoice};
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// Minutes of one hour
+const HOUR: i32 = 60;
+/// Minutes of one day
+const DAY: i32 = 24 * HOUR;
+/// Minutes of one week
+const WEEK: i32 = 7 * DAY;
+/// The unix epoch was a thursday, weeks start three days earlier on monday
+const WEEK_OFFSET: i32 = 3 * DAY;
+
+/// Contains all available aggregations of the sensor data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Aggregation {
+    /// The raw readings are shown
+    #[default]
+    Keine,
+    /// The readings are grouped by hour
+    Stuendlich,
+    /// The readings are grouped by day
+    Taeglich,
+    /// The readings are grouped by week, starting on monday
+    Woechentlich,
+}
+
+impl Aggregation {
+    pub const ALL: [Aggregation; 4] = [
+        Aggregation::Keine,
+        Aggregation::Stuendlich,
+        Aggregation::Taeglich,
+        Aggregation::Woechentlich,
+    ];
+
+    /// Returns the size of a bucket in minutes, `None` if the readings are not aggregated
+    pub fn bucket_minutes(&self) -> Option<i32> {
+        match self {
+            Aggregation::Keine => None,
+            Aggregation::Stuendlich => Some(HOUR),
+            Aggregation::Taeglich => Some(DAY),
+            Aggregation::Woechentlich => Some(WEEK),
+        }
+    }
+}
+
+impl Display for Aggregation {
+    /// Returns the name of the aggregation
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Aggregation::Keine => write!(f, "Rohdaten"),
+            Aggregation::Stuendlich => write!(f, "Stündlich"),
+            Aggregation::Taeglich => write!(f, "Täglich"),
+            Aggregation::Woechentlich => write!(f, "Wöchentlich"),
+        }
+    }
+}
+
+/// The readings of one time interval
+///
+/// Fields:
+/// - `start`: The start of the interval in minutes since the unix epoch
+/// - `mean`: The rounded mean of the readings
+/// - `min`: The smallest reading
+/// - `max`: The largest reading
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bucket {
+    pub start: i32,
+    pub mean: i32,
+    pub min: i32,
+    pub max: i32,
+}
+
+/// Returns the start of the bucket the minute belongs to
+fn bucket_start(minute: i32, size: i32) -> i32 {
+    let offset = if size == WEEK { WEEK_OFFSET } else { 0 };
+    (minute + offset).div_euclid(size) * size - offset
+}
+
+/// Returns the start of the bucket the minute belongs to in the wall clock time of the time zone
+///
+/// Hours are aligned per reading, so the repeated hour in autumn becomes two buckets. Days and
+/// weeks are aligned to the local midnight and last 23 or 25 hours when the clocks change.
+fn local_bucket_start(minute: i32, size: i32, time_zone: TimeZoneChoice) -> i32 {
+    let offset = time_zone.offset_minutes(&from_minutes(minute));
+    let local_start = bucket_start(minute + offset, size);
+    if size <= HOUR {
+        return minute - (minute + offset - local_start);
+    }
+    time_zone
+        .to_utc(&from_minutes(local_start).naive_utc())
+        .map(|start| to_minutes(&start))
+        .unwrap_or(local_start - offset)
+}
+
+/// Groups the readings into buckets of the given aggregation
+///
+/// The buckets follow the wall clock time of the time zone. Without an aggregation every reading
+/// becomes its own bucket.
+pub fn aggregate(
+    x: &[i32],
+    y: &[i32],
+    aggregation: Aggregation,
+    time_zone: TimeZoneChoice,
+) -> Vec<Bucket> {
+    let size = match aggregation.bucket_minutes() {
+        Some(size) => size,
+        None => {
+            return x
+                .iter()
+                .zip(y.iter())
+                .map(|(x, y)| Bucket {
+                    start: *x,
+                    mean: *y,
+                    min: *y,
+                    max: *y,
+                })
+                .collect()
+        }
+    };
+    // Sum, count, min and max of every bucket
+    let mut buckets: BTreeMap<i32, (i64, i64, i32, i32)> = BTreeMap::new();
+    for (x, y) in x.iter().zip(y.iter()) {
+        let bucket = buckets
+            .entry(local_bucket_start(*x, size, time_zone))
+            .or_insert((0, 0, *y, *y));
+        bucket.0 += *y as i64;
+        bucket.1 += 1;
+        bucket.2 = bucket.2.min(*y);
+        bucket.3 = bucket.3.max(*y);
+    }
+    buckets
+        .into_iter()
+        .map(|(start, (sum, count, min, max))| Bucket {
+            start,
+            mean: (sum as f64 / count as f64).round() as i32,
+            min,
+            max,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Tz;
+
+    const UTC: TimeZoneChoice = TimeZoneChoice::Zone(Tz::UTC);
+    const BERLIN: TimeZoneChoice = TimeZoneChoice::Zone(Tz::Europe__Berlin);
+
+    #[test]
+    fn test_aggregate_without_aggregation() {
+        let buckets = aggregate(&[1, 2], &[5, 6], Aggregation::Keine, UTC);
+        assert_eq!(buckets.len(), 2);
+        assert_eq!(
+            buckets[1],
+            Bucket {
+                start: 2,
+                mean: 6,
+                min: 6,
+                max: 6
+            }
+        );
+    }
+
+    #[test]
+    fn test_aggregate_hourly() {
+        let x = vec![0, 20, 59, 60, 130];
+        let y = vec![10, 20, 30, 5, -3];
+        let buckets = aggregate(&x, &y, Aggregation::Stuendlich, UTC);
+        assert_eq!(
+            buckets,
+            vec![
+                Bucket {
+                    start: 0,
+                    mean: 20,
+                    min: 10,
+                    max: 30
+                },
+                Bucket {
+                    start: 60,
+                    mean: 5,
+                    min: 5,
+                    max: 5
+                },
+                Bucket {
+                    start: 120,
+                    mean: -3,
+                    min: -3,
+                    max: -3
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weekly_buckets_start_on_monday() {
+        // 1970-01-05 was the first monday after the epoch
+        let monday = 4 * DAY;
+        assert_eq!(bucket_start(monday + 3 * DAY, WEEK), monday);
+        assert_eq!(bucket_start(monday - 1, WEEK), monday - WEEK);
+    }
+
+    #[test]
+    fn test_daily_buckets_follow_the_time_zone() {
+        // 2023-07-14 23:30 UTC is already the 15th in Berlin
+        let minute = 28_156_290;
+        let buckets = aggregate(&[minute], &[1], Aggregation::Taeglich, BERLIN);
+        assert_eq!(
+            from_minutes(buckets[0].start).to_rfc3339(),
+            "2023-07-14T22:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_daylight_saving_transitions() {
+        // 2023-10-29 00:30 and 01:30 UTC are both 02:30 in Berlin
+        let first = 28_308_990;
+        let x = vec![first, first + HOUR];
+        let hourly = aggregate(&x, &[1, 2], Aggregation::Stuendlich, BERLIN);
+        assert_eq!(hourly.len(), 2);
+        assert_eq!(hourly[1].start - hourly[0].start, HOUR);
+        // The day of the change lasts 25 hours
+        let x = vec![first - 120, first + 22 * HOUR];
+        let daily = aggregate(&x, &[1, 2], Aggregation::Taeglich, BERLIN);
+        assert_eq!(daily.len(), 1);
+        assert_eq!(
+            from_minutes(daily[0].start).to_rfc3339(),
+            "2023-10-28T22:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_aggregation_display() {
+        assert_eq!(Aggregation::Taeglich.to_string(), "Täglich");
+        assert_eq!(Aggregation::default(), Aggregation::Keine);
+    }
+}
@@ -0,0 +1,812 @@
+use crate::channels::{deliver, ChannelEditor, ChannelMessage, GroupChoice};
+use crate::detail::Sensortypes;
+use crate::graphs::{format_range, format_value, graph_points};
+use crate::notifications::{DesktopNotifier, NotificationSettings, Notifier};
+use crate::requests::{PlantReadings, RequestResult, SensorRange};
+use crate::timezone::{from_minutes, TimeZoneChoice};
+use crate::{Icon, Message, Tab, API_CLIENT, TEXT_SIZE};
+use chrono::{Duration, NaiveTime, SecondsFormat, Utc};
+use iced::alignment::Horizontal;
+use iced::widget::{scrollable, Button, Checkbox, Column, Container, Row, Rule, Text, TextInput};
+use iced::Alignment::Center;
+use iced::{Color, Command, Element, Length};
+use iced_aw::TabLabel;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// Minutes a sensor has to stay out of range before an alert is raised
+const MIN_DURATION: i32 = 30;
+/// Share of the range width a value has to be back inside the range to resolve an alert
+const HYSTERESIS: f64 = 0.05;
+/// Share of the range width a value has to be outside of the range to be critical
+const CRITICAL_SHARE: f64 = 0.25;
+/// Hours of readings that are loaded for every check
+const CHECK_HOURS: i64 = 6;
+/// Seconds between two checks in the background
+pub const CHECK_INTERVAL: u64 = 300;
+/// Number of resolved alerts that are kept in the list
+const MAX_RESOLVED: usize = 100;
+
+/// Contains the severities of an alert
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The value is slightly out of range
+    Warnung,
+    /// The value is far out of range
+    Kritisch,
+}
+
+impl Severity {
+    /// Returns the severity of a value outside of the range
+    fn of(value: i32, range: (i32, i32)) -> Severity {
+        let deviation = if value < range.0 {
+            range.0 - value
+        } else {
+            value - range.1
+        };
+        let width = (range.1 - range.0).max(1) as f64;
+        if deviation as f64 > width * CRITICAL_SHARE {
+            Severity::Kritisch
+        } else {
+            Severity::Warnung
+        }
+    }
+
+    /// Returns the color the severity is displayed with
+    pub fn color(&self) -> Color {
+        match self {
+            Severity::Warnung => Color::from_rgb(1.0, 0.65, 0.0),
+            Severity::Kritisch => Color::from_rgb(214.0 / 255.0, 73.0 / 255.0, 51.0 / 255.0),
+        }
+    }
+}
+
+impl Display for Severity {
+    /// Returns the name of the severity
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Warnung => write!(f, "Warnung"),
+            Severity::Kritisch => write!(f, "Kritisch"),
+        }
+    }
+}
+
+/// An alert raised for a sensor of a plant that left the range of its group
+///
+/// Fields:
+/// * `id` - The unique id of the alert
+/// * `plant_id` - The id of the plant
+/// * `plant_name` - The name of the plant
+/// * `group_id` - The id of the plant's group
+/// * `group_name` - The name of the plant's group
+/// * `sensor` - The sensor that is out of range
+/// * `value` - The latest value of the sensor
+/// * `range` - The min and max of the group's range
+/// * `severity` - The highest severity since the alert was raised
+/// * `since` - The first reading out of range in minutes since the unix epoch
+/// * `resolved` - The reading that resolved the alert, `None` while the alert is active
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alert {
+    pub id: u64,
+    pub plant_id: String,
+    pub plant_name: String,
+    pub group_id: i32,
+    pub group_name: String,
+    pub sensor: Sensortypes,
+    pub value: i32,
+    pub range: (i32, i32),
+    pub severity: Severity,
+    pub since: i32,
+    pub resolved: Option<i32>,
+}
+
+/// The state of one sensor of a plant between two checks
+#[derive(Debug, Clone, Default)]
+struct SensorState {
+    /// The latest reading that was evaluated
+    last_seen: Option<i32>,
+    /// The first reading of the current out of range period
+    out_since: Option<i32>,
+    /// The id of the active alert
+    active: Option<u64>,
+}
+
+/// Evaluates the readings of all plants against the sensor ranges of their groups
+///
+/// An alert is raised once a sensor stayed out of range for `min_duration` minutes. It is resolved
+/// once the value is back inside the range by `hysteresis` of the range width, so values close to
+/// the limits do not raise and resolve alerts over and over.
+#[derive(Debug, Clone)]
+pub struct AlertEngine {
+    pub min_duration: i32,
+    pub hysteresis: f64,
+    pub alerts: Vec<Alert>,
+    states: HashMap<(String, Sensortypes), SensorState>,
+    next_id: u64,
+}
+
+impl AlertEngine {
+    /// Creates a new alert engine with the default duration and hysteresis
+    pub fn new() -> Self {
+        AlertEngine {
+            min_duration: MIN_DURATION,
+            hysteresis: HYSTERESIS,
+            alerts: vec![],
+            states: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Returns all alerts that are not resolved
+    pub fn active(&self) -> impl Iterator<Item = &Alert> {
+        self.alerts.iter().filter(|alert| alert.resolved.is_none())
+    }
+
+    /// Removes all resolved alerts
+    pub fn clear_resolved(&mut self) {
+        self.alerts.retain(|alert| alert.resolved.is_none());
+    }
+
+    /// Evaluates the readings that were not evaluated yet
+    ///
+    /// Returns the alerts that were raised by these readings.
+    pub fn evaluate(&mut self, plants: &[PlantReadings]) -> Vec<Alert> {
+        let mut raised = vec![];
+        for plant in plants {
+            for (name, data) in plant.readings.iter() {
+                let sensor = match Sensortypes::from_name(name) {
+                    Some(sensor) => sensor,
+                    None => continue,
+                };
+                // Groups without a range for the sensor are not checked
+                let range = match plant
+                    .group
+                    .sensorRanges
+                    .iter()
+                    .find(|range| range.sensorType.name == *name)
+                    .and_then(SensorRange::bounds)
+                {
+                    Some(range) => range,
+                    None => continue,
+                };
+                for point in graph_points(std::slice::from_ref(data)) {
+                    if let Some(alert) = self.evaluate_reading(plant, sensor, range, point) {
+                        raised.push(alert);
+                    }
+                }
+            }
+        }
+        self.trim_resolved();
+        raised
+    }
+
+    /// Evaluates one reading of a sensor, returns the alert if one was raised
+    fn evaluate_reading(
+        &mut self,
+        plant: &PlantReadings,
+        sensor: Sensortypes,
+        range: (i32, i32),
+        (time, value): (i32, i32),
+    ) -> Option<Alert> {
+        let state = self.states.entry((plant.id.clone(), sensor)).or_default();
+        if state.last_seen.is_some_and(|last_seen| time <= last_seen) {
+            return None;
+        }
+        state.last_seen = Some(time);
+        let outside = value < range.0 || value > range.1;
+
+        if let Some(id) = state.active {
+            let margin = ((range.1 - range.0) as f64 * self.hysteresis).round() as i32;
+            let recovered = value >= range.0 + margin && value <= range.1 - margin;
+            if recovered {
+                state.active = None;
+                state.out_since = None;
+            }
+            if let Some(alert) = self.alerts.iter_mut().find(|alert| alert.id == id) {
+                alert.value = value;
+                alert.range = range;
+                if outside {
+                    alert.severity = alert.severity.max(Severity::of(value, range));
+                }
+                if recovered {
+                    alert.resolved = Some(time);
+                }
+            }
+            return None;
+        }
+
+        if !outside {
+            state.out_since = None;
+            return None;
+        }
+        let since = *state.out_since.get_or_insert(time);
+        if time - since < self.min_duration {
+            return None;
+        }
+        let alert = Alert {
+            id: self.next_id,
+            plant_id: plant.id.clone(),
+            plant_name: plant.name.clone(),
+            group_id: plant.group.id,
+            group_name: plant.group.name.clone(),
+            sensor,
+            value,
+            range,
+            severity: Severity::of(value, range),
+            since,
+            resolved: None,
+        };
+        self.next_id += 1;
+        state.active = Some(alert.id);
+        self.alerts.push(alert.clone());
+        Some(alert)
+    }
+
+    /// Removes the oldest resolved alerts if there are too many
+    fn trim_resolved(&mut self) {
+        let resolved = self.alerts.len() - self.active().count();
+        let mut to_remove = resolved.saturating_sub(MAX_RESOLVED);
+        self.alerts.retain(|alert| {
+            if to_remove > 0 && alert.resolved.is_some() {
+                to_remove -= 1;
+                return false;
+            }
+            true
+        });
+    }
+}
+
+/// Returns the plants whose readings were loaded and the description of the failed ones
+///
+/// The description is empty if every plant was loaded. A failed plant is not checked, so its
+/// alerts are neither raised nor resolved.
+fn split_failed(
+    results: Vec<(String, String, RequestResult<PlantReadings>)>,
+) -> (Vec<PlantReadings>, String) {
+    let mut plants = vec![];
+    let mut failed = vec![];
+    for (_, name, result) in results {
+        match result {
+            Ok(plant) => plants.push(plant),
+            Err(e) => failed.push(format!("{} ({})", name, e)),
+        }
+    }
+    let description = match failed.len() {
+        0 => String::new(),
+        1 => format!("1 Pflanze nicht geprüft: {}", failed[0]),
+        count => format!("{} Pflanzen nicht geprüft: {}", count, failed.join(", ")),
+    };
+    (plants, description)
+}
+
+/// Contains all messages of the alerts tab
+#[derive(Debug, Clone)]
+pub enum AlertsMessage {
+    /// Loads the latest readings of all plants and evaluates them
+    Check,
+    /// The readings were loaded
+    Checked(RequestResult<Vec<(String, String, RequestResult<PlantReadings>)>>),
+    /// Removes all resolved alerts from the list
+    ClearResolved,
+    /// Opens the plant of an alert on the detail page
+    OpenPlant(String),
+    /// Enables or disables the notifications of a sensor
+    NotifySensor(Sensortypes, bool),
+    /// Enables or disables the quiet hours
+    QuietHours(bool),
+    /// Updates the start of the quiet hours
+    QuietStartChanged(String),
+    /// Updates the end of the quiet hours
+    QuietEndChanged(String),
+    /// A desktop notification was sent
+    Notified(Result<(), String>),
+    /// Handles the messages of the alert channels
+    Channel(ChannelMessage),
+    /// An alert was sent over the alert channels
+    Delivered(Result<usize, String>),
+}
+
+/// The tab that lists all alerts
+///
+/// Fields:
+/// * `engine` - The engine that evaluates the readings
+/// * `last_check` - The time of the last successful check
+/// * `checking` - Indicates if a check is running
+/// * `error_message` - The error of the last check, or the plants it could not check
+/// * `notifications` - The settings of the desktop notifications
+/// * `held` - The alerts raised during the quiet hours, notified once they end
+/// * `notifier` - Sends the desktop notifications
+/// * `channels` - The webhook and email channels the alerts are sent to
+pub(crate) struct AlertsTab {
+    pub engine: AlertEngine,
+    pub notifications: NotificationSettings,
+    pub held: Vec<Alert>,
+    pub notifier: Box<dyn Notifier>,
+    pub channels: ChannelEditor,
+    last_check: String,
+    checking: bool,
+    error_message: String,
+}
+
+impl AlertsTab {
+    /// Creates a new alerts tab
+    pub fn new() -> Self {
+        AlertsTab {
+            engine: AlertEngine::new(),
+            notifications: NotificationSettings::new(),
+            held: Vec::new(),
+            notifier: Box::new(DesktopNotifier),
+            channels: ChannelEditor::new(),
+            last_check: String::new(),
+            checking: false,
+            error_message: String::new(),
+        }
+    }
+
+    /// Handles the messages of the alerts tab
+    pub fn update(&mut self, message: AlertsMessage) -> Command<AlertsMessage> {
+        match message {
+            AlertsMessage::Check => {
+                let client = match API_CLIENT.get() {
+                    Some(client) if !self.checking => client.clone(),
+                    _ => return Command::none(),
+                };
+                self.checking = true;
+                let now = Utc::now();
+                let time_range = (
+                    (now - Duration::hours(CHECK_HOURS))
+                        .to_rfc3339_opts(SecondsFormat::Millis, true),
+                    now.to_rfc3339_opts(SecondsFormat::Millis, true),
+                );
+                return Command::perform(
+                    client.get_recent_readings(time_range),
+                    AlertsMessage::Checked,
+                );
+            }
+            AlertsMessage::Checked(result) => {
+                self.checking = false;
+                match result {
+                    Ok(results) => {
+                        let (plants, failed) = split_failed(results);
+                        self.channels.set_groups(
+                            plants
+                                .iter()
+                                .map(|plant| GroupChoice {
+                                    id: plant.group.id,
+                                    name: plant.group.name.clone(),
+                                })
+                                .collect(),
+                        );
+                        let raised = self.engine.evaluate(&plants);
+                        self.last_check =
+                            TimeZoneChoice::current().format(&Utc::now(), "%d.%m.%Y %H:%M");
+                        self.error_message = failed;
+                        return self.deliver(raised);
+                    }
+                    Err(e) => self.error_message = e,
+                }
+            }
+            AlertsMessage::ClearResolved => self.engine.clear_resolved(),
+            // Handled by the application, which switches to the detail page
+            AlertsMessage::OpenPlant(_) => {}
+            AlertsMessage::NotifySensor(sensor, enabled) => {
+                self.notifications.set_sensor(sensor, enabled)
+            }
+            AlertsMessage::QuietHours(enabled) => self.notifications.quiet_hours_enabled = enabled,
+            AlertsMessage::QuietStartChanged(value) => self.notifications.quiet_start = value,
+            AlertsMessage::QuietEndChanged(value) => self.notifications.quiet_end = value,
+            AlertsMessage::Notified(result) => {
+                if let Err(e) = result {
+                    self.error_message = format!("Benachrichtigung fehlgeschlagen: {}", e);
+                }
+            }
+            AlertsMessage::Channel(message) => {
+                return self.channels.update(message).map(AlertsMessage::Channel)
+            }
+            AlertsMessage::Delivered(result) => {
+                if let Err(e) = result {
+                    self.error_message = format!("Versand fehlgeschlagen: {}", e);
+                }
+            }
+        }
+        Command::none()
+    }
+
+    /// Sends the raised alerts that are still active as desktop notifications and over the
+    /// alert channels
+    ///
+    /// The sensor switches and quiet hours only apply to the desktop notifications, the channels
+    /// are meant for the people who are not at the desktop.
+    fn deliver(&mut self, raised: Vec<Alert>) -> Command<AlertsMessage> {
+        let now = TimeZoneChoice::current().to_local(&Utc::now()).time();
+        let active: Vec<Alert> = raised
+            .into_iter()
+            .filter(|alert| self.engine.active().any(|active| active.id == alert.id))
+            .collect();
+        let mut channels = Vec::new();
+        if self.channels.settings.enabled() {
+            for alert in &active {
+                channels.push(Command::perform(
+                    deliver(self.channels.settings.clone(), alert.clone()),
+                    AlertsMessage::Delivered,
+                ));
+            }
+        }
+        Command::batch(self.notify(active, now).into_iter().chain(channels))
+    }
+
+    /// Sends the desktop notifications of the raised alerts at the given local time
+    ///
+    /// The alerts held back during the quiet hours are sent with the first check after them,
+    /// unless they were resolved in the meantime.
+    fn notify(&mut self, raised: Vec<Alert>, time: NaiveTime) -> Vec<Command<AlertsMessage>> {
+        self.notifications
+            .due(&mut self.held, raised, time)
+            .into_iter()
+            .filter(|alert| self.engine.active().any(|active| active.id == alert.id))
+            .map(|alert| Command::perform(self.notifier.send(alert), AlertsMessage::Notified))
+            .collect()
+    }
+
+    /// Returns the settings of the desktop notifications
+    fn notification_settings(&self) -> Element<'_, AlertsMessage> {
+        let sensors = Sensortypes::iter().fold(
+            Row::new()
+                .spacing(20)
+                .push(Text::new("Benachrichtigungen:").size(TEXT_SIZE)),
+            |row, sensor| {
+                row.push(
+                    Checkbox::new(
+                        sensor.to_string(),
+                        self.notifications.sensors.contains(&sensor),
+                        move |enabled| AlertsMessage::NotifySensor(sensor, enabled),
+                    )
+                    .text_size(TEXT_SIZE),
+                )
+            },
+        );
+        let quiet_hours = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(
+                Checkbox::new(
+                    "Ruhezeit",
+                    self.notifications.quiet_hours_enabled,
+                    AlertsMessage::QuietHours,
+                )
+                .text_size(TEXT_SIZE),
+            )
+            .push(
+                TextInput::new("22:00", &self.notifications.quiet_start)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(120))
+                    .on_input(AlertsMessage::QuietStartChanged),
+            )
+            .push(Text::new("bis").size(TEXT_SIZE))
+            .push(
+                TextInput::new("07:00", &self.notifications.quiet_end)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(120))
+                    .on_input(AlertsMessage::QuietEndChanged),
+            )
+            .push(if self.notifications.quiet_hours_invalid() {
+                Text::new("Format: HH:MM")
+                    .size(TEXT_SIZE)
+                    .style(Color::from_rgb(1.0, 0.0, 0.0))
+            } else {
+                Text::new("")
+            });
+        Column::new()
+            .spacing(10)
+            .push(sensors)
+            .push(quiet_hours)
+            .into()
+    }
+}
+
+/// Returns a cell of the alert list
+fn cell<'a>(text: impl ToString, color: Option<Color>) -> Container<'a, AlertsMessage> {
+    let text = Text::new(text.to_string()).size(TEXT_SIZE);
+    let text = match color {
+        Some(color) => text.style(color),
+        None => text,
+    };
+    Container::new(text)
+        .center_x()
+        .center_y()
+        .padding(10)
+        .width(Length::FillPortion(1))
+}
+
+impl Tab for AlertsTab {
+    type Message = Message;
+
+    fn title(&self) -> String {
+        String::from("Warnungen")
+    }
+
+    fn tab_label(&self) -> TabLabel {
+        TabLabel::IconText(Icon::Alert.into(), self.title())
+    }
+
+    fn content(&self) -> Element<'_, Self::Message> {
+        let status = if self.checking {
+            "Prüfe...".to_string()
+        } else if self.last_check.is_empty() {
+            "Noch nicht geprüft".to_string()
+        } else {
+            format!("Letzte Prüfung: {}", self.last_check)
+        };
+        let header_row = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(
+                Button::new(Text::new("Jetzt prüfen").size(TEXT_SIZE))
+                    .on_press(AlertsMessage::Check),
+            )
+            .push(
+                Button::new(Text::new("Behobene entfernen").size(TEXT_SIZE))
+                    .on_press(AlertsMessage::ClearResolved),
+            )
+            .push(Text::new(status).size(TEXT_SIZE))
+            .push(
+                Text::new(&self.error_message)
+                    .size(TEXT_SIZE)
+                    .style(Color::from_rgb(1.0, 0.0, 0.0)),
+            );
+
+        let mut alert_list = Column::new().width(Length::Fill).push(
+            Row::new()
+                .spacing(20)
+                .push(cell("Schweregrad", None))
+                .push(cell("Pflanze", None))
+                .push(cell("Sensor", None))
+                .push(cell("Wert", None))
+                .push(cell("Bereich", None))
+                .push(cell("Seit", None))
+                .push(cell("Status", None))
+                .push(cell("", None)),
+        );
+        let time_zone = TimeZoneChoice::current();
+        let mut alerts: Vec<&Alert> = self.engine.alerts.iter().collect();
+        // Active alerts first, the newest on top
+        alerts.sort_by_key(|alert| (alert.resolved.is_some(), std::cmp::Reverse(alert.since)));
+        for alert in alerts {
+            let status = match alert.resolved {
+                Some(resolved) => format!(
+                    "Behoben {}",
+                    time_zone.format(&from_minutes(resolved), "%d.%m. %H:%M")
+                ),
+                None => "Aktiv".to_string(),
+            };
+            let row = Row::new()
+                .spacing(20)
+                .push(cell(alert.severity, Some(alert.severity.color())))
+                .push(cell(&alert.plant_name, None))
+                .push(cell(alert.sensor, None))
+                .push(cell(format_value(alert.sensor, alert.value), None))
+                .push(cell(format_range(alert.sensor, alert.range), None))
+                .push(cell(
+                    time_zone.format(&from_minutes(alert.since), "%d.%m. %H:%M"),
+                    None,
+                ))
+                .push(cell(status, None))
+                .push(
+                    Container::new(
+                        Button::new(Text::new("Öffnen").size(TEXT_SIZE))
+                            .on_press(AlertsMessage::OpenPlant(alert.plant_id.clone())),
+                    )
+                    .center_x()
+                    .center_y()
+                    .width(Length::FillPortion(1)),
+                );
+            alert_list = alert_list.push(row).push(Rule::horizontal(10));
+        }
+        if self.engine.alerts.is_empty() {
+            alert_list = alert_list.push(
+                Text::new("Keine Warnungen")
+                    .size(TEXT_SIZE)
+                    .horizontal_alignment(Horizontal::Center),
+            );
+        }
+
+        let content: Element<'_, AlertsMessage> = Column::new()
+            .spacing(20)
+            .push(header_row)
+            .push(self.notification_settings())
+            .push(self.channels.view().map(AlertsMessage::Channel))
+            .push(
+                scrollable::Scrollable::new(alert_list)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .into();
+        content.map(Message::Alerts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requests::{GraphData, PlantGroupMetadata};
+    use iced::futures::future::BoxFuture;
+    use std::sync::{Arc, Mutex};
+
+    /// Records the alerts instead of sending them
+    #[derive(Clone, Default)]
+    struct RecordingNotifier(Arc<Mutex<Vec<Alert>>>);
+
+    impl Notifier for RecordingNotifier {
+        fn send(&self, alert: Alert) -> BoxFuture<'static, Result<(), String>> {
+            self.0.lock().unwrap().push(alert);
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    /// Returns the readings of one plant with soil moisture values every ten minutes
+    fn readings(start: i32, values: &[i32]) -> PlantReadings {
+        let mut group = PlantGroupMetadata::default();
+        group.sensorRanges[0].min = 30;
+        group.sensorRanges[0].max = 70;
+        let timestamps = (0..values.len())
+            .map(|i| from_minutes(start + i as i32 * 10).to_rfc3339_opts(SecondsFormat::Secs, true))
+            .collect();
+        PlantReadings {
+            id: "1".to_string(),
+            name: "Basilikum".to_string(),
+            group,
+            readings: vec![(
+                "soil-moisture".to_string(),
+                GraphData {
+                    values: values.to_vec(),
+                    timestamps,
+                },
+            )],
+            ..PlantReadings::default()
+        }
+    }
+
+    #[test]
+    fn test_alert_needs_min_duration() {
+        let mut engine = AlertEngine::new();
+        // Out of range for 20 minutes only
+        let raised = engine.evaluate(&[readings(0, &[50, 20, 20, 20, 50])]);
+        assert!(raised.is_empty());
+        assert!(engine.alerts.is_empty());
+    }
+
+    #[test]
+    fn test_alert_is_raised() {
+        let mut engine = AlertEngine::new();
+        let raised = engine.evaluate(&[readings(0, &[50, 25, 25, 25, 25])]);
+        assert_eq!(raised.len(), 1);
+        let alert = &raised[0];
+        assert_eq!(alert.plant_name, "Basilikum");
+        assert_eq!(alert.sensor, Sensortypes::Feuchtigkeit);
+        assert_eq!(alert.value, 25);
+        assert_eq!(alert.since, 10);
+        assert_eq!(alert.severity, Severity::Warnung);
+        // The same readings do not raise the alert again
+        assert!(engine
+            .evaluate(&[readings(0, &[50, 25, 25, 25, 25])])
+            .is_empty());
+        assert_eq!(engine.active().count(), 1);
+    }
+
+    #[test]
+    fn test_alert_hysteresis() {
+        let mut engine = AlertEngine::new();
+        engine.evaluate(&[readings(0, &[0, 0, 0, 0])]);
+        assert_eq!(engine.alerts[0].severity, Severity::Kritisch);
+        // 31 is inside the range but within the hysteresis of 2
+        engine.evaluate(&[readings(40, &[31])]);
+        assert_eq!(engine.active().count(), 1);
+        assert_eq!(engine.alerts[0].value, 31);
+        engine.evaluate(&[readings(50, &[40])]);
+        assert_eq!(engine.active().count(), 0);
+        assert_eq!(engine.alerts[0].resolved, Some(50));
+        engine.clear_resolved();
+        assert!(engine.alerts.is_empty());
+    }
+
+    #[test]
+    fn test_unset_range_is_ignored() {
+        let mut engine = AlertEngine::new();
+        let mut plant = readings(0, &[0, 0, 0, 0, 0]);
+        plant.group.sensorRanges[0].max = 30;
+        assert!(engine.evaluate(&[plant]).is_empty());
+    }
+
+    #[test]
+    fn test_severity() {
+        assert_eq!(Severity::of(25, (30, 70)), Severity::Warnung);
+        assert_eq!(Severity::of(81, (30, 70)), Severity::Kritisch);
+        assert_eq!(Severity::Kritisch.to_string(), "Kritisch");
+    }
+
+    #[test]
+    fn test_failed_plants_are_reported() {
+        let (plants, failed) = split_failed(vec![
+            (
+                "1".to_string(),
+                "Basilikum".to_string(),
+                Ok(readings(0, &[50])),
+            ),
+            (
+                "2".to_string(),
+                "Minze".to_string(),
+                Err("503 Service Unavailable".to_string()),
+            ),
+        ]);
+        assert_eq!(plants.len(), 1);
+        assert_eq!(
+            failed,
+            "1 Pflanze nicht geprüft: Minze (503 Service Unavailable)"
+        );
+
+        let (_, failed) = split_failed(vec![
+            (
+                "1".to_string(),
+                "Basilikum".to_string(),
+                Err("timeout".to_string()),
+            ),
+            (
+                "2".to_string(),
+                "Minze".to_string(),
+                Err("timeout".to_string()),
+            ),
+        ]);
+        assert_eq!(
+            failed,
+            "2 Pflanzen nicht geprüft: Basilikum (timeout), Minze (timeout)"
+        );
+        assert_eq!(split_failed(vec![]).1, "");
+    }
+
+    #[test]
+    fn test_notification_settings_messages() {
+        let mut tab = AlertsTab::new();
+        let _ = tab.update(AlertsMessage::NotifySensor(Sensortypes::Licht, true));
+        let _ = tab.update(AlertsMessage::QuietHours(true));
+        let _ = tab.update(AlertsMessage::QuietStartChanged("23:00".to_string()));
+        assert!(tab.notifications.sensors.contains(&Sensortypes::Licht));
+        assert!(tab.notifications.quiet_hours_enabled);
+        assert_eq!(tab.notifications.quiet_start, "23:00");
+    }
+
+    #[test]
+    fn test_quiet_hours_send_held_alerts_afterwards() {
+        let recorder = RecordingNotifier::default();
+        let mut tab = AlertsTab::new();
+        tab.notifier = Box::new(recorder.clone());
+        tab.notifications.quiet_hours_enabled = true;
+        let night = NaiveTime::from_hms_opt(23, 0, 0).unwrap();
+        let morning = NaiveTime::from_hms_opt(7, 5, 0).unwrap();
+
+        let raised = tab.engine.evaluate(&[readings(0, &[50, 25, 25, 25, 25])]);
+        assert!(tab.notify(raised.clone(), night).is_empty());
+        assert!(tab.notify(raised, night).is_empty());
+        assert!(recorder.0.lock().unwrap().is_empty());
+        assert_eq!(tab.held.len(), 1);
+
+        assert_eq!(tab.notify(vec![], morning).len(), 1);
+        assert_eq!(recorder.0.lock().unwrap()[0].plant_name, "Basilikum");
+        assert!(tab.held.is_empty());
+        assert!(tab.notify(vec![], morning).is_empty());
+    }
+
+    #[test]
+    fn test_resolved_held_alerts_are_dropped() {
+        let recorder = RecordingNotifier::default();
+        let mut tab = AlertsTab::new();
+        tab.notifier = Box::new(recorder.clone());
+        tab.notifications.quiet_hours_enabled = true;
+        let raised = tab.engine.evaluate(&[readings(0, &[50, 25, 25, 25, 25])]);
+        let _ = tab.notify(raised, NaiveTime::from_hms_opt(23, 0, 0).unwrap());
+        tab.engine.evaluate(&[readings(50, &[50])]);
+        assert!(tab
+            .notify(vec![], NaiveTime::from_hms_opt(8, 0, 0).unwrap())
+            .is_empty());
+        assert!(recorder.0.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,462 @@
+use crate::confirm::Confirmation;
+use crate::detail::URGENT_COLOR;
+use crate::forms::check_length;
+use crate::group_picker::{GroupPicker, GroupPickerMessage};
+use crate::plant_cards::Status;
+use crate::requests::{PlantMetadata, RequestResult};
+use crate::{API_CLIENT, TEXT_SIZE};
+use iced::widget::{pick_list, Button, Column, Row, Text, TextInput};
+use iced::{theme, Command, Element, Length};
+use iced_core::Alignment::Center;
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+/// Contains the actions that can be run on several plants at once
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BulkAction {
+    #[default]
+    Verschieben,
+    Loeschen,
+    Standort,
+    Pflegehinweis,
+}
+
+impl BulkAction {
+    /// All actions in the order they are offered
+    pub const ALL: [BulkAction; 4] = [
+        BulkAction::Verschieben,
+        BulkAction::Loeschen,
+        BulkAction::Standort,
+        BulkAction::Pflegehinweis,
+    ];
+}
+
+impl Display for BulkAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BulkAction::Verschieben => write!(f, "In Gruppe verschieben"),
+            BulkAction::Loeschen => write!(f, "Löschen"),
+            BulkAction::Standort => write!(f, "Standort setzen"),
+            BulkAction::Pflegehinweis => write!(f, "Pflegehinweis hinzufügen"),
+        }
+    }
+}
+
+/// A change of a plant with the values entered by the user
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkChange {
+    /// Moves the plant into the group with the id
+    Move(i32),
+    /// Deletes the plant
+    Delete,
+    /// Sets the location of the plant
+    Location(String),
+    /// Adds the care tip to the plant, unless it already has it
+    CareTip(String),
+}
+
+impl BulkChange {
+    /// Applies the change to the metadata of the plant and returns the id of its group afterwards
+    pub fn apply(&self, plant: &mut PlantMetadata) -> i32 {
+        match self {
+            BulkChange::Move(group_id) => return *group_id,
+            BulkChange::Delete => {}
+            BulkChange::Location(location) => plant.location = location.clone(),
+            BulkChange::CareTip(tip) => {
+                if !plant.additionalCareTips.contains(tip) {
+                    plant.additionalCareTips.push(tip.clone());
+                }
+            }
+        }
+        plant.plantGroup.id
+    }
+}
+
+/// Runs the change on one plant
+///
+/// The plant is loaded first and updated with its id, as an update replaces all of its metadata.
+async fn run(change: BulkChange, plant_id: String) -> RequestResult<()> {
+    let client = API_CLIENT.get().unwrap().clone();
+    if change == BulkChange::Delete {
+        return client
+            .delete_plant(plant_id)
+            .await
+            .map_err(|e| e.to_string());
+    }
+    let mut plant = client.clone().get_plant(plant_id.clone()).await?;
+    let group_id = change.apply(&mut plant);
+    client.create_plant(plant, group_id, Some(plant_id)).await
+}
+
+/// A plant the action runs on
+///
+/// Fields:
+/// * `id` - The id of the plant
+/// * `name` - The name of the plant
+/// * `result` - The result of the action, `None` while it is pending
+#[derive(Debug, Clone, PartialEq)]
+pub struct BulkItem {
+    pub id: String,
+    pub name: String,
+    pub result: Option<RequestResult<()>>,
+}
+
+/// Contains all messages of the bulk actions
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkMessage {
+    /// Selects the action
+    Action(BulkAction),
+    /// Handles the messages of the picker of the group the plants are moved to
+    Group(GroupPickerMessage),
+    /// Updates the location or care tip
+    Input(String),
+    /// Runs the action on the selected plants, handled by the plant table which owns the selection
+    Run,
+    /// Deletes the plants after it was confirmed
+    ConfirmDelete,
+    /// Closes the confirmation without deleting the plants
+    CancelDelete,
+    /// The action finished on the plant with the id
+    Done(String, RequestResult<()>),
+    /// Hides the summary of the last run
+    CloseSummary,
+}
+
+/// Runs an action on several plants, one after the other, and sums up the results
+///
+/// Fields:
+/// * `action` - The selected action
+/// * `group` - The picker of the group the plants are moved to
+/// * `input` - The location or care tip
+/// * `error` - The reason the action was not started
+/// * `change` - The change of the current run
+/// * `items` - The plants of the current or last run with their results
+/// * `confirm_delete` - The confirmation of deleting the plants, with their ids and names
+#[derive(Debug, Clone, Default)]
+pub struct BulkEditor {
+    pub action: BulkAction,
+    pub group: GroupPicker,
+    pub input: String,
+    pub error: String,
+    pub change: Option<BulkChange>,
+    pub items: Vec<BulkItem>,
+    pub confirm_delete: Option<Confirmation<Vec<(String, String)>>>,
+}
+
+impl BulkEditor {
+    /// Returns the change of the selected action, or sets the error if an input is missing
+    pub fn change(&mut self) -> Option<BulkChange> {
+        let input = self.input.trim().to_string();
+        let result = match self.action {
+            BulkAction::Verschieben => return self.group.validate().map(BulkChange::Move),
+            BulkAction::Loeschen => Ok(BulkChange::Delete),
+            BulkAction::Standort if input.is_empty() => {
+                Err("Bitte einen Standort eingeben".to_string())
+            }
+            BulkAction::Pflegehinweis if input.is_empty() => {
+                Err("Bitte einen Pflegehinweis eingeben".to_string())
+            }
+            BulkAction::Standort => check_length(&input).map(|_| BulkChange::Location(input)),
+            BulkAction::Pflegehinweis => check_length(&input).map(|_| BulkChange::CareTip(input)),
+        };
+        match result {
+            Ok(change) => {
+                self.error = String::new();
+                Some(change)
+            }
+            Err(e) => {
+                self.error = e;
+                None
+            }
+        }
+    }
+
+    /// Returns if the action still runs on some plants
+    pub fn is_running(&self) -> bool {
+        self.items.iter().any(|item| item.result.is_none())
+    }
+
+    /// Returns the number of finished plants and the number of all plants of the run
+    pub fn progress(&self) -> (usize, usize) {
+        let finished = self
+            .items
+            .iter()
+            .filter(|item| item.result.is_some())
+            .count();
+        (finished, self.items.len())
+    }
+
+    /// Returns the ids of the plants the action succeeded on
+    pub fn succeeded(&self) -> Vec<String> {
+        self.items
+            .iter()
+            .filter(|item| matches!(item.result, Some(Ok(()))))
+            .map(|item| item.id.clone())
+            .collect()
+    }
+
+    /// Starts the action on the plants given with their ids and names
+    ///
+    /// Deleting asks for a confirmation first. Nothing is started while a run is in progress.
+    pub fn start(&mut self, plants: Vec<(String, String)>) -> Command<BulkMessage> {
+        if self.is_running() {
+            return Command::none();
+        }
+        if plants.is_empty() {
+            self.error = "Bitte Pflanzen auswählen".to_string();
+            return Command::none();
+        }
+        match self.change() {
+            Some(BulkChange::Delete) => {
+                let title = match plants.len() {
+                    1 => "1 Pflanze löschen?".to_string(),
+                    count => format!("{} Pflanzen löschen?", count),
+                };
+                let names = plants.iter().map(|(_, name)| name.as_str()).join(", ");
+                self.confirm_delete = Some(Confirmation::new(
+                    plants,
+                    title,
+                    vec![
+                        names,
+                        "Die Messwerte und das Pflegetagebuch gehen verloren".to_string(),
+                    ],
+                ));
+                Command::none()
+            }
+            Some(change) => self.run(change, plants),
+            None => Command::none(),
+        }
+    }
+
+    /// Replaces the last run with a run of the change on the plants
+    fn run(&mut self, change: BulkChange, plants: Vec<(String, String)>) -> Command<BulkMessage> {
+        self.items = plants
+            .into_iter()
+            .map(|(id, name)| BulkItem {
+                id,
+                name,
+                result: None,
+            })
+            .collect();
+        self.change = Some(change);
+        self.next()
+    }
+
+    /// Runs the change on the next pending plant
+    ///
+    /// The plants are changed one after the other, so the progress can be shown.
+    fn next(&self) -> Command<BulkMessage> {
+        let pending = self.items.iter().find(|item| item.result.is_none());
+        match (&self.change, pending) {
+            (Some(change), Some(item)) => {
+                let id = item.id.clone();
+                Command::perform(run(change.clone(), id.clone()), move |result| {
+                    BulkMessage::Done(id, result)
+                })
+            }
+            _ => Command::none(),
+        }
+    }
+
+    /// Handles the messages of the bulk actions
+    pub fn update(&mut self, message: BulkMessage) -> Command<BulkMessage> {
+        match message {
+            BulkMessage::Action(action) => {
+                self.action = action;
+                self.input = String::new();
+                self.error = String::new();
+            }
+            BulkMessage::Group(message) => self.group.update(message),
+            BulkMessage::Input(input) => self.input = input,
+            // Handled by the plant table
+            BulkMessage::Run => {}
+            BulkMessage::ConfirmDelete => {
+                if let Some(confirmation) = self.confirm_delete.take() {
+                    return self.run(BulkChange::Delete, confirmation.item);
+                }
+            }
+            BulkMessage::CancelDelete => self.confirm_delete = None,
+            BulkMessage::Done(id, result) => {
+                if let Some(item) = self
+                    .items
+                    .iter_mut()
+                    .find(|item| item.id == id && item.result.is_none())
+                {
+                    item.result = Some(result);
+                }
+                return self.next();
+            }
+            BulkMessage::CloseSummary => {
+                if !self.is_running() {
+                    self.items.clear();
+                    self.change = None;
+                }
+            }
+        }
+        Command::none()
+    }
+
+    /// Returns the action with its input and the run button, below them the progress and results
+    pub fn view(&self, selected: usize) -> Element<'_, BulkMessage> {
+        let input: Element<'_, BulkMessage> = match self.action {
+            BulkAction::Verschieben => self.group.view().map(BulkMessage::Group),
+            BulkAction::Loeschen => Text::new("").into(),
+            BulkAction::Standort | BulkAction::Pflegehinweis => {
+                let placeholder = if self.action == BulkAction::Standort {
+                    "Neuer Standort"
+                } else {
+                    "Neuer Pflegehinweis"
+                };
+                TextInput::new(placeholder, &self.input)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(400))
+                    .on_input(BulkMessage::Input)
+                    .on_submit(BulkMessage::Run)
+                    .into()
+            }
+        };
+        let mut run = Button::new(Text::new("Ausführen").size(TEXT_SIZE));
+        if selected > 0 && !self.is_running() {
+            run = run.on_press(BulkMessage::Run);
+        }
+        let mut column = Column::new().spacing(10).push(
+            Row::new()
+                .spacing(20)
+                .align_items(Center)
+                .push(Text::new(format!("{} ausgewählt", selected)).size(TEXT_SIZE))
+                .push(
+                    pick_list(&BulkAction::ALL[..], Some(self.action), BulkMessage::Action)
+                        .text_size(TEXT_SIZE),
+                )
+                .push(input)
+                .push(run),
+        );
+        if !self.error.is_empty() {
+            column = column.push(
+                Text::new(&self.error)
+                    .size(TEXT_SIZE - 8)
+                    .style(URGENT_COLOR),
+            );
+        }
+        if self.items.is_empty() {
+            return column.into();
+        }
+        let (finished, total) = self.progress();
+        let mut summary = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(Text::new(format!("Fortschritt: {} von {}", finished, total)).size(TEXT_SIZE));
+        if !self.is_running() {
+            summary = summary.push(
+                Button::new(Text::new("Schließen").size(TEXT_SIZE))
+                    .style(theme::Button::Secondary)
+                    .on_press(BulkMessage::CloseSummary),
+            );
+        }
+        column = column.push(summary);
+        for item in &self.items {
+            column = column.push(match &item.result {
+                None => Text::new(format!("… {}", item.name)).size(TEXT_SIZE - 8),
+                Some(Ok(())) => Text::new(format!("✓ {}", item.name))
+                    .size(TEXT_SIZE - 8)
+                    .style(Status::Gut.color()),
+                Some(Err(e)) => Text::new(format!("✗ {}: {}", item.name, e))
+                    .size(TEXT_SIZE - 8)
+                    .style(URGENT_COLOR),
+            });
+        }
+        column.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plants() -> Vec<(String, String)> {
+        vec![
+            ("1".to_string(), "Basilikum".to_string()),
+            ("2".to_string(), "Minze".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_apply_change() {
+        let mut plant = PlantMetadata {
+            location: "Küche".to_string(),
+            additionalCareTips: vec!["Gießen".to_string()],
+            ..PlantMetadata::default()
+        };
+        plant.plantGroup.id = 4;
+        assert_eq!(BulkChange::Move(7).apply(&mut plant), 7);
+        assert_eq!(
+            BulkChange::Location("Balkon".to_string()).apply(&mut plant),
+            4
+        );
+        assert_eq!(plant.location, "Balkon");
+        BulkChange::CareTip("Gießen".to_string()).apply(&mut plant);
+        BulkChange::CareTip("Düngen".to_string()).apply(&mut plant);
+        assert_eq!(plant.additionalCareTips, vec!["Gießen", "Düngen"]);
+    }
+
+    #[test]
+    fn test_change_needs_input() {
+        let mut editor = BulkEditor::default();
+        assert_eq!(editor.change(), None);
+        assert_eq!(editor.group.error, "Bitte eine Gruppe auswählen");
+        let _ = editor.update(BulkMessage::Action(BulkAction::Standort));
+        let _ = editor.update(BulkMessage::Input("  ".to_string()));
+        assert_eq!(editor.change(), None);
+        assert_eq!(editor.error, "Bitte einen Standort eingeben");
+        let _ = editor.update(BulkMessage::Input(" Balkon ".to_string()));
+        assert_eq!(
+            editor.change(),
+            Some(BulkChange::Location("Balkon".to_string()))
+        );
+        assert!(editor.error.is_empty());
+    }
+
+    #[test]
+    fn test_delete_asks_first() {
+        let mut editor = BulkEditor::default();
+        let _ = editor.update(BulkMessage::Action(BulkAction::Loeschen));
+        let _ = editor.start(Vec::new());
+        assert_eq!(editor.error, "Bitte Pflanzen auswählen");
+        let _ = editor.start(plants());
+        let confirmation = editor.confirm_delete.as_ref().unwrap();
+        assert_eq!(confirmation.title, "2 Pflanzen löschen?");
+        assert!(editor.items.is_empty());
+        let _ = editor.update(BulkMessage::CancelDelete);
+        assert!(editor.confirm_delete.is_none());
+        assert!(editor.items.is_empty());
+    }
+
+    #[test]
+    fn test_progress_and_summary() {
+        let mut editor = BulkEditor {
+            items: plants()
+                .into_iter()
+                .map(|(id, name)| BulkItem {
+                    id,
+                    name,
+                    result: None,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        assert!(editor.is_running());
+        let _ = editor.update(BulkMessage::Done("2".to_string(), Ok(())));
+        assert_eq!(editor.progress(), (1, 2));
+        // A run in progress keeps its summary
+        let _ = editor.update(BulkMessage::CloseSummary);
+        assert_eq!(editor.items.len(), 2);
+        let _ = editor.update(BulkMessage::Done(
+            "1".to_string(),
+            Err("404 Not Found".to_string()),
+        ));
+        assert!(!editor.is_running());
+        assert_eq!(editor.succeeded(), vec!["2"]);
+        let _ = editor.update(BulkMessage::CloseSummary);
+        assert!(editor.items.is_empty());
+    }
+}
@@ -1,17 +1,15 @@
-use iced::theme::palette::Danger;
 use iced::widget::button;
 use iced::widget::button::{Appearance, StyleSheet};
-use iced::widget::canvas::Style;
-use iced::Theme;
-use iced_core::{Background, Color, Vector};
-use std::vec;
+use iced_core::{Background, Color};
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Default)]
 pub(crate) struct CustomButtonStyle {
     pub(crate) background: Color,
     pub(crate) text_color: Color,
     pub(crate) border_color: Color,
 }
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy, Default)]
 pub enum Button {
     #[default]
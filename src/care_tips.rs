@@ -0,0 +1,218 @@
+use crate::detail::URGENT_COLOR;
+use crate::forms::MAX_LENGTH;
+use crate::TEXT_SIZE;
+use iced::widget::{Button, Column, Row, Text, TextInput};
+use iced::{theme, Element, Length};
+
+/// Contains all messages of the care tips editor
+#[derive(Debug, Clone, PartialEq)]
+pub enum CareTipsMessage {
+    /// Updates the text of the tip at the index
+    Edit(usize, String),
+    /// Removes the tip at the index
+    Remove(usize),
+    /// Moves the tip at the index one up
+    MoveUp(usize),
+    /// Moves the tip at the index one down
+    MoveDown(usize),
+    /// Updates the input of the new tip
+    NewTip(String),
+    /// Adds the new tip at the end of the list
+    Add,
+}
+
+/// Edits a list of care tips, each tip has its own input
+///
+/// Fields:
+/// * `tips` - The care tips in the order they are shown
+/// * `new_tip` - The input of the tip that is added next
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CareTipsEditor {
+    pub tips: Vec<String>,
+    pub new_tip: String,
+}
+
+impl CareTipsEditor {
+    /// Creates an editor without tips
+    pub fn new() -> Self {
+        CareTipsEditor::default()
+    }
+
+    /// Creates the editor filled with the tips of a plant or group
+    pub fn from_tips(tips: &[String]) -> Self {
+        CareTipsEditor {
+            tips: tips.to_vec(),
+            new_tip: String::new(),
+        }
+    }
+
+    /// Returns the error of the tip at the index, if it is too long
+    pub fn error(&self, index: usize) -> Option<String> {
+        self.tips
+            .get(index)
+            .filter(|tip| tip.chars().count() > MAX_LENGTH)
+            .map(|_| format!("Höchstens {} Zeichen", MAX_LENGTH))
+    }
+
+    /// Returns if all tips are valid
+    pub fn is_valid(&self) -> bool {
+        (0..self.tips.len()).all(|index| self.error(index).is_none())
+    }
+
+    /// Writes the tips into the care tips of a plant or group, tips without text are dropped
+    pub fn apply_to(&self, tips: &mut Vec<String>) {
+        *tips = self
+            .tips
+            .iter()
+            .map(|tip| tip.trim())
+            .filter(|tip| !tip.is_empty())
+            .map(String::from)
+            .collect();
+    }
+
+    /// Handles the messages of the care tips editor
+    pub fn update(&mut self, message: CareTipsMessage) {
+        match message {
+            CareTipsMessage::Edit(index, tip) => {
+                if let Some(old) = self.tips.get_mut(index) {
+                    *old = tip;
+                }
+            }
+            CareTipsMessage::Remove(index) => {
+                if index < self.tips.len() {
+                    self.tips.remove(index);
+                }
+            }
+            CareTipsMessage::MoveUp(index) => {
+                if index > 0 && index < self.tips.len() {
+                    self.tips.swap(index - 1, index);
+                }
+            }
+            CareTipsMessage::MoveDown(index) => {
+                if index + 1 < self.tips.len() {
+                    self.tips.swap(index, index + 1);
+                }
+            }
+            CareTipsMessage::NewTip(tip) => self.new_tip = tip,
+            CareTipsMessage::Add => {
+                let tip = self.new_tip.trim();
+                if !tip.is_empty() {
+                    self.tips.push(tip.to_string());
+                    self.new_tip = String::new();
+                }
+            }
+        }
+    }
+
+    /// Returns the inputs of all tips with their controls and the input of the new tip
+    pub fn view(&self) -> Element<'_, CareTipsMessage> {
+        let control = |label: &str, message: Option<CareTipsMessage>| {
+            let button = Button::new(Text::new(label.to_string()).size(TEXT_SIZE))
+                .style(theme::Button::Secondary);
+            match message {
+                Some(message) => button.on_press(message),
+                None => button,
+            }
+        };
+        let mut column = Column::new()
+            .spacing(5)
+            .push(Text::new("Pflegehinweise").size(TEXT_SIZE));
+        for (index, tip) in self.tips.iter().enumerate() {
+            column = column.push(
+                Row::new()
+                    .spacing(5)
+                    .push(
+                        TextInput::new("Pflegehinweis", tip)
+                            .size(TEXT_SIZE)
+                            .width(Length::Fill)
+                            .on_input(move |tip| CareTipsMessage::Edit(index, tip)),
+                    )
+                    .push(control(
+                        "↑",
+                        (index > 0).then_some(CareTipsMessage::MoveUp(index)),
+                    ))
+                    .push(control(
+                        "↓",
+                        (index + 1 < self.tips.len()).then_some(CareTipsMessage::MoveDown(index)),
+                    ))
+                    .push(control("✕", Some(CareTipsMessage::Remove(index)))),
+            );
+            if let Some(error) = self.error(index) {
+                column = column.push(Text::new(error).size(TEXT_SIZE - 8).style(URGENT_COLOR));
+            }
+        }
+        column
+            .push(
+                Row::new()
+                    .spacing(5)
+                    .push(
+                        TextInput::new("Neuer Pflegehinweis", &self.new_tip)
+                            .size(TEXT_SIZE)
+                            .width(Length::Fill)
+                            .on_input(CareTipsMessage::NewTip)
+                            .on_submit(CareTipsMessage::Add),
+                    )
+                    .push(control(
+                        "+",
+                        (!self.new_tip.trim().is_empty()).then_some(CareTipsMessage::Add),
+                    )),
+            )
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor() -> CareTipsEditor {
+        CareTipsEditor::from_tips(&[
+            "Gießen".to_string(),
+            "Düngen; aber sparsam".to_string(),
+            "Umtopfen".to_string(),
+        ])
+    }
+
+    #[test]
+    fn test_add_and_remove() {
+        let mut editor = editor();
+        editor.update(CareTipsMessage::NewTip("  ".to_string()));
+        editor.update(CareTipsMessage::Add);
+        assert_eq!(editor.tips.len(), 3);
+        editor.update(CareTipsMessage::NewTip(" Sonnig ".to_string()));
+        editor.update(CareTipsMessage::Add);
+        assert_eq!(editor.tips[3], "Sonnig");
+        assert!(editor.new_tip.is_empty());
+        editor.update(CareTipsMessage::Remove(0));
+        editor.update(CareTipsMessage::Remove(10));
+        assert_eq!(editor.tips[0], "Düngen; aber sparsam");
+        assert_eq!(editor.tips.len(), 3);
+    }
+
+    #[test]
+    fn test_reorder() {
+        let mut editor = editor();
+        editor.update(CareTipsMessage::MoveUp(0));
+        editor.update(CareTipsMessage::MoveDown(2));
+        assert_eq!(editor, self::editor());
+        editor.update(CareTipsMessage::MoveDown(0));
+        editor.update(CareTipsMessage::MoveUp(2));
+        assert_eq!(
+            editor.tips,
+            vec!["Düngen; aber sparsam", "Umtopfen", "Gießen"]
+        );
+    }
+
+    #[test]
+    fn test_apply_keeps_semicolons() {
+        let mut editor = editor();
+        editor.update(CareTipsMessage::Edit(2, " ".to_string()));
+        assert!(editor.is_valid());
+        let mut tips = vec![];
+        editor.apply_to(&mut tips);
+        assert_eq!(tips, vec!["Gießen", "Düngen; aber sparsam"]);
+        editor.update(CareTipsMessage::Edit(0, "x".repeat(MAX_LENGTH + 1)));
+        assert!(!editor.is_valid());
+        assert!(editor.error(0).is_some());
+    }
+}
@@ -0,0 +1,767 @@
+use crate::alerts::{Alert, Severity};
+use crate::detail::Sensortypes;
+use crate::graphs::{factor, format_value, unit_symbol};
+use crate::timezone::{from_minutes, to_minutes, TimeZoneChoice};
+use crate::TEXT_SIZE;
+use chrono::{SecondsFormat, Utc};
+use iced::widget::{pick_list, Button, Checkbox, Column, Row, Text, TextInput};
+use iced::Alignment::Center;
+use iced::{Color, Command, Element, Length};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message as Email, Tokio1Executor};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+
+/// The placeholders that can be used in the templates
+pub const PLACEHOLDERS: &str =
+    "{pflanze} {gruppe} {sensor} {wert} {min} {max} {schweregrad} {seit}";
+
+/// The recipients of the alerts of one group, overriding the default recipients
+///
+/// Fields:
+/// * `webhook_urls` - The input of the comma separated webhook urls
+/// * `emails` - The input of the comma separated email addresses
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupRecipients {
+    pub webhook_urls: String,
+    pub emails: String,
+}
+
+/// The settings of the outgoing alert channels
+///
+/// Fields:
+/// * `webhook_enabled` - Indicates if alerts are posted to the webhooks
+/// * `webhook_urls` - The input of the comma separated default webhook urls
+/// * `webhook_template` - The template of the text of the webhook message
+/// * `email_enabled` - Indicates if alerts are sent as emails
+/// * `smtp_host` - The host of the SMTP server
+/// * `smtp_port` - The input of the port of the SMTP server
+/// * `smtp_user` - The user of the SMTP server, no authentication if empty
+/// * `smtp_password` - The password of the SMTP server
+/// * `smtp_tls` - Indicates if the connection is upgraded with STARTTLS
+/// * `email_from` - The sender address of the emails
+/// * `emails` - The input of the comma separated default email addresses
+/// * `subject_template` - The template of the email subject
+/// * `body_template` - The template of the email body
+/// * `recipients` - The recipients of single groups by group id
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelSettings {
+    pub webhook_enabled: bool,
+    pub webhook_urls: String,
+    pub webhook_template: String,
+    pub email_enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: String,
+    pub smtp_user: String,
+    pub smtp_password: String,
+    pub smtp_tls: bool,
+    pub email_from: String,
+    pub emails: String,
+    pub subject_template: String,
+    pub body_template: String,
+    pub recipients: BTreeMap<i32, GroupRecipients>,
+}
+
+impl ChannelSettings {
+    /// Creates the default settings with both channels disabled
+    pub fn new() -> Self {
+        ChannelSettings {
+            webhook_enabled: false,
+            webhook_urls: String::new(),
+            webhook_template: "{schweregrad}: {pflanze} ({gruppe}) - {sensor} {wert} außerhalb von {min} bis {max} seit {seit}".to_string(),
+            email_enabled: false,
+            smtp_host: String::new(),
+            smtp_port: "587".to_string(),
+            smtp_user: String::new(),
+            smtp_password: String::new(),
+            smtp_tls: true,
+            email_from: String::new(),
+            emails: String::new(),
+            subject_template: "[Plantbuddy] {schweregrad}: {pflanze} - {sensor}".to_string(),
+            body_template: "Die Pflanze {pflanze} der Gruppe {gruppe} meldet für {sensor} den Wert {wert}.\nDer Bereich der Gruppe liegt bei {min} bis {max}.\nAußerhalb des Bereichs seit {seit}.".to_string(),
+            recipients: BTreeMap::new(),
+        }
+    }
+
+    /// Returns if any channel is enabled
+    pub fn enabled(&self) -> bool {
+        self.webhook_enabled || self.email_enabled
+    }
+
+    /// Returns the port of the SMTP server, `None` if the input is not a port
+    pub fn port(&self) -> Option<u16> {
+        self.smtp_port.trim().parse().ok()
+    }
+
+    /// Returns the webhook urls of a group, the default urls if the group has none
+    pub fn webhook_urls_of(&self, group_id: i32) -> Vec<String> {
+        let group = self
+            .recipients
+            .get(&group_id)
+            .map(|recipients| split_list(&recipients.webhook_urls))
+            .unwrap_or_default();
+        if group.is_empty() {
+            split_list(&self.webhook_urls)
+        } else {
+            group
+        }
+    }
+
+    /// Returns the email addresses of a group, the default addresses if the group has none
+    pub fn emails_of(&self, group_id: i32) -> Vec<String> {
+        let group = self
+            .recipients
+            .get(&group_id)
+            .map(|recipients| split_list(&recipients.emails))
+            .unwrap_or_default();
+        if group.is_empty() {
+            split_list(&self.emails)
+        } else {
+            group
+        }
+    }
+}
+
+/// Splits a comma separated input into its trimmed, non empty entries
+fn split_list(input: &str) -> Vec<String> {
+    input
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Replaces the placeholders of a template with the values of the alert
+///
+/// The value and the range are written in the unit of the sensor, e.g. `42.50 %`.
+pub fn render(template: &str, alert: &Alert) -> String {
+    let since = TimeZoneChoice::current().format(&from_minutes(alert.since), "%d.%m.%Y %H:%M");
+    template
+        .replace("{pflanze}", &alert.plant_name)
+        .replace("{gruppe}", &alert.group_name)
+        .replace("{sensor}", &alert.sensor.to_string())
+        .replace("{wert}", &format_value(alert.sensor, alert.value))
+        .replace("{min}", &format_value(alert.sensor, alert.range.0))
+        .replace("{max}", &format_value(alert.sensor, alert.range.1))
+        .replace("{schweregrad}", &alert.severity.to_string())
+        .replace("{seit}", &since)
+}
+
+/// Returns an alert with example values that is sent by the test button
+pub fn sample_alert(group_id: i32, group_name: &str) -> Alert {
+    Alert {
+        id: 0,
+        plant_id: "0".to_string(),
+        plant_name: "Testpflanze".to_string(),
+        group_id,
+        group_name: group_name.to_string(),
+        sensor: Sensortypes::Feuchtigkeit,
+        value: 1200,
+        range: (3000, 7000),
+        severity: Severity::Kritisch,
+        since: to_minutes(&Utc::now()),
+        resolved: None,
+    }
+}
+
+/// Sends the alert over all enabled channels to the recipients of its group
+///
+/// Returns the number of sent messages, or the errors of all failed messages.
+pub async fn deliver(settings: ChannelSettings, alert: Alert) -> Result<usize, String> {
+    let mut sent = 0;
+    let mut errors = Vec::new();
+    if settings.webhook_enabled {
+        for url in settings.webhook_urls_of(alert.group_id) {
+            match send_webhook(&url, &settings.webhook_template, &alert).await {
+                Ok(()) => sent += 1,
+                Err(e) => errors.push(format!("Webhook {}: {}", url, e)),
+            }
+        }
+    }
+    if settings.email_enabled {
+        let emails = settings.emails_of(alert.group_id);
+        if !emails.is_empty() {
+            match send_email(&settings, &emails, &alert).await {
+                Ok(()) => sent += 1,
+                Err(e) => errors.push(format!("E-Mail: {}", e)),
+            }
+        }
+    }
+    if errors.is_empty() {
+        Ok(sent)
+    } else {
+        Err(errors.join(", "))
+    }
+}
+
+/// Converts a value of the server into a number in the unit of the sensor
+fn in_unit(sensor: Sensortypes, value: i32) -> f64 {
+    value as f64 / factor(sensor) as f64
+}
+
+/// Posts the alert as JSON to a webhook
+///
+/// The rendered template is sent in the `text` field, next to the values of the alert. The value
+/// and the range are numbers in the unit of the sensor, which is sent in the `unit` field.
+async fn send_webhook(url: &str, template: &str, alert: &Alert) -> Result<(), String> {
+    let payload = json!({
+        "text": render(template, alert),
+        "plant_id": alert.plant_id,
+        "plant": alert.plant_name,
+        "group_id": alert.group_id,
+        "group": alert.group_name,
+        "sensor": alert.sensor.to_string(),
+        "value": in_unit(alert.sensor, alert.value),
+        "min": in_unit(alert.sensor, alert.range.0),
+        "max": in_unit(alert.sensor, alert.range.1),
+        "unit": unit_symbol(alert.sensor),
+        "severity": alert.severity.to_string(),
+        "since": from_minutes(alert.since).to_rfc3339_opts(SecondsFormat::Secs, true),
+    });
+    reqwest::Client::new()
+        .post(url)
+        .json(&payload)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// Sends the alert as one email to all recipients
+async fn send_email(
+    settings: &ChannelSettings,
+    to: &[String],
+    alert: &Alert,
+) -> Result<(), String> {
+    let port = settings.port().ok_or("Ungültiger Port")?;
+    let from: Mailbox = settings
+        .email_from
+        .trim()
+        .parse()
+        .map_err(|_| format!("Ungültiger Absender {}", settings.email_from))?;
+    let mut builder = Email::builder()
+        .from(from)
+        .subject(render(&settings.subject_template, alert));
+    for address in to {
+        let mailbox: Mailbox = address
+            .parse()
+            .map_err(|_| format!("Ungültige Adresse {}", address))?;
+        builder = builder.to(mailbox);
+    }
+    let email = builder
+        .body(render(&settings.body_template, alert))
+        .map_err(|e| e.to_string())?;
+
+    let host = settings.smtp_host.trim();
+    let mut transport = if settings.smtp_tls {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host).map_err(|e| e.to_string())?
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host)
+    }
+    .port(port);
+    if !settings.smtp_user.is_empty() {
+        transport = transport.credentials(Credentials::new(
+            settings.smtp_user.clone(),
+            settings.smtp_password.clone(),
+        ));
+    }
+    transport
+        .build()
+        .send(email)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+/// A group whose recipients can be edited
+///
+/// Fields:
+/// * `id` - The id of the group
+/// * `name` - The name of the group
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupChoice {
+    pub id: i32,
+    pub name: String,
+}
+
+impl Display for GroupChoice {
+    /// Returns the name of the group
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+/// Contains all messages of the channel settings
+#[derive(Debug, Clone)]
+pub enum ChannelMessage {
+    /// Enables or disables the webhook channel
+    WebhookEnabled(bool),
+    /// Updates the default webhook urls
+    WebhookUrlsChanged(String),
+    /// Updates the template of the webhook text
+    WebhookTemplateChanged(String),
+    /// Enables or disables the email channel
+    EmailEnabled(bool),
+    /// Updates the SMTP host
+    SmtpHostChanged(String),
+    /// Updates the SMTP port
+    SmtpPortChanged(String),
+    /// Updates the SMTP user
+    SmtpUserChanged(String),
+    /// Updates the SMTP password
+    SmtpPasswordChanged(String),
+    /// Enables or disables STARTTLS
+    SmtpTls(bool),
+    /// Updates the sender address
+    EmailFromChanged(String),
+    /// Updates the default email addresses
+    EmailsChanged(String),
+    /// Updates the template of the email subject
+    SubjectTemplateChanged(String),
+    /// Updates the template of the email body
+    BodyTemplateChanged(String),
+    /// Selects the group whose recipients are edited
+    GroupSelected(GroupChoice),
+    /// Updates the webhook urls of the selected group
+    GroupWebhookUrlsChanged(String),
+    /// Updates the email addresses of the selected group
+    GroupEmailsChanged(String),
+    /// Sends a sample alert over the enabled channels
+    Test,
+    /// The sample alert was sent
+    Tested(Result<usize, String>),
+}
+
+/// The editor of the alert channels
+///
+/// Fields:
+/// * `settings` - The settings of the channels
+/// * `groups` - The groups the recipients can be set for
+/// * `selected_group` - The group whose recipients are edited
+/// * `status` - The result of the last test
+pub struct ChannelEditor {
+    pub settings: ChannelSettings,
+    pub groups: Vec<GroupChoice>,
+    pub selected_group: Option<GroupChoice>,
+    status: String,
+}
+
+impl ChannelEditor {
+    /// Creates a new editor with the default settings
+    pub fn new() -> Self {
+        ChannelEditor {
+            settings: ChannelSettings::new(),
+            groups: Vec::new(),
+            selected_group: None,
+            status: String::new(),
+        }
+    }
+
+    /// Updates the known groups, keeping the selection if the group still exists
+    pub fn set_groups(&mut self, mut groups: Vec<GroupChoice>) {
+        groups.sort_by(|a, b| a.name.cmp(&b.name));
+        groups.dedup();
+        if let Some(selected) = &self.selected_group {
+            if !groups.iter().any(|group| group.id == selected.id) {
+                self.selected_group = None;
+            }
+        }
+        self.groups = groups;
+    }
+
+    /// Returns the recipients of the selected group
+    fn group_recipients(&mut self) -> Option<&mut GroupRecipients> {
+        let id = self.selected_group.as_ref()?.id;
+        Some(self.settings.recipients.entry(id).or_default())
+    }
+
+    /// Handles the messages of the channel settings
+    pub fn update(&mut self, message: ChannelMessage) -> Command<ChannelMessage> {
+        match message {
+            ChannelMessage::WebhookEnabled(enabled) => self.settings.webhook_enabled = enabled,
+            ChannelMessage::WebhookUrlsChanged(value) => self.settings.webhook_urls = value,
+            ChannelMessage::WebhookTemplateChanged(value) => self.settings.webhook_template = value,
+            ChannelMessage::EmailEnabled(enabled) => self.settings.email_enabled = enabled,
+            ChannelMessage::SmtpHostChanged(value) => self.settings.smtp_host = value,
+            ChannelMessage::SmtpPortChanged(value) => self.settings.smtp_port = value,
+            ChannelMessage::SmtpUserChanged(value) => self.settings.smtp_user = value,
+            ChannelMessage::SmtpPasswordChanged(value) => self.settings.smtp_password = value,
+            ChannelMessage::SmtpTls(enabled) => self.settings.smtp_tls = enabled,
+            ChannelMessage::EmailFromChanged(value) => self.settings.email_from = value,
+            ChannelMessage::EmailsChanged(value) => self.settings.emails = value,
+            ChannelMessage::SubjectTemplateChanged(value) => self.settings.subject_template = value,
+            ChannelMessage::BodyTemplateChanged(value) => self.settings.body_template = value,
+            ChannelMessage::GroupSelected(group) => self.selected_group = Some(group),
+            ChannelMessage::GroupWebhookUrlsChanged(value) => {
+                if let Some(recipients) = self.group_recipients() {
+                    recipients.webhook_urls = value;
+                }
+            }
+            ChannelMessage::GroupEmailsChanged(value) => {
+                if let Some(recipients) = self.group_recipients() {
+                    recipients.emails = value;
+                }
+            }
+            ChannelMessage::Test => {
+                if !self.settings.enabled() {
+                    self.status = "Kein Kanal aktiviert".to_string();
+                    return Command::none();
+                }
+                self.status = "Sende Testwarnung...".to_string();
+                let alert = match &self.selected_group {
+                    Some(group) => sample_alert(group.id, &group.name),
+                    None => sample_alert(0, "Testgruppe"),
+                };
+                return Command::perform(
+                    deliver(self.settings.clone(), alert),
+                    ChannelMessage::Tested,
+                );
+            }
+            ChannelMessage::Tested(result) => {
+                self.status = match result {
+                    Ok(sent) => format!("{} Testnachricht(en) gesendet", sent),
+                    Err(e) => format!("Test fehlgeschlagen: {}", e),
+                }
+            }
+        }
+        Command::none()
+    }
+
+    /// Returns the view of the channel settings
+    pub fn view(&self) -> Element<'_, ChannelMessage> {
+        let input = |placeholder: &str, value: &str, width: u16| {
+            TextInput::new(placeholder, value)
+                .size(TEXT_SIZE)
+                .width(Length::from(width))
+        };
+        let webhook = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(
+                Checkbox::new(
+                    "Webhook",
+                    self.settings.webhook_enabled,
+                    ChannelMessage::WebhookEnabled,
+                )
+                .text_size(TEXT_SIZE),
+            )
+            .push(
+                input("https://...", &self.settings.webhook_urls, 400)
+                    .on_input(ChannelMessage::WebhookUrlsChanged),
+            )
+            .push(
+                input("Vorlage", &self.settings.webhook_template, 600)
+                    .on_input(ChannelMessage::WebhookTemplateChanged),
+            );
+        let smtp = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(
+                Checkbox::new(
+                    "E-Mail",
+                    self.settings.email_enabled,
+                    ChannelMessage::EmailEnabled,
+                )
+                .text_size(TEXT_SIZE),
+            )
+            .push(
+                input("SMTP-Server", &self.settings.smtp_host, 250)
+                    .on_input(ChannelMessage::SmtpHostChanged),
+            )
+            .push(
+                input("Port", &self.settings.smtp_port, 100)
+                    .on_input(ChannelMessage::SmtpPortChanged),
+            )
+            .push(
+                input("Benutzer", &self.settings.smtp_user, 200)
+                    .on_input(ChannelMessage::SmtpUserChanged),
+            )
+            .push(
+                input("Passwort", &self.settings.smtp_password, 200)
+                    .password()
+                    .on_input(ChannelMessage::SmtpPasswordChanged),
+            )
+            .push(
+                Checkbox::new("STARTTLS", self.settings.smtp_tls, ChannelMessage::SmtpTls)
+                    .text_size(TEXT_SIZE),
+            )
+            .push(if self.settings.port().is_none() {
+                Text::new("Ungültiger Port")
+                    .size(TEXT_SIZE)
+                    .style(Color::from_rgb(1.0, 0.0, 0.0))
+            } else {
+                Text::new("")
+            });
+        let email = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(
+                input("Absender", &self.settings.email_from, 250)
+                    .on_input(ChannelMessage::EmailFromChanged),
+            )
+            .push(
+                input("Empfänger", &self.settings.emails, 350)
+                    .on_input(ChannelMessage::EmailsChanged),
+            )
+            .push(
+                input("Betreff", &self.settings.subject_template, 400)
+                    .on_input(ChannelMessage::SubjectTemplateChanged),
+            )
+            .push(
+                input("Nachricht", &self.settings.body_template, 600)
+                    .on_input(ChannelMessage::BodyTemplateChanged),
+            );
+        let recipients = self
+            .selected_group
+            .as_ref()
+            .and_then(|group| self.settings.recipients.get(&group.id))
+            .cloned()
+            .unwrap_or_default();
+        let mut group_row = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(Text::new("Empfänger der Gruppe:").size(TEXT_SIZE))
+            .push(
+                pick_list(
+                    &self.groups[..],
+                    self.selected_group.clone(),
+                    ChannelMessage::GroupSelected,
+                )
+                .placeholder("Gruppe")
+                .text_size(TEXT_SIZE),
+            );
+        if self.selected_group.is_some() {
+            group_row = group_row
+                .push(
+                    input("Webhooks", &recipients.webhook_urls, 350)
+                        .on_input(ChannelMessage::GroupWebhookUrlsChanged),
+                )
+                .push(
+                    input("E-Mails", &recipients.emails, 350)
+                        .on_input(ChannelMessage::GroupEmailsChanged),
+                );
+        }
+        let test_row = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(
+                Button::new(Text::new("Testwarnung senden").size(TEXT_SIZE))
+                    .on_press(ChannelMessage::Test),
+            )
+            .push(Text::new(&self.status).size(TEXT_SIZE))
+            .push(Text::new(format!("Platzhalter: {}", PLACEHOLDERS)).size(TEXT_SIZE));
+        Column::new()
+            .spacing(10)
+            .push(webhook)
+            .push(smtp)
+            .push(email)
+            .push(group_row)
+            .push(test_row)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+    use tokio::task::JoinHandle;
+
+    /// Starts a stand-in HTTP server that answers one request and returns its body
+    async fn http_server() -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).await.unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).await.unwrap();
+            reader
+                .get_mut()
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(body).unwrap()
+        });
+        (url, handle)
+    }
+
+    /// Starts a stand-in SMTP server that accepts one email and returns the conversation
+    async fn smtp_server() -> (u16, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut conversation = String::new();
+            let mut data = false;
+            reader
+                .get_mut()
+                .write_all(b"220 localhost ESMTP\r\n")
+                .await
+                .unwrap();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).await.unwrap() == 0 {
+                    break;
+                }
+                conversation.push_str(&line);
+                let reply: &[u8] = if data {
+                    if line != ".\r\n" {
+                        continue;
+                    }
+                    data = false;
+                    b"250 OK\r\n"
+                } else if line.starts_with("EHLO") {
+                    b"250 localhost\r\n"
+                } else if line.starts_with("DATA") {
+                    data = true;
+                    b"354 Start mail input\r\n"
+                } else if line.starts_with("QUIT") {
+                    reader.get_mut().write_all(b"221 Bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 OK\r\n"
+                };
+                reader.get_mut().write_all(reply).await.unwrap();
+            }
+            conversation
+        });
+        (port, handle)
+    }
+
+    fn alert(group_id: i32) -> Alert {
+        Alert {
+            group_name: "Kräuter".to_string(),
+            plant_name: "Basilikum".to_string(),
+            value: 1250,
+            range: (3000, 7000),
+            ..sample_alert(group_id, "Kräuter")
+        }
+    }
+
+    #[test]
+    fn test_render() {
+        let text = render(
+            "{schweregrad}: {pflanze} ({gruppe}) {sensor} {wert} [{min}, {max}]",
+            &alert(1),
+        );
+        assert_eq!(
+            text,
+            "Kritisch: Basilikum (Kräuter) Feuchtigkeit 12.50 % [30 %, 70 %]"
+        );
+    }
+
+    #[test]
+    fn test_group_recipients() {
+        let mut settings = ChannelSettings::new();
+        settings.webhook_urls = "http://a, ,http://b".to_string();
+        settings.emails = "nacht@example.com".to_string();
+        settings.recipients.insert(
+            2,
+            GroupRecipients {
+                webhook_urls: String::new(),
+                emails: "kraeuter@example.com, chef@example.com".to_string(),
+            },
+        );
+        assert_eq!(settings.webhook_urls_of(1), vec!["http://a", "http://b"]);
+        assert_eq!(settings.webhook_urls_of(2), vec!["http://a", "http://b"]);
+        assert_eq!(settings.emails_of(1), vec!["nacht@example.com"]);
+        assert_eq!(
+            settings.emails_of(2),
+            vec!["kraeuter@example.com", "chef@example.com"]
+        );
+    }
+
+    #[test]
+    fn test_editor_group_messages() {
+        let mut editor = ChannelEditor::new();
+        // Without a selected group the input is ignored
+        let _ = editor.update(ChannelMessage::GroupEmailsChanged(
+            "a@example.com".to_string(),
+        ));
+        assert!(editor.settings.recipients.is_empty());
+        let group = GroupChoice {
+            id: 3,
+            name: "Kakteen".to_string(),
+        };
+        editor.set_groups(vec![group.clone()]);
+        let _ = editor.update(ChannelMessage::GroupSelected(group));
+        let _ = editor.update(ChannelMessage::GroupEmailsChanged(
+            "a@example.com".to_string(),
+        ));
+        assert_eq!(editor.settings.emails_of(3), vec!["a@example.com"]);
+        editor.set_groups(Vec::new());
+        assert_eq!(editor.selected_group, None);
+    }
+
+    #[tokio::test]
+    async fn test_webhook_delivery() {
+        let (url, server) = http_server().await;
+        let mut settings = ChannelSettings::new();
+        settings.webhook_enabled = true;
+        settings.webhook_template = "{pflanze}: {sensor}".to_string();
+        settings.recipients.insert(
+            1,
+            GroupRecipients {
+                webhook_urls: url,
+                emails: String::new(),
+            },
+        );
+        assert_eq!(deliver(settings, alert(1)).await, Ok(1));
+        let body: serde_json::Value = serde_json::from_str(&server.await.unwrap()).unwrap();
+        assert_eq!(body["text"], "Basilikum: Feuchtigkeit");
+        assert_eq!(body["group"], "Kräuter");
+        assert_eq!(body["value"], 12.5);
+        assert_eq!(body["max"], 70.0);
+        assert_eq!(body["unit"], "%");
+        assert_eq!(body["severity"], "Kritisch");
+    }
+
+    #[tokio::test]
+    async fn test_email_delivery() {
+        let (port, server) = smtp_server().await;
+        let mut settings = ChannelSettings::new();
+        settings.email_enabled = true;
+        settings.smtp_host = "127.0.0.1".to_string();
+        settings.smtp_port = port.to_string();
+        settings.smtp_tls = false;
+        settings.email_from = "plantbuddy@example.com".to_string();
+        settings.emails = "nacht@example.com".to_string();
+        settings.subject_template = "Warnung {pflanze}".to_string();
+        assert_eq!(deliver(settings, alert(1)).await, Ok(1));
+        let conversation = server.await.unwrap();
+        assert!(conversation.contains("MAIL FROM:<plantbuddy@example.com>"));
+        assert!(conversation.contains("RCPT TO:<nacht@example.com>"));
+        assert!(conversation.contains("Subject: Warnung Basilikum"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_delivery() {
+        let mut settings = ChannelSettings::new();
+        settings.email_enabled = true;
+        settings.email_from = "kein absender".to_string();
+        settings.emails = "nacht@example.com".to_string();
+        let result = deliver(settings, alert(1)).await;
+        assert_eq!(
+            result,
+            Err("E-Mail: Ungültiger Absender kein absender".to_string())
+        );
+    }
+}
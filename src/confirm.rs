@@ -0,0 +1,176 @@
+use crate::TEXT_SIZE;
+use iced::alignment::Horizontal;
+use iced::widget::{Button, Column, Container, Row, Text};
+use iced::{theme, Command, Element, Length};
+use iced_aw::{Card, Modal};
+use std::time::Duration;
+
+/// The number of seconds a deleted item can be restored
+pub const UNDO_SECONDS: u64 = 10;
+
+/// A destructive action that waits for the confirmation of the user
+///
+/// Fields:
+/// * `item` - The item that is deleted once confirmed, kept to restore it afterwards
+/// * `title` - The question naming the item
+/// * `consequences` - What is lost when the item is deleted
+/// * `ready` - Indicates if the item is complete, the item can only be deleted once it is
+#[derive(Debug, Clone, PartialEq)]
+pub struct Confirmation<T> {
+    pub item: T,
+    pub title: String,
+    pub consequences: Vec<String>,
+    pub ready: bool,
+}
+
+impl<T> Confirmation<T> {
+    /// Creates the confirmation of deleting the item
+    pub fn new(item: T, title: String, consequences: Vec<String>) -> Self {
+        Confirmation {
+            item,
+            title,
+            consequences,
+            ready: true,
+        }
+    }
+
+    /// Creates the confirmation of an item that is still loaded, deleting it is disabled until then
+    pub fn pending(item: T, title: String, consequences: Vec<String>) -> Self {
+        Confirmation {
+            ready: false,
+            ..Confirmation::new(item, title, consequences)
+        }
+    }
+
+    /// Returns the modal asking for the confirmation above the underlay
+    pub fn view<'a, M: Clone + 'a>(
+        &'a self,
+        underlay: Element<'a, M>,
+        on_confirm: M,
+        on_cancel: M,
+    ) -> Element<'a, M> {
+        let close = on_cancel.clone();
+        Modal::new(true, underlay, move || {
+            let body =
+                self.consequences
+                    .iter()
+                    .fold(Column::new().spacing(10), |column, consequence| {
+                        column.push(Text::new(format!("• {}", consequence)).size(TEXT_SIZE))
+                    });
+            Card::new(
+                Text::new(&self.title)
+                    .size(TEXT_SIZE)
+                    .horizontal_alignment(Horizontal::Center),
+                body,
+            )
+            .foot(
+                Row::new()
+                    .spacing(10)
+                    .padding(5)
+                    .width(Length::Fill)
+                    .push(
+                        Button::new(
+                            Text::new("Abbrechen")
+                                .size(TEXT_SIZE)
+                                .horizontal_alignment(Horizontal::Center),
+                        )
+                        .width(Length::Fill)
+                        .on_press(on_cancel.clone()),
+                    )
+                    .push({
+                        let delete = Button::new(
+                            Text::new("Löschen")
+                                .size(TEXT_SIZE)
+                                .horizontal_alignment(Horizontal::Center),
+                        )
+                        .style(theme::Button::Destructive)
+                        .width(Length::Fill);
+                        if self.ready {
+                            delete.on_press(on_confirm.clone())
+                        } else {
+                            delete
+                        }
+                    }),
+            )
+            .max_width(500.0)
+            .on_close(on_cancel.clone())
+            .into()
+        })
+        .backdrop(close.clone())
+        .on_esc(close)
+        .into()
+    }
+}
+
+/// A deleted item that can be restored until the toast is dismissed
+///
+/// Fields:
+/// * `key` - Identifies the deleted item, so an old timeout does not dismiss a newer toast
+/// * `item` - The cached item that is recreated on undo
+/// * `text` - The text of the toast
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndoToast<T> {
+    pub key: String,
+    pub item: T,
+    pub text: String,
+}
+
+impl<T> UndoToast<T> {
+    /// Creates the toast of a deleted item
+    pub fn new(key: String, item: T, text: String) -> Self {
+        UndoToast { key, item, text }
+    }
+
+    /// Returns the text with an undo and a close button
+    pub fn view<'a, M: Clone + 'a>(&'a self, on_undo: M, on_dismiss: M) -> Element<'a, M> {
+        Container::new(
+            Row::new()
+                .spacing(20)
+                .push(Text::new(&self.text).size(TEXT_SIZE))
+                .push(Button::new(Text::new("Rückgängig").size(TEXT_SIZE)).on_press(on_undo))
+                .push(
+                    Button::new(Text::new("✕").size(TEXT_SIZE))
+                        .style(theme::Button::Text)
+                        .on_press(on_dismiss),
+                ),
+        )
+        .style(theme::Container::Box)
+        .padding(10)
+        .into()
+    }
+}
+
+/// Returns a command sending the message once the undo time is over
+pub fn undo_timeout<M: Send + 'static>(message: M) -> Command<M> {
+    Command::perform(
+        tokio::time::sleep(Duration::from_secs(UNDO_SECONDS)),
+        move |_| message,
+    )
+}
+
+/// Dismisses the toast if it belongs to the key
+///
+/// Used when the timeout of a toast arrives, a newer toast with another key stays.
+pub fn dismiss<T>(toast: &mut Option<UndoToast<T>>, key: &str) {
+    if matches!(toast, Some(toast) if toast.key == key) {
+        *toast = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dismiss_only_matching_toast() {
+        let mut toast = Some(UndoToast::new(
+            "2".to_string(),
+            "Kräuter",
+            "Gruppe „Kräuter“ gelöscht".to_string(),
+        ));
+        dismiss(&mut toast, "1");
+        assert!(toast.is_some());
+        dismiss(&mut toast, "2");
+        assert!(toast.is_none());
+    }
+}
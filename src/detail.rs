@@ -1,12 +1,26 @@
-use crate::graphs::{PlantChart, PlantCharts};
-use std::collections::HashMap;
-
-use crate::requests::{GraphData, PlantGroupMetadata, PlantMetadata};
+use crate::aggregation::Aggregation;
+use crate::care_tips::{CareTipsEditor, CareTipsMessage};
+use crate::confirm::{dismiss, undo_timeout, Confirmation, UndoToast};
+use crate::forecast::Forecast;
+use crate::forms::{Form, GroupField, PlantField};
+use crate::gauge::Gauge;
+use crate::graphs::PlantCharts;
+use crate::group_picker::{GroupPicker, GroupPickerMessage};
+use crate::journal::{CareJournal, JournalMessage};
+use crate::plant_table::{PlantTable, PlantTableMessage};
+use crate::range_editor::{RangeEditor, RangeEditorMessage, PREVIEW_MINUTES};
+use crate::range_selector::{RangeMessage, RangePreset, RangeSelector};
+use crate::requests::{GraphData, PlantGroupMetadata, PlantMetadata, RequestResult, SensorRange};
+use crate::schedule::{TaskForm, TaskFormMessage, TaskTarget};
+use crate::sensor_health::{check_graph_data, SensorIssue};
+use crate::timezone::{from_minutes, to_minutes, TimeZoneChoice};
+use crate::watering::{format_interval, Waterings};
 use crate::{Icon, Message, MyStylesheet, Tab, API_CLIENT, TEXT_SIZE};
+use chrono::{DateTime, Utc};
 use iced::alignment::{Horizontal, Vertical};
 use iced::futures::TryFutureExt;
 
-use iced::widget::{scrollable, Button, Column, Container, Row, Text, TextInput};
+use iced::widget::{pick_list, scrollable, Button, Canvas, Column, Container, Row, Text};
 use iced::{theme, Command, Element, Length};
 use iced_aw::tab_bar::TabLabel;
 use iced_aw::{Card, Modal};
@@ -18,6 +32,22 @@ use rand::Rng;
 use std::fmt::{Display, Formatter};
 use std::vec;
 
+/// The number of detected waterings listed on the detail page, the most recent first
+const MAX_LISTED_WATERINGS: usize = 10;
+/// The gauges show the latest reading of this many minutes before now
+const GAUGE_MINUTES: i64 = 24 * 60;
+/// The width and height of a gauge in pixels
+const GAUGE_SIZE: u16 = 180;
+/// A plant that needs water within this many hours is highlighted
+pub const URGENT_HOURS: f64 = 24.0;
+/// The color of urgent forecasts, matches the danger color of the theme
+pub const URGENT_COLOR: iced::Color = iced::Color {
+    r: 214.0 / 255.0,
+    g: 73.0 / 255.0,
+    b: 51.0 / 255.0,
+    a: 1.0,
+};
+
 /// Stores all information about a plant that is displayed on the detail page
 ///
 /// Arguments:
@@ -56,16 +86,28 @@ impl DetailPlant {
 pub enum DetailMessage {
     /// Closes the modal and sends the changes of the plant or group to the server
     OkButtonPressed,
-    /// Sets the message to pending to display the overview
-    SwitchTime(chrono::Duration),
+    /// Handles the messages of the range selector
+    Range(RangeMessage),
     /// Opens the modal to edit the plant
     OpenModalPlant,
     /// Opens the modal to edit the group
     OpenModalGroup,
     /// Closes the modal
     CloseModal,
-    /// Deletes the plant
+    /// Asks for the confirmation of deleting the plant
     Delete,
+    /// Deletes the plant after it was confirmed
+    ConfirmDelete,
+    /// Closes the confirmation without deleting the plant
+    CancelDelete,
+    /// Recreates the deleted plant from its cached metadata
+    UndoDelete,
+    /// The plant with the given name was recreated, or recreating it failed
+    Restored(String, RequestResult<()>),
+    /// The changes of the plant or its group were saved, or saving them failed
+    Saved(RequestResult<()>),
+    /// Hides the undo toast of the plant with the given id
+    DismissUndo(String),
     /// The message is pending, the overview is displayed
     Pending,
     /// Loads the data of the plant
@@ -74,44 +116,102 @@ pub enum DetailMessage {
     PlantData(String),
     /// Indicates that the plant data was loaded
     Loaded,
-    /// Switches the graph to the given sensor
-    SwitchGraph(Sensortypes),
-    /// Handles the input of the plant id to search for a plant
-    Search(String),
-    /// Handles the input of the plant or group metadata
-    FieldUpdated(u8, String),
-    /// Indicates that the plant was deleted
-    DeleteSuccess,
+    /// The readings of the last day of every sensor of the plant with the given id were loaded
+    GaugesLoaded(String, Vec<(Sensortypes, Vec<(GraphData, String)>)>),
+    /// The readings of the last week of the group of the plant with the given id were loaded
+    PreviewLoaded(String, Vec<(Sensortypes, Vec<(GraphData, String)>)>),
+    /// Shows or hides the graph of the given sensor
+    ToggleSensor(Sensortypes),
+    /// Handles the messages of the table of all plants
+    Table(PlantTableMessage),
+    /// Handles the messages of the group picker of the plant
+    PlantGroup(GroupPickerMessage),
+    /// Updates a field of the form of the plant
+    PlantForm(PlantField, String),
+    /// Updates a field of the form of the group
+    GroupForm(GroupField, String),
+    /// Handles the messages of the care tips editor of the plant
+    PlantTips(CareTipsMessage),
+    /// Handles the messages of the care tips editor of the group
+    GroupTips(CareTipsMessage),
+    /// Handles the messages of the range editor of the group
+    Ranges(RangeEditorMessage),
+    /// The plant was deleted, or deleting it failed
+    DeleteDone(RequestResult<()>),
+    /// Toggles the logarithmic y axis for the light sensor
+    ToggleLogScale,
+    /// Switches the aggregation of the displayed readings
+    SwitchAggregation(Aggregation),
+    /// Exports the displayed readings as CSV file
+    ExportCsv,
+    /// Handles the messages of the care journal
+    Journal(JournalMessage),
+    /// Handles the messages of the care tasks of the plant
+    Tasks(TaskFormMessage),
+    /// Adds the watering detected at the given minutes to the care journal
+    AcceptWatering(i32),
 }
 
 /// Contains all information about the detail page
 ///
 /// Fields:
-/// * `active_sensor` - The sensor that is currently displayed
-/// * `timerange` - The timerange that is currently displayed
+/// * `active_sensors` - The sensors that are currently displayed, the first one uses the primary y axis
+/// * `range` - The selector of the timerange that is currently displayed
 /// * `modal` - Indicates if the modal is open
 /// * `modal_is_plant` - Indicates if the modal is open for a plant or a group
-/// * `additionalCareTips` - The additional care tips of the plant only for this plant
-/// * `careTips` - The care tips of the plant for all plants of this group
-/// * `sensor_border` - The min max values of the sensor
-/// * `id_names` - The id and name of the plant
+/// * `plant_form` - The inputs of the edited plant
+/// * `group_form` - The inputs of the edited group
+/// * `plant_tips` - The care tips of the edited plant
+/// * `group_tips` - The care tips of the edited group
+/// * `ranges` - The sensor ranges of the edited group
+/// * `table` - The table of all plants, shown if no plant is selected
+/// * `group_picker` - The picker of the group of the edited plant
 /// * `plant` - The plant that is displayed
 /// * `message` - The message that is currently displayed
+/// * `log_scale` - Indicates if the light sensor is displayed with a logarithmic y axis
+/// * `aggregation` - The aggregation of the displayed readings
+/// * `export_message` - The result of the last CSV export
+/// * `journal` - The care journal of the plant, drawn as markers into the charts
+/// * `tasks` - The recurring care tasks of the plant
+/// * `waterings` - The waterings detected in the soil moisture of the displayed timerange
+/// * `forecast` - The forecast when the soil moisture falls below the minimum of the group
+/// * `sensor_issues` - The gaps, stuck values and spikes found in the readings of the loaded sensors
+/// * `gauges` - The latest reading of every sensor with its range and trend
+/// * `confirm_delete` - The confirmation of deleting the plant, with its id and metadata
+/// * `deleting` - The id and metadata of the plant that is being deleted
+/// * `error` - The reason the last deletion, restore or change failed
+/// * `undo` - The toast to restore the last deleted plant
 pub(crate) struct DetailPage {
-    pub active_sensor: Sensortypes,
-    pub timerange: (String, String),
+    pub active_sensors: Vec<Sensortypes>,
+    pub range: RangeSelector,
     pub modal: bool,
     pub modal_is_plant: bool,
-    pub additionalCareTips: String,
-    pub careTips: String,
-    pub sensor_border: HashMap<String, String>,
-    pub id_names: Vec<(String, String)>,
+    pub plant_form: Form<PlantField>,
+    pub group_form: Form<GroupField>,
+    pub plant_tips: CareTipsEditor,
+    pub group_tips: CareTipsEditor,
+    pub ranges: RangeEditor,
+    pub table: PlantTable,
+    pub group_picker: GroupPicker,
     pub plant: DetailPlant,
     pub message: DetailMessage,
+    pub log_scale: bool,
+    pub aggregation: Aggregation,
+    pub export_message: String,
+    pub journal: CareJournal,
+    pub tasks: TaskForm,
+    pub waterings: Waterings,
+    pub forecast: Option<Forecast>,
+    pub sensor_issues: Vec<SensorIssue>,
+    pub gauges: Vec<Gauge>,
+    pub confirm_delete: Option<Confirmation<(String, PlantMetadata)>>,
+    pub deleting: Option<(String, PlantMetadata)>,
+    pub error: String,
+    pub undo: Option<UndoToast<PlantMetadata>>,
 }
 
 /// Contains all available sensors, their names, and colors
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Sensortypes {
     /// Soil moisture sensor
     Feuchtigkeit,
@@ -146,6 +246,21 @@ impl Sensortypes {
         }
     }
 
+    /// Returns the sensor with the given english name of the server
+    pub fn from_name(name: &str) -> Option<Sensortypes> {
+        Sensortypes::iter().find(|sensor| sensor.get_name() == name)
+    }
+
+    /// Returns the default unit of the sensor, as used by the server
+    pub fn get_unit(&self) -> String {
+        match self {
+            Sensortypes::Feuchtigkeit => String::from("percent"),
+            Sensortypes::Luftfeuchtigkeit => String::from("percent"),
+            Sensortypes::Temperatur => String::from("celcius"),
+            Sensortypes::Licht => String::from("lux"),
+        }
+    }
+
     /// Returns the color associated with the sensor
     pub fn get_color(&self) -> RGBColor {
         match self {
@@ -166,7 +281,7 @@ impl Sensortypes {
             Sensortypes::Feuchtigkeit => RGBColor(offset, offset2, 255 - offset3),
             Sensortypes::Luftfeuchtigkeit => RGBColor(offset, 255 - offset3, offset2),
             Sensortypes::Temperatur => RGBColor(255 - offset3, offset, offset2),
-            Sensortypes::Licht => RGBColor(255 - offset3, 255 - offset3.clone(), offset),
+            Sensortypes::Licht => RGBColor(255 - offset3, 255 - offset3, offset),
         }
     }
 
@@ -191,22 +306,58 @@ impl DetailPage {
             charts: PlantCharts::new(Vec::new(), DetailMessage::Loaded),
         };
         DetailPage {
-            active_sensor: Sensortypes::Feuchtigkeit,
-            id_names: vec![],
-            timerange: (
-                "2019-01-01T00:00:00.000Z".to_string(),
-                chrono::offset::Local::now()
-                    .format("%Y-%m-%dT%H:%M:%S.000Z")
-                    .to_string(),
-            ),
+            active_sensors: vec![Sensortypes::Feuchtigkeit],
+            table: PlantTable::new(),
+            group_picker: GroupPicker::new(),
+            range: RangeSelector::new(RangePreset::Gesamt),
             modal: false,
             modal_is_plant: true,
-            careTips: String::new(),
-            sensor_border: HashMap::new(),
-            additionalCareTips: String::new(),
+            plant_form: Form::new(),
+            group_form: Form::new(),
+            plant_tips: CareTipsEditor::new(),
+            group_tips: CareTipsEditor::new(),
+            ranges: RangeEditor::new(),
             plant,
             message: DetailMessage::Pending,
+            log_scale: false,
+            aggregation: Aggregation::default(),
+            export_message: String::new(),
+            journal: CareJournal::new(),
+            tasks: TaskForm::new(),
+            waterings: Waterings::default(),
+            forecast: None,
+            sensor_issues: Vec::new(),
+            gauges: Vec::new(),
+            confirm_delete: None,
+            deleting: None,
+            error: String::new(),
+            undo: None,
+        }
+    }
+    /// Returns the confirmation of deleting the displayed plant, naming what is lost
+    pub fn delete_confirmation(&self) -> Confirmation<(String, PlantMetadata)> {
+        let data = &self.plant.data;
+        let mut consequences = vec![];
+        if !data.plantGroup.name.is_empty() {
+            consequences.push(format!(
+                "Die Pflanze wird aus der Gruppe „{}“ entfernt",
+                data.plantGroup.name
+            ));
+        }
+        if !self.journal.entries.is_empty() {
+            consequences.push(format!(
+                "{} Einträge im Pflegetagebuch gehen verloren",
+                self.journal.entries.len()
+            ));
         }
+        consequences.push(
+            "Rückgängig stellt nur die Stammdaten wieder her, nicht die Messwerte".to_string(),
+        );
+        Confirmation::new(
+            (self.plant.id.clone(), data.clone()),
+            format!("Pflanze „{}“ löschen?", data.name),
+            consequences,
+        )
     }
     /// If the string is longer than 30 characters, a newline is inserted every 30 characters
     pub fn insert_newline_to_string(&self, string: String) -> String {
@@ -224,77 +375,312 @@ impl DetailPage {
         }
         new_string
     }
-    /// Adds the sensor border graph to the plant charts
-    pub fn min_max_graphs(&self, sensor_types: Sensortypes) -> Vec<PlantChart> {
-        let mut charts = vec![];
+    /// Returns the min and max of the group's sensor range for the given sensor, `None` if it is
+    /// unset
+    pub fn sensor_range(&self, sensor_types: Sensortypes) -> Option<(i32, i32)> {
         self.plant
             .data
             .plantGroup
             .sensorRanges
             .iter()
-            .filter(|sensor| sensor.sensorType.name == sensor_types.get_name())
-            .for_each(|sensor| {
-                let current_chart = self
-                    .plant
-                    .charts
-                    .charts
-                    .get(0)
-                    .map(|chart| chart.clone())
-                    .unwrap_or_default();
-                charts.push(PlantChart::new(
-                    format!("{:?}_Max_Grenze", self.plant.data.name.clone()),
-                    current_chart.x.clone(),
-                    vec![sensor.max; current_chart.x.len()],
-                    BLACK,
-                ));
-                charts.push(PlantChart::new(
-                    format!("{:?}_Min_Grenze", self.plant.data.name.clone()),
-                    current_chart.x.clone(),
-                    vec![sensor.min; current_chart.x.len()],
-                    BLACK,
+            .find(|sensor| sensor.sensorType.name == sensor_types.get_name())
+            .and_then(SensorRange::bounds)
+    }
+    /// Returns the unit of the given sensor, using the unit of the group's sensor range if available
+    pub fn unit(&self, sensor_types: Sensortypes) -> String {
+        self.plant
+            .data
+            .plantGroup
+            .sensorRanges
+            .iter()
+            .find(|sensor| sensor.sensorType.name == sensor_types.get_name())
+            .map(|sensor| sensor.sensorType.unit.clone())
+            .filter(|unit| !unit.is_empty())
+            .unwrap_or_else(|| sensor_types.get_unit())
+    }
+    /// Returns the title of the y axis for the given sensor
+    pub fn axis_label(&self, sensor_types: Sensortypes) -> String {
+        format!("{} [{}]", sensor_types, self.unit(sensor_types))
+    }
+    /// Returns the sensor that is drawn against the primary y axis
+    pub fn primary_sensor(&self) -> Sensortypes {
+        self.active_sensors
+            .first()
+            .copied()
+            .unwrap_or(Sensortypes::Feuchtigkeit)
+    }
+    /// Returns true if the sensor has a different unit than the primary sensor
+    pub fn is_secondary(&self, sensor_types: Sensortypes) -> bool {
+        self.unit(sensor_types) != self.unit(self.primary_sensor())
+    }
+    /// Shows the sensor if it is hidden and hides it if it is shown, the last sensor stays visible
+    ///
+    /// The chart has one axis per side, so only the primary unit and one other unit are shown.
+    /// A sensor with a third unit replaces the sensors of the secondary axis.
+    pub fn toggle_sensor(&mut self, sensor_types: Sensortypes) {
+        if let Some(position) = self.active_sensors.iter().position(|s| *s == sensor_types) {
+            if self.active_sensors.len() > 1 {
+                self.active_sensors.remove(position);
+            }
+        } else {
+            if self.is_secondary(sensor_types) {
+                let units = [self.unit(self.primary_sensor()), self.unit(sensor_types)];
+                self.active_sensors = self
+                    .active_sensors
+                    .iter()
+                    .copied()
+                    .filter(|sensor| units.contains(&self.unit(*sensor)))
+                    .collect();
+            }
+            self.active_sensors.push(sensor_types);
+        }
+    }
+    /// Applies the sensor range, axis titles and scale of the active sensors to the charts
+    fn apply_axes(&mut self) {
+        let primary = self.primary_sensor();
+        self.plant.charts.range = self.sensor_range(primary);
+        self.plant.charts.y_label = self.axis_label(primary);
+        self.plant.charts.secondary_label = self
+            .active_sensors
+            .iter()
+            .filter(|sensor| self.is_secondary(**sensor))
+            .map(|sensor| self.axis_label(*sensor))
+            .collect::<Vec<String>>()
+            .join(", ");
+        self.plant.charts.log_scale = self.log_scale && primary == Sensortypes::Licht;
+        self.plant.charts.aggregation = self.aggregation;
+        self.plant.charts.markers = self.journal.markers();
+        self.plant.charts.projection = match self.forecast {
+            Some(forecast) if primary == Sensortypes::Feuchtigkeit => forecast.projection(),
+            _ => Vec::new(),
+        };
+    }
+    /// Analyses the soil moisture for waterings and forecasts when the plant needs water
+    ///
+    /// Forecasts from a timerange that ended long ago are dropped.
+    fn analyse_soil_moisture(&mut self, graph_data: &[GraphData]) {
+        self.waterings = Waterings::from_graph_data(graph_data);
+        let now = to_minutes(&Utc::now());
+        self.forecast = self
+            .sensor_range(Sensortypes::Feuchtigkeit)
+            .and_then(|(min, _)| Forecast::fit(graph_data, min))
+            .filter(|forecast| forecast.is_current(now));
+    }
+    /// Returns the end of the displayed timerange in minutes, never later than now
+    fn checked_until(&self) -> i32 {
+        let now = Utc::now();
+        let end = DateTime::parse_from_rfc3339(&self.range.timerange().1)
+            .map(|end| end.with_timezone(&Utc).min(now))
+            .unwrap_or(now);
+        to_minutes(&end)
+    }
+    /// Loads the readings of the last day of every sensor for the gauges
+    fn load_gauges(&self) -> Command<DetailMessage> {
+        let now = Utc::now();
+        let timerange = (
+            (now - chrono::Duration::minutes(GAUGE_MINUTES))
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        );
+        let id = self.plant.id.clone();
+        Command::perform(
+            API_CLIENT
+                .get()
+                .unwrap()
+                .clone()
+                .get_sensor_graphs(vec![id.clone()], timerange),
+            move |graphs| DetailMessage::GaugesLoaded(id, graphs),
+        )
+    }
+    /// Creates the gauges from the loaded readings of every sensor
+    fn set_gauges(&mut self, graphs: Vec<(Sensortypes, Vec<(GraphData, String)>)>) {
+        self.gauges = graphs
+            .into_iter()
+            .map(|(sensor, data)| {
+                let graph_data: Vec<GraphData> =
+                    data.into_iter().map(|(graph_data, _)| graph_data).collect();
+                Gauge::new(sensor, &graph_data, self.sensor_range(sensor))
+            })
+            .collect();
+    }
+    /// Loads the readings of the last week of all plants of the group for the preview of the ranges
+    fn load_range_preview(&self) -> Command<DetailMessage> {
+        let now = Utc::now();
+        let timerange = (
+            (now - chrono::Duration::minutes(PREVIEW_MINUTES))
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+            now.to_rfc3339_opts(chrono::SecondsFormat::Millis, true),
+        );
+        let mut plant_ids: Vec<String> = self
+            .table
+            .rows
+            .iter()
+            .filter(|row| row.group.id == self.plant.data.plantGroup.id)
+            .map(|row| row.id.clone())
+            .collect();
+        if !plant_ids.contains(&self.plant.id) {
+            plant_ids.push(self.plant.id.clone());
+        }
+        let id = self.plant.id.clone();
+        Command::perform(
+            API_CLIENT
+                .get()
+                .unwrap()
+                .clone()
+                .get_sensor_graphs(plant_ids, timerange),
+            move |graphs| DetailMessage::PreviewLoaded(id, graphs),
+        )
+    }
+    /// Loads the data of all active sensors and draws them into one chart
+    fn load_charts(&mut self) {
+        let mut charts = vec![];
+        self.sensor_issues = Vec::new();
+        for sensor_types in self.active_sensors.clone() {
+            let data = API_CLIENT
+                .get()
+                .unwrap()
+                .clone()
+                .get_graphs(
+                    vec![self.plant.id.clone()],
+                    true,
+                    sensor_types.get_name(),
+                    self.range.timerange(),
+                )
+                .unwrap_or_default();
+            let graph_data: Vec<GraphData> = data.iter().map(|(g, _)| g.clone()).collect();
+            if sensor_types == Sensortypes::Feuchtigkeit {
+                self.analyse_soil_moisture(&graph_data);
+            }
+            let mut issues = check_graph_data(sensor_types, &graph_data, self.checked_until());
+            self.sensor_issues.append(&mut issues);
+            let secondary = self.is_secondary(sensor_types);
+            let mut sensor_charts = PlantCharts::create_charts(
+                DetailMessage::Loaded,
+                graph_data,
+                sensor_types,
+                vec![self.plant.data.name.clone()],
+            )
+            .charts;
+            sensor_charts
+                .iter_mut()
+                .for_each(|chart| chart.secondary = secondary);
+            charts.append(&mut sensor_charts);
+        }
+        self.plant.charts = PlantCharts::new(charts, DetailMessage::Loaded);
+        self.apply_axes();
+    }
+    /// Returns the detected waterings with buttons to add them to the care journal
+    fn waterings_view(&self) -> Column<'_, DetailMessage> {
+        let time_zone = TimeZoneChoice::current();
+        let mut column = Column::new()
+            .spacing(10)
+            .push(Text::new("Erkannte Bewässerungen:").size(TEXT_SIZE));
+        let last = match self.waterings.last() {
+            Some(event) => time_zone.format(&from_minutes(event.minutes), "%d.%m.%Y %H:%M"),
+            None => "-".to_string(),
+        };
+        column = column.push(Text::new(format!("Zuletzt gegossen: {}", last)).size(TEXT_SIZE));
+        if let Some(interval) = self.waterings.average_interval() {
+            column = column.push(
+                Text::new(format!(
+                    "Durchschnittlich alle {}",
+                    format_interval(interval)
                 ))
-            });
-        charts
+                .size(TEXT_SIZE),
+            );
+        }
+        let suggestions = self.waterings.suggestions(&self.journal.entries);
+        for event in self
+            .waterings
+            .events
+            .iter()
+            .rev()
+            .take(MAX_LISTED_WATERINGS)
+        {
+            let mut row = Row::new().spacing(10).align_items(Center).push(
+                Text::new(format!(
+                    "{}: {:.1} % → {:.1} %",
+                    time_zone.format(&from_minutes(event.minutes), "%d.%m. %H:%M"),
+                    event.before,
+                    event.after
+                ))
+                .size(TEXT_SIZE),
+            );
+            if suggestions.contains(event) {
+                row = row.push(
+                    Button::new(Text::new("Ins Tagebuch").size(TEXT_SIZE))
+                        .on_press(DetailMessage::AcceptWatering(event.minutes)),
+                );
+            }
+            column = column.push(row);
+        }
+        column
     }
     /// Handles the messages for the detail page
     pub fn update(&mut self, message: DetailMessage) -> Command<DetailMessage> {
         match message {
-            DetailMessage::SwitchTime(value) => {
-                info!("Switching time to {:?}", value);
-                let now = chrono::offset::Local::now();
-                let start = now - value;
-                self.timerange = (
-                    start.format("%Y-%m-%dT%H:%M:%S.000Z").to_string(),
-                    now.format("%Y-%m-%dT%H:%M:%S.000Z").to_string(),
-                );
-                return self.update(DetailMessage::SwitchGraph(self.active_sensor));
+            DetailMessage::Range(message) => {
+                if self.range.update(message) {
+                    info!("Switching time to {:?}", self.range.timerange());
+                    self.load_charts();
+                }
             }
             DetailMessage::Pending => {
                 self.message = DetailMessage::Pending;
             }
             DetailMessage::Delete => {
-                let plant_id = self.plant.id.clone();
+                self.modal = false;
+                self.confirm_delete = Some(self.delete_confirmation());
+            }
+            DetailMessage::ConfirmDelete => {
+                let (plant_id, data) = match self.confirm_delete.take() {
+                    Some(confirmation) => confirmation.item,
+                    None => return Command::none(),
+                };
+                self.deleting = Some((plant_id.clone(), data));
+                self.error = String::new();
                 return Command::perform(
                     API_CLIENT
                         .get()
                         .unwrap()
                         .clone()
                         .delete_plant(plant_id)
-                        .unwrap_or_else(|_| ()),
-                    |_| DetailMessage::DeleteSuccess,
+                        .map_err(|e| e.to_string()),
+                    DetailMessage::DeleteDone,
                 );
             }
+            DetailMessage::CancelDelete => self.confirm_delete = None,
+            DetailMessage::UndoDelete => {
+                if let Some(toast) = self.undo.take() {
+                    let group_id = toast.item.plantGroup.id;
+                    let name = toast.item.name.clone();
+                    return Command::perform(
+                        API_CLIENT
+                            .get()
+                            .unwrap()
+                            .clone()
+                            .create_plant(toast.item, group_id, None),
+                        move |result| DetailMessage::Restored(name, result),
+                    );
+                }
+            }
+            DetailMessage::Restored(name, Err(e)) => {
+                self.error = format!("Pflanze „{}“ wurde nicht wiederhergestellt: {}", name, e);
+            }
+            DetailMessage::Restored(_, Ok(())) => return self.update(DetailMessage::Load),
+            DetailMessage::Saved(Err(e)) => {
+                self.error = format!("Die Änderungen wurden nicht gespeichert: {}", e);
+            }
+            DetailMessage::Saved(Ok(())) => (),
+            DetailMessage::DismissUndo(plant_id) => dismiss(&mut self.undo, &plant_id),
 
             DetailMessage::Load => {
-                info!("Refresh Id List");
-                //if empty self.id_names should be an empty vec
-                self.id_names = API_CLIENT
-                    .get()
-                    .unwrap()
-                    .clone()
-                    .get_all_plant_ids_names()
-                    .unwrap_or_default();
+                info!("Refresh plant table");
                 self.message = DetailMessage::Pending;
+                return self
+                    .table
+                    .update(PlantTableMessage::Load)
+                    .map(DetailMessage::Table);
             }
             DetailMessage::PlantData(id) => {
                 let data = API_CLIENT
@@ -305,208 +691,214 @@ impl DetailPage {
                         vec![id.clone()],
                         true,
                         Sensortypes::Feuchtigkeit.get_name(),
-                        self.timerange.clone(),
+                        self.range.timerange(),
                     )
                     .unwrap_or_default();
                 let graph_data: Vec<GraphData> = data.iter().map(|(g, _)| g.clone()).collect();
-                self.plant = DetailPlant::new(id, graph_data);
-                self.additionalCareTips = String::new();
-                self.plant.data.additionalCareTips.iter().for_each(|x| {
-                    self.additionalCareTips.push_str(x);
-                    self.additionalCareTips.push(';');
-                });
-                self.careTips = String::new();
-                self.plant.data.plantGroup.careTips.iter().for_each(|x| {
-                    self.careTips.push_str(x);
-                    self.careTips.push(';');
-                });
+                self.plant = DetailPlant::new(id, graph_data.clone());
+                self.analyse_soil_moisture(&graph_data);
+                self.sensor_issues =
+                    check_graph_data(Sensortypes::Feuchtigkeit, &graph_data, self.checked_until());
                 info!("SensorType: {:?}", self.plant.data.plantGroup.sensorRanges);
-                self.plant
-                    .data
-                    .plantGroup
-                    .sensorRanges
-                    .iter()
-                    .for_each(|x| match x.sensorType.name.as_str() {
-                        "soil-moisture" => {
-                            self.sensor_border.insert(
-                                Sensortypes::Feuchtigkeit.get_name(),
-                                format!("{};{}", x.max, x.min),
-                            );
-                        }
-                        "humidity" => {
-                            self.sensor_border.insert(
-                                Sensortypes::Luftfeuchtigkeit.get_name(),
-                                format!("{};{}", x.max, x.min),
-                            );
-                        }
-                        "temperature" => {
-                            self.sensor_border.insert(
-                                Sensortypes::Temperatur.get_name(),
-                                format!("{};{}", x.max, x.min),
-                            );
-                        }
-                        "light" => {
-                            self.sensor_border.insert(
-                                Sensortypes::Licht.get_name(),
-                                format!("{};{}", x.max, x.min),
-                            );
-                        }
-                        _ => {}
-                    });
-                Sensortypes::iter().for_each(|sensor| {
-                    if !self.sensor_border.contains_key(sensor.get_name().as_str()) {
-                        self.sensor_border
-                            .insert(sensor.get_name(), String::from("0;0"));
-                    }
-                });
-                self.plant
-                    .charts
-                    .charts
-                    .append(&mut self.min_max_graphs(Sensortypes::Feuchtigkeit));
+                self.active_sensors = vec![Sensortypes::Feuchtigkeit];
+                self.gauges = Vec::new();
+                self.apply_axes();
                 self.message = DetailMessage::Loaded;
+                let id = self.plant.id.clone();
+                self.tasks.update(TaskFormMessage::Select(
+                    TaskTarget::Plant(id.clone()),
+                    self.plant.data.name.clone(),
+                ));
+                return Command::batch(vec![
+                    self.load_gauges(),
+                    self.journal
+                        .update(JournalMessage::Load(id))
+                        .map(DetailMessage::Journal),
+                ]);
             }
-            DetailMessage::SwitchGraph(sensor_types) => {
-                info!("Switching Graph to {:?}", sensor_types);
-                self.active_sensor = sensor_types;
-                let sensor_name = sensor_types.get_name();
-                let data = API_CLIENT
-                    .get()
-                    .unwrap()
-                    .clone()
-                    .get_graphs(
-                        vec![self.plant.id.clone()],
-                        true,
-                        sensor_name,
-                        self.timerange.clone(),
-                    )
-                    .unwrap_or_default();
-                let graph_data: Vec<GraphData> = data.iter().map(|(g, _)| g.clone()).collect();
-                self.plant.charts = PlantCharts::update_charts(
-                    &self.plant.charts,
-                    DetailMessage::Loaded,
-                    graph_data,
-                    sensor_types,
-                    vec![self.plant.data.name.clone()],
-                );
-                self.plant
-                    .charts
-                    .charts
-                    .append(&mut self.min_max_graphs(sensor_types));
+            DetailMessage::GaugesLoaded(id, graphs) => {
+                // The readings of a plant that is no longer shown are dropped
+                if id == self.plant.id {
+                    self.set_gauges(graphs);
+                }
+            }
+            DetailMessage::PreviewLoaded(id, graphs) => {
+                if id == self.plant.id {
+                    for (sensor, data) in graphs {
+                        let graph_data: Vec<GraphData> =
+                            data.into_iter().map(|(graph_data, _)| graph_data).collect();
+                        self.ranges.set_preview(sensor, &graph_data);
+                    }
+                }
+            }
+            DetailMessage::ToggleSensor(sensor_types) => {
+                info!("Toggling Graph {:?}", sensor_types);
+                self.toggle_sensor(sensor_types);
+                self.load_charts();
                 self.message = DetailMessage::Loaded;
             }
             DetailMessage::Loaded => {}
-            DetailMessage::Search(value) => {
-                self.plant.id = value;
+            DetailMessage::PlantGroup(message) => self.group_picker.update(message),
+            DetailMessage::Table(PlantTableMessage::Open(id)) => {
+                return self.update(DetailMessage::PlantData(id));
+            }
+            DetailMessage::Table(message) => {
+                return self.table.update(message).map(DetailMessage::Table);
             }
             DetailMessage::OpenModalPlant => {
+                let groups = API_CLIENT
+                    .get()
+                    .unwrap()
+                    .clone()
+                    .get_all_groups()
+                    .unwrap_or_default();
+                self.group_picker.set_groups(groups);
+                self.group_picker.select_id(self.plant.data.plantGroup.id);
+                self.plant_form = Form::from_plant(&self.plant.data);
+                self.plant_tips = CareTipsEditor::from_tips(&self.plant.data.additionalCareTips);
                 self.modal_is_plant = true;
                 self.modal = true;
             }
             DetailMessage::OpenModalGroup => {
+                self.group_form = Form::from_group(&self.plant.data.plantGroup);
+                self.group_tips = CareTipsEditor::from_tips(&self.plant.data.plantGroup.careTips);
+                self.ranges = RangeEditor::from_group(&self.plant.data.plantGroup);
                 self.modal_is_plant = false;
                 self.modal = true;
+                return self.load_range_preview();
             }
             DetailMessage::CloseModal => {
                 self.modal = false;
             }
             DetailMessage::OkButtonPressed => {
                 return if self.modal_is_plant {
-                    self.plant.data.additionalCareTips = self
-                        .additionalCareTips
-                        .split(';')
-                        .map(|x| x.to_string())
-                        .collect();
+                    if self.group_picker.validate().is_none()
+                        || !self.plant_form.is_valid()
+                        || !self.plant_tips.is_valid()
+                    {
+                        self.plant_form.touch_all();
+                        return Command::none();
+                    }
+                    if let Some(group) = &self.group_picker.selected {
+                        self.plant.data.plantGroup.id = group.id;
+                        self.plant.data.plantGroup.name = group.name.clone();
+                    }
+                    self.plant_form.apply_to(&mut self.plant.data);
+                    self.plant_tips
+                        .apply_to(&mut self.plant.data.additionalCareTips);
                     self.modal = false;
+                    self.error = String::new();
                     Command::perform(
                         API_CLIENT.get().unwrap().clone().create_plant(
                             self.plant.data.clone(),
-                            self.plant.data.plantGroup.id.clone(),
+                            self.plant.data.plantGroup.id,
                             Some(self.plant.id.clone()),
                         ),
-                        |_| DetailMessage::Loaded,
+                        DetailMessage::Saved,
                     )
                 } else {
-                    self.plant.data.plantGroup.careTips =
-                        self.careTips.split(';').map(|x| x.to_string()).collect();
-                    for sensor in self.plant.data.plantGroup.sensorRanges.iter_mut() {
-                        for i in Sensortypes::iter() {
-                            if i.get_name() == sensor.sensorType.name {
-                                sensor.max = self
-                                    .sensor_border
-                                    .clone()
-                                    .get(i.get_name().as_str())
-                                    .unwrap()
-                                    .split(';')
-                                    .next()
-                                    .unwrap()
-                                    .parse()
-                                    .unwrap_or_default();
-                                sensor.min = self
-                                    .sensor_border
-                                    .clone()
-                                    .get(i.get_name().as_str())
-                                    .unwrap()
-                                    .split(';')
-                                    .last()
-                                    .unwrap()
-                                    .parse()
-                                    .unwrap_or_default();
-                            }
-                        }
+                    if !self.group_form.is_valid()
+                        || !self.group_tips.is_valid()
+                        || !self.ranges.is_valid()
+                    {
+                        self.group_form.touch_all();
+                        self.ranges.touch_all();
+                        return Command::none();
                     }
+                    self.group_form.apply_to(&mut self.plant.data.plantGroup);
+                    self.group_tips
+                        .apply_to(&mut self.plant.data.plantGroup.careTips);
+                    self.ranges.apply_to(&mut self.plant.data.plantGroup);
                     self.modal = false;
+                    self.error = String::new();
                     Command::perform(
                         API_CLIENT.get().unwrap().clone().create_group(
                             self.plant.data.plantGroup.clone(),
                             Some(self.plant.data.plantGroup.id.to_string()),
                         ),
-                        |_| DetailMessage::Loaded,
+                        DetailMessage::Saved,
                     )
                 }
             }
-            DetailMessage::FieldUpdated(index, value) => match index {
-                0 => self.plant.data.name = value,
-                1 => self.plant.data.description = value,
-                2 => self.plant.data.location = value,
-                3 => self.plant.data.species = value,
-                4 => self.plant.data.plantGroup.id = value.parse().unwrap_or(1),
-                5 => self.additionalCareTips = value,
-                6 => {
-                    self.plant.data.plantGroup.name = value;
-                }
-                7 => {
-                    self.plant.data.description = value;
-                }
-                8 => {
-                    self.careTips = value;
-                }
-                9 => {
-                    self.sensor_border
-                        .insert(Sensortypes::Feuchtigkeit.get_name(), value);
-                }
-                10 => {
-                    self.sensor_border
-                        .insert(Sensortypes::Luftfeuchtigkeit.get_name(), value);
-                }
-                11 => {
-                    self.sensor_border
-                        .insert(Sensortypes::Temperatur.get_name(), value);
-                }
-                12 => {
-                    self.sensor_border
-                        .insert(Sensortypes::Licht.get_name(), value);
+            DetailMessage::PlantForm(field, value) => self.plant_form.set(field, value),
+            DetailMessage::GroupForm(field, value) => self.group_form.set(field, value),
+            DetailMessage::PlantTips(message) => self.plant_tips.update(message),
+            DetailMessage::GroupTips(message) => self.group_tips.update(message),
+            DetailMessage::Ranges(message) => self.ranges.update(message),
+            // A failed deletion keeps the plant open and offers no undo
+            DetailMessage::DeleteDone(Err(e)) => {
+                if let Some((_, data)) = self.deleting.take() {
+                    self.error = format!("Pflanze „{}“ wurde nicht gelöscht: {}", data.name, e);
                 }
-                _ => {}
-            },
-            DetailMessage::DeleteSuccess => {
+            }
+            DetailMessage::DeleteDone(Ok(())) => {
                 self.modal = false;
                 self.message = DetailMessage::Pending;
+                if let Some((plant_id, data)) = self.deleting.take() {
+                    let text = format!("Pflanze „{}“ gelöscht", data.name);
+                    self.undo = Some(UndoToast::new(plant_id.clone(), data, text));
+                    return Command::batch(vec![
+                        self.update(DetailMessage::Load),
+                        undo_timeout(DetailMessage::DismissUndo(plant_id)),
+                    ]);
+                }
+            }
+            DetailMessage::ToggleLogScale => {
+                self.log_scale = !self.log_scale;
+                self.apply_axes();
+            }
+            DetailMessage::SwitchAggregation(aggregation) => {
+                self.aggregation = aggregation;
+                self.apply_axes();
+            }
+            DetailMessage::ExportCsv => {
+                self.export_message = match self.plant.charts.export_csv(&self.plant.data.name) {
+                    Ok(path) => format!("Exportiert nach {}", path.display()),
+                    Err(e) => format!("Export fehlgeschlagen: {}", e),
+                };
+            }
+            DetailMessage::Journal(message) => {
+                let command = self.journal.update(message).map(DetailMessage::Journal);
+                self.apply_axes();
+                return command;
+            }
+            // Handled by the application, which owns the schedule
+            DetailMessage::Tasks(_) => {}
+            DetailMessage::AcceptWatering(minutes) => {
+                if let Some(event) = self
+                    .waterings
+                    .events
+                    .iter()
+                    .find(|event| event.minutes == minutes)
+                {
+                    let command = self
+                        .journal
+                        .submit(event.to_entry())
+                        .map(DetailMessage::Journal);
+                    self.apply_axes();
+                    return command;
+                }
             }
         }
         Command::none()
     }
 }
 
+/// Returns the Ok button of the modals, it is disabled until the form is valid
+fn ok_button<'a>(enabled: bool) -> Button<'a, DetailMessage> {
+    let button = Button::new(
+        Text::new("Ok")
+            .size(TEXT_SIZE)
+            .horizontal_alignment(Horizontal::Center),
+    )
+    .width(Length::Fill);
+    if enabled {
+        button.on_press(DetailMessage::OkButtonPressed)
+    } else {
+        button
+    }
+}
+
 impl Tab for DetailPage {
     type Message = Message;
 
@@ -535,48 +927,11 @@ impl Tab for DetailPage {
                             .size(TEXT_SIZE)
                             .horizontal_alignment(Horizontal::Center),
                         Column::new()
-                            .push(
-                                TextInput::new("Pflanzenname", &self.plant.data.name)
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| DetailMessage::FieldUpdated(0, input)),
-                            )
-                            .spacing(20)
-                            .push(
-                                TextInput::new(
-                                    "Beschreibung der Pflanze",
-                                    &self.plant.data.description,
-                                )
-                                .size(TEXT_SIZE)
-                                .on_input(|input| DetailMessage::FieldUpdated(1, input)),
-                            )
+                            .push(self.plant_form.view(DetailMessage::PlantForm))
                             .spacing(20)
-                            .push(
-                                TextInput::new("Position der Pflanze", &self.plant.data.location)
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| DetailMessage::FieldUpdated(2, input)),
-                            )
-                            .spacing(20)
-                            .spacing(20)
-                            .push(
-                                TextInput::new("Pflanzenspezies", &self.plant.data.species)
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| DetailMessage::FieldUpdated(3, input)),
-                            )
-                            .spacing(20)
-                            .push(
-                                TextInput::new(
-                                    "Pflanzengruppe",
-                                    &self.plant.data.plantGroup.id.to_string(),
-                                )
-                                .size(TEXT_SIZE)
-                                .on_input(|input| DetailMessage::FieldUpdated(4, input)),
-                            )
-                            .spacing(20)
-                            .push(
-                                TextInput::new("Pflegehinweise", &self.additionalCareTips)
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| DetailMessage::FieldUpdated(5, input)),
-                            )
+                            .push(self.plant_tips.view().map(DetailMessage::PlantTips))
+                            .push(Text::new("Pflanzengruppe").size(TEXT_SIZE))
+                            .push(self.group_picker.view().map(DetailMessage::PlantGroup))
                             .spacing(20),
                     )
                     .foot(
@@ -594,17 +949,13 @@ impl Tab for DetailPage {
                                 .width(Length::Fill)
                                 .on_press(DetailMessage::Delete),
                             )
-                            .push(
-                                Button::new(
-                                    Text::new("Ok")
-                                        .size(TEXT_SIZE)
-                                        .horizontal_alignment(Horizontal::Center),
-                                )
-                                .width(Length::Fill)
-                                .on_press(DetailMessage::OkButtonPressed),
-                            ),
+                            .push(ok_button(
+                                self.plant_form.is_valid()
+                                    && self.plant_tips.is_valid()
+                                    && self.group_picker.selected.is_some(),
+                            )),
                     )
-                    .max_width(300.0)
+                    .max_width(500.0)
                     .on_close(DetailMessage::CloseModal)
                     .into()
                 })
@@ -625,75 +976,10 @@ impl Tab for DetailPage {
                             .size(TEXT_SIZE)
                             .horizontal_alignment(Horizontal::Center),
                         Column::new()
-                            .push(
-                                TextInput::new("Gruppennamen", &self.plant.data.plantGroup.name)
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| DetailMessage::FieldUpdated(6, input)),
-                            )
-                            .spacing(20)
-                            .push(
-                                TextInput::new(
-                                    "Beschreibung der Gruppe",
-                                    &self.plant.data.plantGroup.description,
-                                )
-                                .size(TEXT_SIZE)
-                                .on_input(|input| DetailMessage::FieldUpdated(7, input)),
-                            )
                             .spacing(20)
-                            .push(
-                                TextInput::new("Pflegehinweise", &self.careTips)
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| DetailMessage::FieldUpdated(8, input)),
-                            )
-                            .spacing(20)
-                            .push(
-                                Text::new("Die Grenzen werden so eingetragen: max;min")
-                                    .size(TEXT_SIZE),
-                            )
-                            .push(
-                                TextInput::new(
-                                    "Feuchtigkeitsgrenzwerte",
-                                    &self
-                                        .sensor_border
-                                        .get(Sensortypes::Feuchtigkeit.get_name().as_str())
-                                        .unwrap(),
-                                )
-                                .size(TEXT_SIZE)
-                                .on_input(|input| DetailMessage::FieldUpdated(9, input)),
-                            )
-                            .push(
-                                TextInput::new(
-                                    "Luftfeuchtigkeitsgrenzwerte",
-                                    &self
-                                        .sensor_border
-                                        .get(Sensortypes::Luftfeuchtigkeit.get_name().as_str())
-                                        .unwrap(),
-                                )
-                                .size(TEXT_SIZE)
-                                .on_input(|input| DetailMessage::FieldUpdated(10, input)),
-                            )
-                            .push(
-                                TextInput::new(
-                                    "Temperaturgrenzwerte",
-                                    &self
-                                        .sensor_border
-                                        .get(Sensortypes::Temperatur.get_name().as_str())
-                                        .unwrap(),
-                                )
-                                .size(TEXT_SIZE)
-                                .on_input(|input| DetailMessage::FieldUpdated(11, input)),
-                            )
-                            .push(
-                                TextInput::new(
-                                    "Lichtgrenzwerte",
-                                    &self
-                                        .sensor_border
-                                        .get(Sensortypes::Licht.get_name().as_str())
-                                        .unwrap(),
-                                )
-                                .size(TEXT_SIZE)
-                                .on_input(|input| DetailMessage::FieldUpdated(12, input)),
-                            ),
+                            .push(self.group_form.view(DetailMessage::GroupForm))
+                            .push(self.group_tips.view().map(DetailMessage::GroupTips))
+                            .push(self.ranges.view().map(DetailMessage::Ranges)),
                     )
                     .foot(
                         Row::new()
@@ -709,17 +995,13 @@ impl Tab for DetailPage {
                                 .width(Length::Fill)
                                 .on_press(DetailMessage::CloseModal),
                             )
-                            .push(
-                                Button::new(
-                                    Text::new("Ok")
-                                        .size(TEXT_SIZE)
-                                        .horizontal_alignment(Horizontal::Center),
-                                )
-                                .width(Length::Fill)
-                                .on_press(DetailMessage::OkButtonPressed),
-                            ),
+                            .push(ok_button(
+                                self.group_form.is_valid()
+                                    && self.group_tips.is_valid()
+                                    && self.ranges.is_valid(),
+                            )),
                     )
-                    .max_width(300.0)
+                    .max_width(500.0)
                     .on_close(DetailMessage::CloseModal)
                     .into()
                 })
@@ -780,27 +1062,22 @@ impl Tab for DetailPage {
                     detail_column =
                         detail_column.push(Text::new(group_caretip.clone()).size(TEXT_SIZE));
                 }
-                let row: Row<DetailMessage> = Row::new()
-                    .push(
-                        Button::new(Text::new("Feuchtigkeit").size(TEXT_SIZE))
-                            .on_press(DetailMessage::SwitchGraph(Sensortypes::Feuchtigkeit)),
-                    )
-                    .spacing(20)
-                    .push(
-                        Button::new(Text::new("Luftfeuchtigkeit").size(TEXT_SIZE))
-                            .on_press(DetailMessage::SwitchGraph(Sensortypes::Luftfeuchtigkeit)),
-                    )
-                    .spacing(20)
-                    .push(
-                        Button::new(Text::new("Temperatur").size(TEXT_SIZE))
-                            .on_press(DetailMessage::SwitchGraph(Sensortypes::Temperatur)),
-                    )
-                    .spacing(20)
-                    .push(
-                        Button::new(Text::new("Licht").size(TEXT_SIZE))
-                            .on_press(DetailMessage::SwitchGraph(Sensortypes::Licht)),
-                    )
-                    .spacing(20)
+                detail_column = detail_column
+                    .push(self.journal.view().map(DetailMessage::Journal))
+                    .push(self.waterings_view())
+                    .push(self.tasks.view().map(DetailMessage::Tasks));
+                let row: Row<DetailMessage> = Sensortypes::iter()
+                    .fold(Row::new().spacing(20), |row, sensor| {
+                        row.push(
+                            Button::new(Text::new(sensor.to_string()).size(TEXT_SIZE))
+                                .style(if self.active_sensors.contains(&sensor) {
+                                    theme::Button::Primary
+                                } else {
+                                    theme::Button::Secondary
+                                })
+                                .on_press(DetailMessage::ToggleSensor(sensor)),
+                        )
+                    })
                     .push(
                         Button::new(Text::new("Andere Pflanze anzeigen").size(TEXT_SIZE))
                             .on_press(DetailMessage::Load),
@@ -816,76 +1093,103 @@ impl Tab for DetailPage {
                             .on_press(DetailMessage::OpenModalGroup),
                     )
                     .spacing(20);
-                let time_row = Row::new()
+                let export_row = Row::new()
                     .push(
-                        Button::new(Text::new("Letzte 6 Stunden").size(TEXT_SIZE))
-                            .on_press(DetailMessage::SwitchTime(chrono::Duration::hours(6))),
+                        pick_list(
+                            &Aggregation::ALL[..],
+                            Some(self.aggregation),
+                            DetailMessage::SwitchAggregation,
+                        )
+                        .text_size(TEXT_SIZE),
                     )
                     .spacing(20)
                     .push(
-                        Button::new(Text::new("Letzte 12 Stunden").size(TEXT_SIZE))
-                            .on_press(DetailMessage::SwitchTime(chrono::Duration::hours(12))),
+                        Button::new(Text::new("CSV exportieren").size(TEXT_SIZE))
+                            .on_press(DetailMessage::ExportCsv),
                     )
                     .spacing(20)
-                    .push(
-                        Button::new(Text::new("Gesamt").size(TEXT_SIZE))
-                            .on_press(DetailMessage::SwitchTime(chrono::Duration::weeks(100))),
+                    .push(Text::new(&self.export_message).size(TEXT_SIZE))
+                    .align_items(Center);
+                let export_row = if self.primary_sensor() == Sensortypes::Licht {
+                    export_row.spacing(20).push(
+                        Button::new(
+                            Text::new(if self.log_scale {
+                                "Lineare Skala"
+                            } else {
+                                "Logarithmische Skala"
+                            })
+                            .size(TEXT_SIZE),
+                        )
+                        .on_press(DetailMessage::ToggleLogScale),
                     )
-                    .spacing(20);
-                let chart_col = Column::new().push(row).push(container).push(time_row);
+                } else {
+                    export_row
+                };
+                let gauges = self
+                    .gauges
+                    .iter()
+                    .fold(Row::new().spacing(20), |row, gauge| {
+                        row.push(
+                            Canvas::new(gauge.clone())
+                                .width(Length::from(GAUGE_SIZE))
+                                .height(Length::from(GAUGE_SIZE)),
+                        )
+                    });
+                let mut chart_col = Column::new().push(row).push(gauges);
+                if let Some(forecast) = &self.forecast {
+                    let now = to_minutes(&Utc::now());
+                    let badge = Text::new(forecast.badge(now)).size(TEXT_SIZE);
+                    chart_col = chart_col.push(if forecast.hours_until(now) < URGENT_HOURS {
+                        badge.style(URGENT_COLOR)
+                    } else {
+                        badge
+                    });
+                }
+                for issue in &self.sensor_issues {
+                    chart_col = chart_col.push(
+                        Text::new(format!("Sensorproblem – {}", issue.description()))
+                            .size(TEXT_SIZE)
+                            .style(URGENT_COLOR),
+                    );
+                }
+                let chart_col = chart_col
+                    .push(container)
+                    .push(self.range.view().map(DetailMessage::Range))
+                    .push(export_row)
+                    .spacing(10);
                 let row = Row::new()
-                    .push(detail_column)
+                    .push(scrollable::Scrollable::new(detail_column).height(Length::Fill))
                     .push(chart_col)
                     .spacing(20)
                     .align_items(Center);
                 row
             } else {
-                let mut id_name_column: Column<DetailMessage> = Column::new().push(
-                    Row::new()
-                        .push(Text::new("ID").size(TEXT_SIZE))
-                        .push(Text::new("Name").size(TEXT_SIZE))
-                        .spacing(20),
-                );
-                for id in self.id_names.clone() {
-                    let id_name_row = Row::new()
-                        .push(Text::new(id.0.clone()).size(TEXT_SIZE))
-                        .push(Text::new(id.1.clone()).size(TEXT_SIZE))
-                        .spacing(20);
-                    id_name_column = id_name_column.push(id_name_row);
-                }
-                let row = Row::new()
-                    .push(
-                        TextInput::new(
-                            "Trage die ID der Pflanze ein, die du betrachten möchtest",
-                            &self.plant.id,
-                        )
-                        .size(TEXT_SIZE)
-                        .on_input(DetailMessage::Search),
-                    )
-                    .push(
-                        Button::new(Text::new("Anzeigen").size(TEXT_SIZE))
-                            .on_press(DetailMessage::PlantData(self.plant.id.clone())),
-                    )
-                    .spacing(20)
-                    .push(
-                        Button::new(Text::new("Refresh").size(TEXT_SIZE))
-                            .on_press(DetailMessage::Load),
-                    )
-                    .align_items(Center);
-                let id_name_scrollable = scrollable::Scrollable::new(id_name_column);
-                let column = Column::new()
-                    .push(id_name_scrollable)
-                    .align_items(Center)
-                    .push(row);
-                let row = Row::new().push(column).spacing(20).align_items(Center);
-                row
+                Row::new().push(self.table.view().map(DetailMessage::Table))
             };
-            let content: Element<'_, DetailMessage> = Container::new(row)
+            let mut page = Column::new().spacing(10);
+            if !self.error.is_empty() {
+                page = page.push(Text::new(&self.error).size(TEXT_SIZE).style(URGENT_COLOR));
+            }
+            if let Some(toast) = &self.undo {
+                page = page.push(toast.view(
+                    DetailMessage::UndoDelete,
+                    DetailMessage::DismissUndo(toast.key.clone()),
+                ));
+            }
+            let content: Element<'_, DetailMessage> = Container::new(page.push(row))
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .align_x(Horizontal::Center)
                 .align_y(Vertical::Center)
                 .into();
+            let content = match &self.confirm_delete {
+                Some(confirmation) => confirmation.view(
+                    content,
+                    DetailMessage::ConfirmDelete,
+                    DetailMessage::CancelDelete,
+                ),
+                None => content,
+            };
             content.map(Message::Detail)
         }
     }
@@ -893,7 +1197,6 @@ impl Tab for DetailPage {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::collections::HashMap;
 
     #[test]
     fn test_sensortypes_get_name() {
@@ -935,13 +1238,294 @@ mod tests {
     #[test]
     fn test_detail_page_new() {
         let detail_page = DetailPage::new();
-        assert_eq!(detail_page.active_sensor, Sensortypes::Feuchtigkeit);
-        assert_eq!(detail_page.id_names, vec![]);
-        assert_eq!(detail_page.modal, false);
-        assert_eq!(detail_page.modal_is_plant, true);
-        assert_eq!(detail_page.careTips, String::new());
-        assert_eq!(detail_page.sensor_border, HashMap::new());
-        assert_eq!(detail_page.additionalCareTips, String::new());
+        assert_eq!(detail_page.active_sensors, vec![Sensortypes::Feuchtigkeit]);
+        assert!(detail_page.table.rows.is_empty());
+        assert!(!detail_page.modal);
+        assert!(detail_page.modal_is_plant);
+        assert_eq!(detail_page.plant_form, Form::new());
+        assert_eq!(detail_page.group_form, Form::new());
+        assert_eq!(detail_page.plant_tips, CareTipsEditor::new());
+        assert_eq!(detail_page.ranges, RangeEditor::new());
         assert_eq!(detail_page.message, DetailMessage::Pending);
+        assert!(!detail_page.log_scale);
+    }
+
+    #[test]
+    fn test_detail_page_plant_without_group_is_not_sent() {
+        let mut detail_page = DetailPage::new();
+        detail_page.modal = true;
+        detail_page.modal_is_plant = true;
+        let _ = detail_page.update(DetailMessage::OkButtonPressed);
+        assert!(detail_page.modal);
+        assert_eq!(
+            detail_page.group_picker.error,
+            "Bitte eine Gruppe auswählen"
+        );
+    }
+
+    #[test]
+    fn test_detail_page_delete_asks_first() {
+        let mut detail_page = DetailPage::new();
+        detail_page.plant.id = "7".to_string();
+        detail_page.plant.data.name = "Basilikum".to_string();
+        detail_page.plant.data.plantGroup.name = "Kräuter".to_string();
+        detail_page.modal = true;
+        let _ = detail_page.update(DetailMessage::Delete);
+        assert!(!detail_page.modal);
+        let confirmation = detail_page.confirm_delete.clone().unwrap();
+        assert_eq!(confirmation.title, "Pflanze „Basilikum“ löschen?");
+        assert_eq!(
+            confirmation.consequences[0],
+            "Die Pflanze wird aus der Gruppe „Kräuter“ entfernt"
+        );
+        assert_eq!(confirmation.item.0, "7");
+        let _ = detail_page.update(DetailMessage::CancelDelete);
+        assert!(detail_page.confirm_delete.is_none());
+        assert!(detail_page.deleting.is_none());
+    }
+
+    #[test]
+    fn test_detail_page_failed_delete_stays() {
+        let mut detail_page = DetailPage::new();
+        detail_page.plant.id = "7".to_string();
+        detail_page.plant.data.name = "Basilikum".to_string();
+        detail_page.deleting = Some(("7".to_string(), detail_page.plant.data.clone()));
+        let _ = detail_page.update(DetailMessage::DeleteDone(Err(
+            "HTTP status client error (403 Forbidden)".to_string(),
+        )));
+        assert!(detail_page.deleting.is_none());
+        assert!(detail_page.undo.is_none());
+        assert_eq!(detail_page.plant.id, "7");
+        assert_eq!(
+            detail_page.error,
+            "Pflanze „Basilikum“ wurde nicht gelöscht: HTTP status client error (403 Forbidden)"
+        );
+    }
+
+    #[test]
+    fn test_detail_page_failed_restore_and_save() {
+        let mut detail_page = DetailPage::new();
+        let _ = detail_page.update(DetailMessage::Restored(
+            "Basilikum".to_string(),
+            Err("500 Internal Server Error".to_string()),
+        ));
+        assert_eq!(
+            detail_page.error,
+            "Pflanze „Basilikum“ wurde nicht wiederhergestellt: 500 Internal Server Error"
+        );
+        let _ = detail_page.update(DetailMessage::Saved(Err("400 Bad Request".to_string())));
+        assert_eq!(
+            detail_page.error,
+            "Die Änderungen wurden nicht gespeichert: 400 Bad Request"
+        );
+    }
+
+    #[test]
+    fn test_detail_page_gauges_of_the_shown_plant() {
+        let mut detail_page = DetailPage::new();
+        detail_page.plant.id = "3".to_string();
+        let graphs = |id: &str| {
+            let data = GraphData {
+                values: vec![21],
+                timestamps: vec!["2023-05-01T08:00:00Z".to_string()],
+            };
+            vec![(Sensortypes::Temperatur, vec![(data, id.to_string())])]
+        };
+        // The gauges of a plant that was left before they were loaded are dropped
+        let _ = detail_page.update(DetailMessage::GaugesLoaded("4".to_string(), graphs("4")));
+        assert!(detail_page.gauges.is_empty());
+        let _ = detail_page.update(DetailMessage::GaugesLoaded("3".to_string(), graphs("3")));
+        assert_eq!(detail_page.gauges.len(), 1);
+        assert_eq!(detail_page.gauges[0].value, Some(21));
+    }
+
+    #[test]
+    fn test_detail_page_range_preview_of_the_shown_plant() {
+        let mut detail_page = DetailPage::new();
+        detail_page.plant.id = "3".to_string();
+        let graphs = || {
+            let data = GraphData {
+                values: vec![4000, 4200],
+                timestamps: vec![
+                    "2023-05-01T08:20:00Z".to_string(),
+                    "2023-05-01T08:10:00Z".to_string(),
+                ],
+            };
+            vec![(Sensortypes::Feuchtigkeit, vec![(data, "3".to_string())])]
+        };
+        let preview = |detail_page: &DetailPage| {
+            detail_page
+                .ranges
+                .inputs
+                .iter()
+                .find(|input| input.sensor == Sensortypes::Feuchtigkeit)
+                .unwrap()
+                .preview
+                .len()
+        };
+        let _ = detail_page.update(DetailMessage::PreviewLoaded("4".to_string(), graphs()));
+        assert_eq!(preview(&detail_page), 0);
+        let _ = detail_page.update(DetailMessage::PreviewLoaded("3".to_string(), graphs()));
+        assert_eq!(preview(&detail_page), 2);
+    }
+
+    #[test]
+    fn test_detail_page_axis_label() {
+        let mut detail_page = DetailPage::new();
+        assert_eq!(
+            detail_page.axis_label(Sensortypes::Temperatur),
+            "Temperatur [celcius]"
+        );
+        detail_page.plant.data.plantGroup.sensorRanges[3]
+            .sensorType
+            .unit = String::from("lx");
+        assert_eq!(detail_page.axis_label(Sensortypes::Licht), "Licht [lx]");
+    }
+
+    #[test]
+    fn test_detail_page_toggle_sensor() {
+        let mut detail_page = DetailPage::new();
+        detail_page.toggle_sensor(Sensortypes::Temperatur);
+        assert_eq!(
+            detail_page.active_sensors,
+            vec![Sensortypes::Feuchtigkeit, Sensortypes::Temperatur]
+        );
+        detail_page.toggle_sensor(Sensortypes::Feuchtigkeit);
+        assert_eq!(detail_page.active_sensors, vec![Sensortypes::Temperatur]);
+        detail_page.toggle_sensor(Sensortypes::Temperatur);
+        assert_eq!(detail_page.active_sensors, vec![Sensortypes::Temperatur]);
+    }
+
+    #[test]
+    fn test_detail_page_secondary_axis() {
+        let mut detail_page = DetailPage::new();
+        detail_page.active_sensors = vec![Sensortypes::Feuchtigkeit, Sensortypes::Temperatur];
+        assert!(!detail_page.is_secondary(Sensortypes::Luftfeuchtigkeit));
+        assert!(detail_page.is_secondary(Sensortypes::Temperatur));
+        detail_page.apply_axes();
+        assert_eq!(
+            detail_page.plant.charts.secondary_label,
+            "Temperatur [celcius]"
+        );
+
+        // Light has a third unit and takes the secondary axis from the temperature
+        detail_page.toggle_sensor(Sensortypes::Licht);
+        assert_eq!(
+            detail_page.active_sensors,
+            vec![Sensortypes::Feuchtigkeit, Sensortypes::Licht]
+        );
+        detail_page.toggle_sensor(Sensortypes::Luftfeuchtigkeit);
+        assert_eq!(
+            detail_page.active_sensors,
+            vec![
+                Sensortypes::Feuchtigkeit,
+                Sensortypes::Licht,
+                Sensortypes::Luftfeuchtigkeit
+            ]
+        );
+        detail_page.apply_axes();
+        assert_eq!(detail_page.plant.charts.secondary_label, "Licht [lux]");
+    }
+
+    #[test]
+    fn test_detail_page_toggle_log_scale() {
+        let mut detail_page = DetailPage::new();
+        detail_page.active_sensors = vec![Sensortypes::Licht];
+        let _ = detail_page.update(DetailMessage::ToggleLogScale);
+        assert!(detail_page.plant.charts.log_scale);
+        detail_page.active_sensors = vec![Sensortypes::Temperatur, Sensortypes::Licht];
+        let _ = detail_page.update(DetailMessage::ToggleLogScale);
+        let _ = detail_page.update(DetailMessage::ToggleLogScale);
+        assert!(!detail_page.plant.charts.log_scale);
+    }
+
+    #[test]
+    fn test_detail_page_switch_aggregation() {
+        let mut detail_page = DetailPage::new();
+        let _ = detail_page.update(DetailMessage::SwitchAggregation(Aggregation::Taeglich));
+        assert_eq!(detail_page.aggregation, Aggregation::Taeglich);
+        assert_eq!(detail_page.plant.charts.aggregation, Aggregation::Taeglich);
+    }
+
+    #[test]
+    fn test_detail_page_journal_markers() {
+        let path = std::env::temp_dir()
+            .join("plantbuddy_detail_test")
+            .join("journal.json");
+        let _ = std::fs::remove_file(&path);
+        let mut detail_page = DetailPage::new();
+        detail_page.journal = CareJournal::with_path(path);
+        let _ = detail_page.update(DetailMessage::Journal(JournalMessage::Load(
+            "7".to_string(),
+        )));
+        let _ = detail_page.update(DetailMessage::Journal(JournalMessage::AmountChanged(
+            "300".to_string(),
+        )));
+        let _ = detail_page.update(DetailMessage::Journal(JournalMessage::Add));
+        assert_eq!(detail_page.journal.entries.len(), 1);
+        assert_eq!(detail_page.plant.charts.markers.len(), 1);
+        assert_eq!(detail_page.plant.charts.markers[0].label, "Gießen");
+    }
+
+    #[test]
+    fn test_detail_page_forecast_projection() {
+        let mut detail_page = DetailPage::new();
+        detail_page.forecast = Forecast::fit(
+            &[GraphData {
+                values: vec![5000, 4900, 4800, 4700],
+                timestamps: vec![
+                    "2023-05-01T08:00:00Z".to_string(),
+                    "2023-05-01T09:00:00Z".to_string(),
+                    "2023-05-01T10:00:00Z".to_string(),
+                    "2023-05-01T11:00:00Z".to_string(),
+                ],
+            }],
+            3000,
+        );
+        let _ = detail_page.update(DetailMessage::SwitchAggregation(Aggregation::Keine));
+        assert_eq!(detail_page.plant.charts.projection.len(), 2);
+        assert_eq!(detail_page.plant.charts.projection[1].1, 3000);
+        detail_page.active_sensors = vec![Sensortypes::Temperatur];
+        let _ = detail_page.update(DetailMessage::SwitchAggregation(Aggregation::Keine));
+        assert!(detail_page.plant.charts.projection.is_empty());
+    }
+
+    #[test]
+    fn test_detail_page_accept_watering() {
+        let path = std::env::temp_dir()
+            .join("plantbuddy_detail_watering_test")
+            .join("journal.json");
+        let _ = std::fs::remove_file(&path);
+        let mut detail_page = DetailPage::new();
+        detail_page.journal = CareJournal::with_path(path);
+        let _ = detail_page.update(DetailMessage::Journal(JournalMessage::Load(
+            "7".to_string(),
+        )));
+        detail_page.waterings = Waterings::from_graph_data(&[GraphData {
+            values: vec![3000, 2900, 5500],
+            timestamps: vec![
+                "2023-05-02T07:00:00Z".to_string(),
+                "2023-05-02T08:00:00Z".to_string(),
+                "2023-05-02T08:30:00Z".to_string(),
+            ],
+        }]);
+        let minutes = detail_page.waterings.events[0].minutes;
+        let _ = detail_page.update(DetailMessage::AcceptWatering(minutes));
+        assert_eq!(detail_page.journal.entries.len(), 1);
+        assert_eq!(detail_page.plant.charts.markers.len(), 1);
+        assert!(detail_page
+            .waterings
+            .suggestions(&detail_page.journal.entries)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_detail_page_range_input_does_not_reload() {
+        let mut detail_page = DetailPage::new();
+        let _ = detail_page.update(DetailMessage::Range(RangeMessage::FromChanged(
+            "01.05.2023 08:00".to_string(),
+        )));
+        assert_eq!(detail_page.range.from_input, "01.05.2023 08:00");
+        assert_eq!(detail_page.range.preset, Some(RangePreset::Gesamt));
     }
 }
@@ -0,0 +1,144 @@
+/// Reduces a series to `threshold` points with the Largest-Triangle-Three-Buckets algorithm
+///
+/// The first and last point are always kept. The points in between are split into buckets and
+/// from every bucket the point forming the largest triangle with its neighbours is selected,
+/// so peaks and drops of the series survive the downsampling.
+pub fn lttb(points: &[(i32, i32)], threshold: usize) -> Vec<(i32, i32)> {
+    if threshold >= points.len() || threshold < 3 {
+        return points.to_vec();
+    }
+    let mut sampled = Vec::with_capacity(threshold);
+    let bucket_size = (points.len() - 2) as f64 / (threshold - 2) as f64;
+    let mut selected = 0;
+    sampled.push(points[0]);
+    for bucket in 0..threshold - 2 {
+        // The average of the next bucket is the third corner of the triangle
+        let next_start = ((bucket + 1) as f64 * bucket_size) as usize + 1;
+        let next_end = (((bucket + 2) as f64 * bucket_size) as usize + 1).min(points.len());
+        let next = &points[next_start..next_end.max(next_start + 1).min(points.len())];
+        let avg_x = next.iter().map(|p| p.0 as f64).sum::<f64>() / next.len() as f64;
+        let avg_y = next.iter().map(|p| p.1 as f64).sum::<f64>() / next.len() as f64;
+
+        let start = (bucket as f64 * bucket_size) as usize + 1;
+        let end = ((bucket + 1) as f64 * bucket_size) as usize + 1;
+        let (ax, ay) = (points[selected].0 as f64, points[selected].1 as f64);
+        let mut max_area = -1.0;
+        let mut candidate = start;
+        for (index, point) in points.iter().enumerate().take(end).skip(start) {
+            let area =
+                ((ax - avg_x) * (point.1 as f64 - ay) - (ax - point.0 as f64) * (avg_y - ay)).abs();
+            if area > max_area {
+                max_area = area;
+                candidate = index;
+            }
+        }
+        sampled.push(points[candidate]);
+        selected = candidate;
+    }
+    sampled.push(points[points.len() - 1]);
+    sampled
+}
+
+/// Returns the points of a series that lie inside the window
+///
+/// The closest point on each side of the window is kept as well, so the line still reaches the
+/// edges of the chart when zoomed in.
+pub fn visible_points(x: &[i32], y: &[i32], window: (i32, i32)) -> Vec<(i32, i32)> {
+    let points: Vec<(i32, i32)> = x.iter().copied().zip(y.iter().copied()).collect();
+    let first = points
+        .iter()
+        .position(|point| point.0 >= window.0)
+        .unwrap_or(points.len());
+    let last = points
+        .iter()
+        .rposition(|point| point.0 <= window.1)
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    let start = first.saturating_sub(1);
+    let end = (last + 1).min(points.len());
+    if start >= end {
+        return vec![];
+    }
+    points[start..end].to_vec()
+}
+
+/// Zooms the window in or out around the given position
+///
+/// `position` is the relative position of the cursor inside the chart between 0 and 1.
+/// Returns `None` if the zoomed window covers the full range again.
+pub fn zoom_window(
+    full: (i32, i32),
+    current: (i32, i32),
+    position: f32,
+    zoom_in: bool,
+) -> Option<(i32, i32)> {
+    let span = (current.1 - current.0) as f32;
+    let new_span = if zoom_in { span * 0.8 } else { span * 1.25 };
+    // Do not zoom further in than ten minutes
+    let new_span = new_span.max(10.0);
+    if new_span >= (full.1 - full.0) as f32 {
+        return None;
+    }
+    let center = current.0 as f32 + position.clamp(0.0, 1.0) * span;
+    let mut start = (center - position.clamp(0.0, 1.0) * new_span) as i32;
+    let mut end = start + new_span as i32;
+    if start < full.0 {
+        end += full.0 - start;
+        start = full.0;
+    }
+    if end > full.1 {
+        start -= end - full.1;
+        end = full.1;
+    }
+    Some((start.max(full.0), end))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lttb_keeps_small_series() {
+        let points = vec![(0, 1), (1, 2), (2, 3)];
+        assert_eq!(lttb(&points, 10), points);
+    }
+
+    #[test]
+    fn test_lttb_threshold() {
+        let points: Vec<(i32, i32)> = (0..1000).map(|x| (x, x % 7)).collect();
+        let sampled = lttb(&points, 100);
+        assert_eq!(sampled.len(), 100);
+        assert_eq!(sampled[0], (0, 0));
+        assert_eq!(sampled[99], (999, 999 % 7));
+    }
+
+    #[test]
+    fn test_lttb_keeps_peak() {
+        let mut points: Vec<(i32, i32)> = (0..500).map(|x| (x, 10)).collect();
+        points[250].1 = 90;
+        let sampled = lttb(&points, 20);
+        assert!(sampled.contains(&(250, 90)));
+    }
+
+    #[test]
+    fn test_visible_points() {
+        let x = vec![0, 10, 20, 30, 40];
+        let y = vec![1, 2, 3, 4, 5];
+        assert_eq!(
+            visible_points(&x, &y, (15, 25)),
+            vec![(10, 2), (20, 3), (30, 4)]
+        );
+        assert_eq!(visible_points(&x, &y, (0, 40)).len(), 5);
+        assert!(visible_points(&x, &y, (50, 60)).len() <= 1);
+    }
+
+    #[test]
+    fn test_zoom_window() {
+        let full = (0, 1000);
+        let zoomed = zoom_window(full, full, 0.5, true).unwrap();
+        assert_eq!(zoomed, (100, 900));
+        assert_eq!(zoom_window(full, zoomed, 0.5, false), None);
+        let left = zoom_window(full, full, 0.0, true).unwrap();
+        assert_eq!(left, (0, 800));
+    }
+}
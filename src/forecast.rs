@@ -0,0 +1,288 @@
+use crate::graphs::graph_points;
+use crate::requests::{GraphData, PlantReadings};
+use crate::watering::Waterings;
+use itertools::Itertools;
+
+/// The minutes of readings before the latest one the drying curve is fitted to
+pub const FIT_MINUTES: i32 = 48 * 60;
+/// The soil moisture is forecast at most this many minutes into the future
+pub const MAX_HORIZON: i32 = 7 * 24 * 60;
+/// The minimal number of readings needed for a forecast
+const MIN_POINTS: usize = 4;
+/// The name of the soil moisture sensor on the server
+const SOIL_MOISTURE: &str = "soil-moisture";
+
+/// The forecast of the soil moisture, fitted as a line to the drying curve
+///
+/// The values are in the unit of the readings of the server, like the sensor ranges.
+///
+/// Fields:
+/// * `slope` - The change of the soil moisture per minute, always negative
+/// * `last` - The time and fitted value of the latest reading
+/// * `minimum` - The minimum of the group's sensor range
+/// * `crossing` - The time the soil moisture falls below the minimum in minutes since the unix epoch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Forecast {
+    pub slope: f64,
+    pub last: (i32, f64),
+    pub minimum: f64,
+    pub crossing: i32,
+}
+
+impl Forecast {
+    /// Fits the drying curve since the last watering and estimates when it crosses the minimum
+    ///
+    /// Returns `None` if there are too few readings, the soil is not drying out or it stays above
+    /// the minimum for longer than `MAX_HORIZON` minutes.
+    pub fn fit(graph_data: &[GraphData], minimum: i32) -> Option<Forecast> {
+        let points = graph_points(graph_data)
+            .into_iter()
+            .map(|(minutes, value)| (minutes, value as f64))
+            .collect_vec();
+        let latest = points.last()?.0;
+        // The drying curve starts at the highest reading after the last watering
+        let watered = Waterings::from_graph_data(graph_data)
+            .last()
+            .map(|event| event.minutes)
+            .unwrap_or(i32::MIN);
+        let after_watering = points
+            .iter()
+            .position(|(minutes, _)| *minutes >= watered)
+            .unwrap_or(0);
+        let peak = points[after_watering..]
+            .iter()
+            .position_max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|index| index + after_watering)
+            .unwrap_or(0);
+        let curve = points[peak..]
+            .iter()
+            .filter(|(minutes, _)| latest - minutes <= FIT_MINUTES)
+            .copied()
+            .collect_vec();
+        if curve.len() < MIN_POINTS {
+            return None;
+        }
+
+        let (slope, intercept) = fit_line(&curve)?;
+        if slope >= 0.0 {
+            return None;
+        }
+        let value = intercept + slope * latest as f64;
+        let minimum = minimum as f64;
+        let crossing = if value <= minimum {
+            latest
+        } else {
+            // A slope close to zero would put the crossing out of the range of the minutes
+            let steps = ((minimum - value) / slope).round();
+            if steps > MAX_HORIZON as f64 {
+                return None;
+            }
+            latest + steps as i32
+        };
+        Some(Forecast {
+            slope,
+            last: (latest, value),
+            minimum,
+            crossing,
+        })
+    }
+
+    /// Returns if the latest reading is recent enough to forecast from it
+    pub fn is_current(&self, now: i32) -> bool {
+        now - self.last.0 <= FIT_MINUTES
+    }
+
+    /// Returns the hours until the soil moisture falls below the minimum, zero if it already is
+    pub fn hours_until(&self, now: i32) -> f64 {
+        ((self.crossing - now) as f64 / 60.0).max(0.0)
+    }
+
+    /// Returns the text of the badge, e.g. "Braucht Wasser in ~5 Stunden"
+    pub fn badge(&self, now: i32) -> String {
+        let hours = self.hours_until(now).round() as i64;
+        match hours {
+            0 => "Braucht jetzt Wasser".to_string(),
+            1 => "Braucht Wasser in ~1 Stunde".to_string(),
+            hours if hours < 48 => format!("Braucht Wasser in ~{} Stunden", hours),
+            hours => format!("Braucht Wasser in ~{} Tagen", (hours as f64 / 24.0).round()),
+        }
+    }
+
+    /// Returns the projected line from the latest reading to the crossing of the minimum
+    pub fn projection(&self) -> Vec<(i32, i32)> {
+        let (start, value) = self.last;
+        let end = self.crossing;
+        let end_value = value + self.slope * (end - start) as f64;
+        vec![
+            (start, value.round() as i32),
+            (end, end_value.round() as i32),
+        ]
+    }
+}
+
+/// Returns the slope and intercept of the least squares line through the points
+fn fit_line(points: &[(i32, f64)]) -> Option<(f64, f64)> {
+    let count = points.len() as f64;
+    // The times are shifted to the first point to keep the sums precise
+    let origin = points.first()?.0;
+    let mean_x = points.iter().map(|(x, _)| (x - origin) as f64).sum::<f64>() / count;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / count;
+    let (covariance, variance) =
+        points
+            .iter()
+            .fold((0.0, 0.0), |(covariance, variance), (x, y)| {
+                let dx = (x - origin) as f64 - mean_x;
+                (covariance + dx * (y - mean_y), variance + dx * dx)
+            });
+    if variance == 0.0 {
+        return None;
+    }
+    let slope = covariance / variance;
+    let intercept = mean_y - slope * (mean_x + origin as f64);
+    Some((slope, intercept))
+}
+
+/// Returns the forecast of a plant, `None` if its group has no soil moisture range
+pub fn forecast_plant(plant: &PlantReadings) -> Option<Forecast> {
+    let (min, _) = plant
+        .group
+        .sensorRanges
+        .iter()
+        .find(|range| range.sensorType.name == SOIL_MOISTURE)?
+        .bounds()?;
+    let (_, graph_data) = plant
+        .readings
+        .iter()
+        .find(|(sensor, _)| sensor == SOIL_MOISTURE)?;
+    Forecast::fit(std::slice::from_ref(graph_data), min)
+}
+
+/// Returns the plants that need water soonest with their forecast, the most urgent first
+pub fn most_urgent(plants: &[PlantReadings], count: usize) -> Vec<(String, String, Forecast)> {
+    plants
+        .iter()
+        .filter_map(|plant| {
+            forecast_plant(plant).map(|forecast| (plant.id.clone(), plant.name.clone(), forecast))
+        })
+        .sorted_by_key(|(_, _, forecast)| forecast.crossing)
+        .take(count)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requests::{PlantGroupMetadata, SensorRange, SensorType};
+    use crate::timezone::{from_minutes, to_minutes};
+    use chrono::{SecondsFormat, TimeZone, Utc};
+
+    /// Returns hourly readings starting at the given hour of 2023-05-01
+    fn readings(start_hour: i64, values: &[i32]) -> GraphData {
+        let start = Utc.with_ymd_and_hms(2023, 5, 1, 0, 0, 0).unwrap();
+        GraphData {
+            values: values.to_vec(),
+            timestamps: (0..values.len() as i64)
+                .map(|hour| {
+                    (start + chrono::Duration::hours(start_hour + hour))
+                        .to_rfc3339_opts(SecondsFormat::Secs, true)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_fit_line() {
+        let points = vec![(0, 10.0), (60, 8.0), (120, 6.0)];
+        let (slope, intercept) = fit_line(&points).unwrap();
+        assert!((slope + 2.0 / 60.0).abs() < 1e-9);
+        assert!((intercept - 10.0).abs() < 1e-9);
+        assert_eq!(fit_line(&[(5, 1.0), (5, 2.0)]), None);
+    }
+
+    #[test]
+    fn test_forecast_after_watering() {
+        // Dries out, is watered after 4 hours and dries out by 100 per hour afterwards
+        let data = readings(0, &[4000, 3800, 3600, 3400, 6000, 5900, 5800, 5700, 5600]);
+        let forecast = Forecast::fit(&[data], 3000).unwrap();
+        assert!((forecast.slope + 100.0 / 60.0).abs() < 1e-9);
+        let latest = to_minutes(&Utc.with_ymd_and_hms(2023, 5, 1, 8, 0, 0).unwrap());
+        assert_eq!(forecast.last.0, latest);
+        assert_eq!(forecast.crossing, latest + 26 * 60);
+        assert_eq!(forecast.badge(latest), "Braucht Wasser in ~26 Stunden");
+        assert_eq!(
+            forecast.badge(latest + 25 * 60),
+            "Braucht Wasser in ~1 Stunde"
+        );
+        assert_eq!(forecast.badge(latest + 30 * 60), "Braucht jetzt Wasser");
+        assert_eq!(
+            forecast.projection(),
+            vec![(latest, 5600), (latest + 26 * 60, 3000)]
+        );
+        assert_eq!(
+            from_minutes(forecast.crossing).to_rfc3339(),
+            "2023-05-02T10:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_no_forecast_without_drying() {
+        assert_eq!(
+            Forecast::fit(&[readings(0, &[4000, 4100, 4200, 4300])], 3000),
+            None
+        );
+        assert_eq!(Forecast::fit(&[readings(0, &[4000, 3900])], 3000), None);
+        let slow = Forecast::fit(&[readings(0, &[5000, 4999, 4998, 4997])], 4925).unwrap();
+        assert_eq!(slow.projection()[1], (slow.last.0 + 72 * 60, 4925));
+        assert_eq!(slow.badge(slow.last.0), "Braucht Wasser in ~3 Tagen");
+        assert!(slow.is_current(slow.last.0 + FIT_MINUTES));
+        assert!(!slow.is_current(slow.last.0 + FIT_MINUTES + 1));
+    }
+
+    #[test]
+    fn test_no_forecast_beyond_horizon() {
+        // A single higher reading before two days of constant readings gives a slope close to zero
+        let mut values = vec![4000; 49];
+        values[0] = 4001;
+        assert_eq!(Forecast::fit(&[readings(0, &values)], 0), None);
+        assert_eq!(Forecast::fit(&[readings(0, &values)], i32::MIN), None);
+        // One day less than the horizon is still forecast
+        let week = Forecast::fit(&[readings(0, &[5000, 4999, 4998, 4997])], 4997 - 6 * 24);
+        assert_eq!(week.unwrap().crossing - week.unwrap().last.0, 6 * 24 * 60);
+        assert_eq!(
+            Forecast::fit(&[readings(0, &[5000, 4999, 4998, 4997])], 4997 - 8 * 24),
+            None
+        );
+    }
+
+    #[test]
+    fn test_most_urgent() {
+        let plant = |id: &str, values: &[i32]| PlantReadings {
+            id: id.to_string(),
+            name: format!("Pflanze {}", id),
+            group: PlantGroupMetadata {
+                sensorRanges: vec![SensorRange {
+                    sensorType: SensorType {
+                        name: SOIL_MOISTURE.to_string(),
+                        unit: "%".to_string(),
+                    },
+                    min: 3000,
+                    max: 6000,
+                }],
+                ..PlantGroupMetadata::default()
+            },
+            readings: vec![(SOIL_MOISTURE.to_string(), readings(0, values))],
+            ..PlantReadings::default()
+        };
+        let plants = vec![
+            plant("1", &[5000, 4900, 4800, 4700]),
+            plant("2", &[4000, 3800, 3600, 3400]),
+            plant("3", &[4000, 4000, 4100, 4100]),
+        ];
+        let urgent = most_urgent(&plants, 5);
+        assert_eq!(
+            urgent.iter().map(|(id, _, _)| id.as_str()).collect_vec(),
+            vec!["2", "1"]
+        );
+        assert_eq!(most_urgent(&plants, 1).len(), 1);
+    }
+}
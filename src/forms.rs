@@ -0,0 +1,273 @@
+use crate::detail::URGENT_COLOR;
+use crate::requests::{PlantGroupMetadata, PlantMetadata};
+use crate::TEXT_SIZE;
+use iced::widget::{Column, Text, TextInput};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// The maximal number of characters of a text field
+pub const MAX_LENGTH: usize = 200;
+
+/// A field of a form with its label and validation rule
+pub trait FormField: Copy + Eq + Hash + Debug {
+    /// Returns all fields in the order they are displayed
+    fn all() -> Vec<Self>;
+
+    /// Returns the placeholder of the input
+    fn label(&self) -> &'static str;
+
+    /// Returns the explanation shown above the input
+    fn hint(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Checks the value of the field, returning the error shown under the input
+    fn validate(&self, value: &str) -> Result<(), String>;
+}
+
+/// The values of a form, identified by their fields
+///
+/// Errors are only shown for fields the user changed, so an empty form is not covered in errors.
+///
+/// Fields:
+/// * `values` - The inputs of the fields
+/// * `touched` - The fields that were changed by the user
+#[derive(Debug, Clone, PartialEq)]
+pub struct Form<F: FormField> {
+    values: HashMap<F, String>,
+    touched: HashSet<F>,
+}
+
+impl<F: FormField> Default for Form<F> {
+    fn default() -> Self {
+        Form {
+            values: HashMap::new(),
+            touched: HashSet::new(),
+        }
+    }
+}
+
+impl<F: FormField> Form<F> {
+    /// Creates an empty form
+    pub fn new() -> Self {
+        Form::default()
+    }
+
+    /// Returns the input of the field
+    pub fn value(&self, field: F) -> &str {
+        self.values.get(&field).map_or("", String::as_str)
+    }
+
+    /// Sets the input of the field without showing its error
+    pub fn fill(&mut self, field: F, value: String) {
+        self.values.insert(field, value);
+    }
+
+    /// Handles the input of the user
+    pub fn set(&mut self, field: F, value: String) {
+        self.fill(field, value);
+        self.touched.insert(field);
+    }
+
+    /// Returns the error of the field, if its input is invalid
+    pub fn error(&self, field: F) -> Option<String> {
+        field.validate(self.value(field)).err()
+    }
+
+    /// Returns if all fields are valid
+    pub fn is_valid(&self) -> bool {
+        F::all().iter().all(|field| self.error(*field).is_none())
+    }
+
+    /// Shows the errors of all fields, e.g. after a rejected submit
+    pub fn touch_all(&mut self) {
+        self.touched.extend(F::all());
+    }
+
+    /// Returns the inputs of all fields with their hints and the errors of the changed fields
+    pub fn view<'a, M: Clone + 'a>(&'a self, on_input: fn(F, String) -> M) -> Column<'a, M> {
+        F::all()
+            .into_iter()
+            .fold(Column::new().spacing(10), |mut column, field| {
+                if let Some(hint) = field.hint() {
+                    column = column.push(Text::new(hint).size(TEXT_SIZE));
+                }
+                column = column.push(
+                    TextInput::new(field.label(), self.value(field))
+                        .size(TEXT_SIZE)
+                        .on_input(move |input| on_input(field, input)),
+                );
+                match self.error(field) {
+                    Some(error) if self.touched.contains(&field) => {
+                        column.push(Text::new(error).size(TEXT_SIZE - 8).style(URGENT_COLOR))
+                    }
+                    _ => column,
+                }
+            })
+    }
+}
+
+/// Checks that a text is not too long
+pub fn check_length(value: &str) -> Result<(), String> {
+    if value.chars().count() > MAX_LENGTH {
+        Err(format!("Höchstens {} Zeichen", MAX_LENGTH))
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks that a name is given and not too long
+fn check_name(value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        return Err("Bitte einen Namen eingeben".to_string());
+    }
+    check_length(value)
+}
+
+/// Contains the fields of the plant form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlantField {
+    Name,
+    Description,
+    Location,
+    Species,
+}
+
+impl FormField for PlantField {
+    fn all() -> Vec<Self> {
+        vec![
+            PlantField::Name,
+            PlantField::Description,
+            PlantField::Location,
+            PlantField::Species,
+        ]
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            PlantField::Name => "Pflanzenname",
+            PlantField::Description => "Beschreibung der Pflanze",
+            PlantField::Location => "Position der Pflanze",
+            PlantField::Species => "Pflanzenspezies",
+        }
+    }
+
+    fn validate(&self, value: &str) -> Result<(), String> {
+        match self {
+            PlantField::Name => check_name(value),
+            _ => check_length(value),
+        }
+    }
+}
+
+impl Form<PlantField> {
+    /// Creates the form filled with the metadata of a plant
+    pub fn from_plant(plant: &PlantMetadata) -> Self {
+        let mut form = Form::new();
+        form.fill(PlantField::Name, plant.name.clone());
+        form.fill(PlantField::Description, plant.description.clone());
+        form.fill(PlantField::Location, plant.location.clone());
+        form.fill(PlantField::Species, plant.species.clone());
+        form
+    }
+
+    /// Writes the inputs into the metadata of a plant
+    pub fn apply_to(&self, plant: &mut PlantMetadata) {
+        plant.name = self.value(PlantField::Name).trim().to_string();
+        plant.description = self.value(PlantField::Description).to_string();
+        plant.location = self.value(PlantField::Location).to_string();
+        plant.species = self.value(PlantField::Species).to_string();
+    }
+}
+
+/// Contains the fields of the group form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GroupField {
+    Name,
+    Description,
+}
+
+impl FormField for GroupField {
+    fn all() -> Vec<Self> {
+        vec![GroupField::Name, GroupField::Description]
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            GroupField::Name => "Gruppenname",
+            GroupField::Description => "Beschreibung der Gruppe",
+        }
+    }
+
+    fn validate(&self, value: &str) -> Result<(), String> {
+        match self {
+            GroupField::Name => check_name(value),
+            _ => check_length(value),
+        }
+    }
+}
+
+impl Form<GroupField> {
+    /// Creates the form filled with the metadata of a group, the ranges and care tips have their own editors
+    pub fn from_group(group: &PlantGroupMetadata) -> Self {
+        let mut form = Form::new();
+        form.fill(GroupField::Name, group.name.clone());
+        form.fill(GroupField::Description, group.description.clone());
+        form
+    }
+
+    /// Writes the inputs into the metadata of a group
+    pub fn apply_to(&self, group: &mut PlantGroupMetadata) {
+        group.name = self.value(GroupField::Name).trim().to_string();
+        group.description = self.value(GroupField::Description).to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plant_form() {
+        let mut form: Form<PlantField> = Form::new();
+        assert!(!form.is_valid());
+        assert_eq!(
+            form.error(PlantField::Name),
+            Some("Bitte einen Namen eingeben".to_string())
+        );
+        form.set(PlantField::Name, " Basilikum ".to_string());
+        assert!(form.is_valid());
+        form.set(PlantField::Location, "x".repeat(MAX_LENGTH + 1));
+        assert!(!form.is_valid());
+        form.set(PlantField::Location, "Küche".to_string());
+
+        let mut plant = PlantMetadata::default();
+        form.apply_to(&mut plant);
+        assert_eq!(plant.name, "Basilikum");
+        assert_eq!(plant.location, "Küche");
+        assert_eq!(
+            Form::from_plant(&plant).value(PlantField::Location),
+            "Küche"
+        );
+    }
+
+    #[test]
+    fn test_group_form() {
+        let mut group = PlantGroupMetadata {
+            name: "Kräuter".to_string(),
+            description: "Küchenfenster".to_string(),
+            ..PlantGroupMetadata::default()
+        };
+        let mut form = Form::from_group(&group);
+        assert!(form.is_valid());
+        assert_eq!(form.value(GroupField::Description), "Küchenfenster");
+
+        form.set(GroupField::Name, "  ".to_string());
+        assert!(!form.is_valid());
+        form.set(GroupField::Name, "Kakteen".to_string());
+        form.apply_to(&mut group);
+        assert_eq!(group.name, "Kakteen");
+        assert_eq!(group.description, "Küchenfenster");
+    }
+}
@@ -0,0 +1,288 @@
+use crate::detail::Sensortypes;
+use crate::graphs::{format_value, graph_points};
+use crate::requests::GraphData;
+use iced::alignment::{Horizontal, Vertical};
+use iced::widget::canvas::{self, path::Arc, Cursor, Frame, Geometry, Path, Stroke};
+use iced::{Color, Point, Rectangle, Theme};
+use itertools::Itertools;
+use std::f32::consts::PI;
+
+/// The angle of the lowest value of the dial, measured clockwise from the positive x axis
+const START_ANGLE: f32 = 0.75 * PI;
+/// The angle covered by the dial
+const SWEEP: f32 = 1.5 * PI;
+/// The trend is calculated over this many minutes before the latest reading
+pub const TREND_MINUTES: i32 = 60;
+/// Changes smaller than this part of the scale count as steady
+const STEADY_PART: f64 = 0.01;
+
+/// Contains the directions of the readings over the last hour
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    /// The value is rising
+    Steigend,
+    /// The value is falling
+    Fallend,
+    /// The value is steady or there are too few readings
+    Gleichbleibend,
+}
+
+impl Trend {
+    /// Returns the arrow of the trend
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            Trend::Steigend => "↑",
+            Trend::Fallend => "↓",
+            Trend::Gleichbleibend => "→",
+        }
+    }
+}
+
+/// A dial showing the latest reading of a sensor against the range of the group
+///
+/// Fields:
+/// * `sensor` - The sensor
+/// * `value` - The latest reading in the unit of the server, `None` if there is no reading
+/// * `range` - The min and max of the group's sensor range
+/// * `trend` - The direction of the readings over the last hour
+/// * `scale` - The lowest and highest value of the dial
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gauge {
+    pub sensor: Sensortypes,
+    pub value: Option<i32>,
+    pub range: Option<(i32, i32)>,
+    pub trend: Trend,
+    pub scale: (f64, f64),
+}
+
+impl Gauge {
+    /// Creates the gauge from the recent readings of the sensor and the bounds of its range, see
+    /// `SensorRange::bounds`
+    pub fn new(sensor: Sensortypes, graph_data: &[GraphData], range: Option<(i32, i32)>) -> Self {
+        let points = graph_points(graph_data);
+        let value = points.last().map(|point| point.1);
+        let scale = scale(value, range);
+        Gauge {
+            sensor,
+            value,
+            range,
+            trend: trend(&points, scale),
+            scale,
+        }
+    }
+
+    /// Returns the angle of the value on the dial, values outside the scale stay at its ends
+    pub fn angle(&self, value: f64) -> f32 {
+        let (low, high) = self.scale;
+        let part = ((value - low) / (high - low)).clamp(0.0, 1.0);
+        START_ANGLE + SWEEP * part as f32
+    }
+
+    /// Returns the text in the middle of the dial
+    pub fn label(&self) -> String {
+        match self.value {
+            Some(value) => format!(
+                "{} {}",
+                format_value(self.sensor, value),
+                self.trend.arrow()
+            ),
+            None => "-".to_string(),
+        }
+    }
+}
+
+/// Returns the scale of a dial, containing the range with a quarter of its width on both sides
+fn scale(value: Option<i32>, range: Option<(i32, i32)>) -> (f64, f64) {
+    let mut values = vec![];
+    if let Some((min, max)) = range {
+        let padding = (max - min) as f64 / 4.0;
+        values.push(min as f64 - padding);
+        values.push(max as f64 + padding);
+    }
+    if let Some(value) = value {
+        values.push(value as f64);
+        if range.is_none() {
+            // Without a range the value is shown in the middle
+            values.push(0.0);
+            values.push(value as f64 * 2.0);
+        }
+    }
+    match values
+        .iter()
+        .copied()
+        .minmax_by(|a, b| a.total_cmp(b))
+        .into_option()
+    {
+        Some((low, high)) if low < high => (low, high),
+        Some((low, _)) => (low - 1.0, low + 1.0),
+        None => (0.0, 1.0),
+    }
+}
+
+/// Returns the direction from the reading an hour before the latest reading to the latest one
+fn trend(points: &[(i32, i32)], (low, high): (f64, f64)) -> Trend {
+    let (latest_time, latest) = match points.last() {
+        Some(point) => *point,
+        None => return Trend::Gleichbleibend,
+    };
+    let earlier = points
+        .iter()
+        .find(|(time, _)| latest_time - time <= TREND_MINUTES)
+        .filter(|(time, _)| *time < latest_time);
+    match earlier {
+        Some((_, earlier)) => {
+            let change = (latest - earlier) as f64;
+            if change.abs() <= (high - low) * STEADY_PART {
+                Trend::Gleichbleibend
+            } else if change > 0.0 {
+                Trend::Steigend
+            } else {
+                Trend::Fallend
+            }
+        }
+        None => Trend::Gleichbleibend,
+    }
+}
+
+impl<M> canvas::Program<M> for Gauge {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let center = frame.center();
+        let radius = bounds.width.min(bounds.height) / 2.0 - 12.0;
+        let arc = |start: f32, end: f32| {
+            Path::new(|builder| {
+                builder.arc(Arc {
+                    center,
+                    radius,
+                    start_angle: start,
+                    end_angle: end,
+                })
+            })
+        };
+        frame.stroke(
+            &arc(START_ANGLE, START_ANGLE + SWEEP),
+            Stroke::default()
+                .with_color(Color::from_rgb(0.85, 0.85, 0.85))
+                .with_width(12.0),
+        );
+        if let Some((min, max)) = self.range {
+            frame.stroke(
+                &arc(self.angle(min as f64), self.angle(max as f64)),
+                Stroke::default()
+                    .with_color(Color::from_rgb8(13, 171, 118))
+                    .with_width(12.0),
+            );
+        }
+        if let Some(value) = self.value {
+            let angle = self.angle(value as f64);
+            let needle = Path::line(
+                center,
+                Point::new(
+                    center.x + (radius - 6.0) * angle.cos(),
+                    center.y + (radius - 6.0) * angle.sin(),
+                ),
+            );
+            let outside = self
+                .range
+                .is_some_and(|(min, max)| value < min || value > max);
+            frame.stroke(
+                &needle,
+                Stroke::default()
+                    .with_color(if outside {
+                        Color::from_rgb8(214, 73, 51)
+                    } else {
+                        Color::BLACK
+                    })
+                    .with_width(3.0),
+            );
+        }
+        frame.fill_text(canvas::Text {
+            content: self.label(),
+            position: Point::new(center.x, center.y + radius / 2.0),
+            color: Color::WHITE,
+            size: 22.0,
+            horizontal_alignment: Horizontal::Center,
+            vertical_alignment: Vertical::Center,
+            ..canvas::Text::default()
+        });
+        frame.fill_text(canvas::Text {
+            content: self.sensor.to_string(),
+            position: Point::new(center.x, bounds.height - 4.0),
+            color: Color::WHITE,
+            size: 18.0,
+            horizontal_alignment: Horizontal::Center,
+            vertical_alignment: Vertical::Bottom,
+            ..canvas::Text::default()
+        });
+        vec![frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requests::SensorRange;
+
+    fn readings(values: &[i32]) -> GraphData {
+        GraphData {
+            timestamps: (0..values.len())
+                .map(|index| format!("2023-05-01T08:{:02}:00Z", index * 15))
+                .collect(),
+            values: values.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_gauge_with_range() {
+        let gauge = Gauge::new(
+            Sensortypes::Temperatur,
+            &[readings(&[18, 19, 20, 21])],
+            Some((15, 25)),
+        );
+        assert_eq!(gauge.value, Some(21));
+        assert_eq!(gauge.scale, (12.5, 27.5));
+        assert_eq!(gauge.trend, Trend::Steigend);
+        assert_eq!(gauge.label(), "21 °C ↑");
+        assert!((gauge.angle(12.5) - START_ANGLE).abs() < 1e-6);
+        assert!((gauge.angle(20.0) - (START_ANGLE + SWEEP / 2.0)).abs() < 1e-6);
+        assert!((gauge.angle(100.0) - (START_ANGLE + SWEEP)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_gauge_trend() {
+        // Only the last hour counts, the drop before it is ignored
+        let falling = Gauge::new(
+            Sensortypes::Feuchtigkeit,
+            &[readings(&[9000, 5000, 4900, 4800, 4700, 4600])],
+            Some((3000, 6000)),
+        );
+        assert_eq!(falling.trend, Trend::Fallend);
+        let steady = Gauge::new(
+            Sensortypes::Feuchtigkeit,
+            &[readings(&[5000, 5010, 4995])],
+            Some((3000, 6000)),
+        );
+        assert_eq!(steady.trend, Trend::Gleichbleibend);
+        let single = Gauge::new(Sensortypes::Feuchtigkeit, &[readings(&[5000])], None);
+        assert_eq!(single.trend, Trend::Gleichbleibend);
+    }
+
+    #[test]
+    fn test_gauge_without_readings() {
+        let gauge = Gauge::new(Sensortypes::Licht, &[], SensorRange::default().bounds());
+        assert_eq!(gauge.value, None);
+        assert_eq!(gauge.range, None);
+        assert_eq!(gauge.scale, (0.0, 1.0));
+        assert_eq!(gauge.label(), "-");
+        let unranged = Gauge::new(Sensortypes::Licht, &[readings(&[500])], None);
+        assert_eq!(unranged.scale, (0.0, 1000.0));
+    }
+}
@@ -1,47 +1,109 @@
+use crate::aggregation::{aggregate, Aggregation};
 use crate::detail::Sensortypes;
-use crate::requests::GraphData;
+use crate::downsample::{lttb, visible_points, zoom_window};
+use crate::requests::{GraphData, SensorRange};
+use crate::timezone::{from_minutes, TimeZoneChoice};
 use crate::TEXT_SIZE;
-use iced::{Element, Length};
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use iced::widget::canvas::{event, Cursor, Event};
+use iced::{mouse, Element, Length};
 use itertools::{enumerate, Itertools};
-use plotters::chart::SeriesLabelPosition;
-use plotters::element::PathElement;
-use plotters::prelude::RGBColor;
+use plotters::chart::{ChartContext, SeriesLabelPosition};
+use plotters::coord::cartesian::Cartesian2d;
+use plotters::coord::ranged1d::{DefaultFormatting, Ranged, ValueFormatter};
+use plotters::coord::types::RangedCoordi32;
+use plotters::coord::Shift;
+use plotters::drawing::DrawingArea;
+use plotters::element::{PathElement, Polygon, Rectangle};
+use plotters::prelude::{IntoLogRange, RGBColor};
 use plotters::series::LineSeries;
 use plotters::style::{Color, IntoFont, BLACK, BLUE, WHITE};
 use plotters_iced::{Chart, ChartBuilder, ChartWidget, DrawingBackend};
 
+/// The color of the target band, matches the success color of the theme
+const BAND_COLOR: RGBColor = RGBColor(13, 171, 118);
+/// The color of out of range segments, matches the danger color of the theme
+const DANGER_COLOR: RGBColor = RGBColor(214, 73, 51);
+/// The number of pixels per drawn point when a series is downsampled
+const PIXELS_PER_POINT: u32 = 2;
+/// The number of dashes and gaps of a dashed line across the visible x range
+const DASHES_PER_WINDOW: i32 = 120;
+
+/// The state of a chart widget
+///
+/// Fields:
+/// - `zoom`: The visible x range if the user zoomed in, `None` shows the full range
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ChartState {
+    pub zoom: Option<(i32, i32)>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// A chart that can be drawn
 ///
 /// Fields:
 /// - `name`: The name of the chart
-/// - `x`: The x values of the chart
+/// - `x`: The x values of the chart, in minutes since the unix epoch
 /// - `y`: The y values of the chart
 /// - `color`: The color of the chart
+/// - `secondary`: If the chart is drawn against the secondary y axis
+/// - `envelope`: The min and max values for every x value, drawn as an area around the line
 pub struct PlantChart {
     pub name: String,
     pub x: Vec<i32>,
     pub y: Vec<i32>,
     color: RGBColor,
+    pub secondary: bool,
+    pub envelope: Option<(Vec<i32>, Vec<i32>)>,
 }
 impl PlantChart {
     /// Create a new PlantChart
     pub fn new(name: String, x: Vec<i32>, y: Vec<i32>, color: RGBColor) -> PlantChart {
-        PlantChart { name, x, y, color }
+        PlantChart {
+            name,
+            x,
+            y,
+            color,
+            secondary: false,
+            envelope: None,
+        }
     }
     /// Create a test PlantChart
+    #[cfg(test)]
     pub fn test() -> PlantChart {
         PlantChart {
             name: String::from("Test"),
             x: vec![0, 0, 0, 0, 0, 0],
             y: vec![0, 1, 2, 3, 4, 5],
             color: BLUE,
+            secondary: false,
+            envelope: None,
         }
     }
     /// Get the color of the chart
     pub fn get_color(&self) -> RGBColor {
         self.color
     }
+    /// Returns the chart with its values grouped by the aggregation
+    ///
+    /// The line shows the mean of every interval and the envelope its min and max.
+    pub fn aggregated(&self, aggregation: Aggregation) -> PlantChart {
+        if aggregation == Aggregation::Keine {
+            return self.clone();
+        }
+        let buckets = aggregate(&self.x, &self.y, aggregation, TimeZoneChoice::current());
+        PlantChart {
+            name: self.name.clone(),
+            x: buckets.iter().map(|bucket| bucket.start).collect(),
+            y: buckets.iter().map(|bucket| bucket.mean).collect(),
+            color: self.color,
+            secondary: self.secondary,
+            envelope: Some((
+                buckets.iter().map(|bucket| bucket.min).collect(),
+                buckets.iter().map(|bucket| bucket.max).collect(),
+            )),
+        }
+    }
 }
 impl Default for PlantChart {
     /// Create a default PlantChart
@@ -51,33 +113,82 @@ impl Default for PlantChart {
             x: Vec::new(),
             y: Vec::new(),
             color: BLUE,
+            secondary: false,
+            envelope: None,
         }
     }
 }
+/// A vertical marker of an event, e.g. an entry of the care journal
+///
+/// Fields:
+/// - `x`: The time of the event, in minutes since the unix epoch
+/// - `color`: The color of the marker
+/// - `label`: The label of the marker in the legend, markers with the same label share one entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChartMarker {
+    pub x: i32,
+    pub color: RGBColor,
+    pub label: String,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// A collection of PlantCharts
 ///
 /// Fields:
 /// - `charts`: The charts
 /// - `message`: The message that is passed to the charts, depending on the page it is used in
+/// - `range`: The min and max of the sensor range, drawn as a shaded band behind the data, see
+///   `SensorRange::checked`
+/// - `y_label`: The title of the y axis, containing the unit of the sensor
+/// - `secondary_label`: The title of the secondary y axis, only drawn if a chart uses it
+/// - `log_scale`: If the y axis uses a logarithmic scale
+/// - `aggregation`: The time interval the values are grouped by before drawing
+/// - `markers`: The events drawn as vertical lines over the charts
+/// - `projection`: The forecast of the primary sensor, drawn as dashed line after the data
 pub struct PlantCharts<M> {
     pub charts: Vec<PlantChart>,
     pub message: M,
+    pub range: Option<(i32, i32)>,
+    pub y_label: String,
+    pub secondary_label: String,
+    pub log_scale: bool,
+    pub aggregation: Aggregation,
+    pub markers: Vec<ChartMarker>,
+    pub projection: Vec<(i32, i32)>,
 }
 
 impl<M: 'static> PlantCharts<M> {
     /// Create a new PlantCharts object
     pub fn new(charts: Vec<PlantChart>, message: M) -> PlantCharts<M> {
-        PlantCharts { charts, message }
+        PlantCharts {
+            charts,
+            message,
+            range: None,
+            y_label: String::new(),
+            secondary_label: String::new(),
+            log_scale: false,
+            aggregation: Aggregation::Keine,
+            markers: Vec::new(),
+            projection: Vec::new(),
+        }
     }
     /// Create a test PlantCharts object
+    #[cfg(test)]
     pub fn test(message: M) -> PlantCharts<M> {
         PlantCharts {
             charts: vec![PlantChart::test()],
             message,
+            range: None,
+            y_label: String::new(),
+            secondary_label: String::new(),
+            log_scale: false,
+            aggregation: Aggregation::Keine,
+            markers: Vec::new(),
+            projection: Vec::new(),
         }
     }
     /// Get the largest x and y values of the charts
+    #[allow(dead_code)]
     pub fn largest_x_y(&self) -> (i32, i32) {
         let mut x = 0;
         let mut y = 0;
@@ -93,6 +204,115 @@ impl<M: 'static> PlantCharts<M> {
         }
         (x, y)
     }
+    /// Get the smallest and largest x values of the charts
+    pub fn x_bounds(&self) -> (i32, i32) {
+        let xs = self
+            .charts
+            .iter()
+            .flat_map(|chart| chart.x.iter().copied())
+            .chain(self.projection.iter().map(|point| point.0));
+        match xs.minmax().into_option() {
+            Some((min, max)) if min < max => (min, max),
+            Some((min, _)) => (min, min + 1),
+            None => (0, 1),
+        }
+    }
+    /// Get the bounds of the primary y axis
+    ///
+    /// The bounds contain all values and the sensor range, with a padding of 10% on both sides.
+    /// Negative values are kept, a flat series still gets a visible range.
+    pub fn y_bounds(&self) -> (i32, i32) {
+        let mut ys = self
+            .charts
+            .iter()
+            .filter(|chart| !chart.secondary)
+            .flat_map(|chart| chart.y.iter().copied())
+            .chain(self.projection.iter().map(|point| point.1))
+            .collect_vec();
+        if let Some((min, max)) = self.valid_range() {
+            ys.push(min);
+            ys.push(max);
+        }
+        padded_bounds(&ys)
+    }
+    /// Returns the sensor range, `None` if it is unset or its minimum is not below its maximum
+    fn valid_range(&self) -> Option<(i32, i32)> {
+        self.range.and_then(SensorRange::checked)
+    }
+    /// Returns the parts of the chart inside `window` that leave the sensor range
+    ///
+    /// The parts are found in all readings, so short excursions are highlighted even if the
+    /// downsampling of the drawn line drops them.
+    fn out_of_range(&self, chart: &PlantChart, window: (i32, i32)) -> Vec<Vec<(i32, i32)>> {
+        match self.valid_range() {
+            Some(range) => {
+                let (x, y): (Vec<i32>, Vec<i32>) = visible_points(&chart.x, &chart.y, window)
+                    .into_iter()
+                    .unzip();
+                out_of_range_segments(&x, &y, range)
+            }
+            None => vec![],
+        }
+    }
+    /// Get the bounds of the secondary y axis
+    pub fn secondary_y_bounds(&self) -> (i32, i32) {
+        let ys = self
+            .charts
+            .iter()
+            .filter(|chart| chart.secondary)
+            .flat_map(|chart| chart.y.iter().copied())
+            .collect_vec();
+        padded_bounds(&ys)
+    }
+    /// Returns the charts as they are drawn, grouped by the selected aggregation
+    pub fn displayed_charts(&self) -> Vec<PlantChart> {
+        self.charts
+            .iter()
+            .map(|chart| chart.aggregated(self.aggregation))
+            .collect()
+    }
+    /// Returns the displayed values as semicolon separated values
+    ///
+    /// Aggregated charts contain the mean, min and max of every interval.
+    pub fn to_csv(&self) -> String {
+        let mut csv = if self.aggregation == Aggregation::Keine {
+            String::from("Serie;Zeit;Wert\n")
+        } else {
+            format!(
+                "Serie;Intervall ({});Mittelwert;Minimum;Maximum\n",
+                self.aggregation
+            )
+        };
+        for chart in self.displayed_charts() {
+            for (i, (x, y)) in chart.x.iter().zip(chart.y.iter()).enumerate() {
+                let time = from_minutes(*x).to_rfc3339_opts(SecondsFormat::Secs, true);
+                match &chart.envelope {
+                    Some((min, max)) => csv.push_str(&format!(
+                        "{};{};{};{};{}\n",
+                        chart.name, time, y, min[i], max[i]
+                    )),
+                    None => csv.push_str(&format!("{};{};{}\n", chart.name, time, y)),
+                }
+            }
+        }
+        csv
+    }
+    /// Writes the displayed values as a csv file into the home directory
+    ///
+    /// Returns the path of the written file.
+    pub fn export_csv(&self, name: &str) -> std::io::Result<std::path::PathBuf> {
+        let directory = std::env::var("HOME")
+            .map(std::path::PathBuf::from)
+            .or_else(|_| std::env::current_dir())?;
+        let file_name = format!(
+            "plantbuddy_{}_{}.csv",
+            name.replace(|c: char| !c.is_alphanumeric(), "_"),
+            TimeZoneChoice::current().format(&Utc::now(), "%Y%m%d_%H%M%S")
+        );
+        let path = directory.join(file_name);
+        std::fs::write(&path, self.to_csv())?;
+        Ok(path)
+    }
     /// Create the charts from the data
     pub fn create_charts(
         message: M,
@@ -102,15 +322,19 @@ impl<M: 'static> PlantCharts<M> {
     ) -> PlantCharts<M> {
         let mut charts = Vec::new();
         for (i, data) in enumerate(&graph_data) {
+            let (x, y): (Vec<i32>, Vec<i32>) =
+                graph_points(std::slice::from_ref(data)).into_iter().unzip();
             let chart = PlantChart::new(
                 format!("{}-{}", name[i], sensor),
-                (0..data.timestamps.len() as i32).collect_vec(),
-                data.values.clone(),
+                x,
+                y,
                 sensor.get_color_with_random_offset(),
             );
             charts.push(chart);
         }
-        PlantCharts::new(charts, message)
+        let mut plant_charts = PlantCharts::new(charts, message);
+        plant_charts.y_label = format!("{} [{}]", sensor, sensor.get_unit());
+        plant_charts
     }
     /// Update the charts with new data
     pub fn update_charts(
@@ -125,42 +349,214 @@ impl<M: 'static> PlantCharts<M> {
 }
 
 impl<M: 'static + Clone> Chart<M> for PlantCharts<M> {
-    type State = ();
+    type State = ChartState;
     /// Build the chart
-    fn build_chart<DB: DrawingBackend>(&self, _state: &Self::State, mut builder: ChartBuilder<DB>) {
-        //Change background color
-        let mut chart = builder
+    fn build_chart<DB: DrawingBackend>(&self, state: &Self::State, builder: ChartBuilder<DB>) {
+        self.build_chart_with_width(state, builder, 1000);
+    }
+    /// Draw the chart, the width of the drawing area decides how many points are drawn
+    fn draw_chart<DB: DrawingBackend>(&self, state: &Self::State, root: DrawingArea<DB, Shift>) {
+        let width = root.dim_in_pixel().0;
+        let builder = ChartBuilder::on(&root);
+        self.build_chart_with_width(state, builder, width);
+    }
+    /// Zooms the x axis with the mouse wheel, a right click shows the full range again
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: Event,
+        bounds: iced::Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<M>) {
+        let position = match cursor.position_in(&bounds) {
+            Some(position) => position,
+            None => return (event::Status::Ignored, None),
+        };
+        match event {
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                let y = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y,
+                };
+                let full = self.x_bounds();
+                state.zoom = zoom_window(
+                    full,
+                    state.zoom.unwrap_or(full),
+                    position.x / bounds.width,
+                    y > 0.0,
+                );
+                (event::Status::Captured, None)
+            }
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
+                state.zoom = None;
+                (event::Status::Captured, None)
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+}
+
+impl<M: 'static> PlantCharts<M> {
+    /// Builds the chart for the visible x range, downsampled to the given width in pixels
+    fn build_chart_with_width<DB: DrawingBackend>(
+        &self,
+        state: &ChartState,
+        mut builder: ChartBuilder<DB>,
+        width: u32,
+    ) {
+        builder
             .caption("Pflanzengraphen", ("sans-serif", TEXT_SIZE).into_font())
             .margin(10)
-            .x_label_area_size(40)
-            .y_label_area_size(40)
-            .build_cartesian_2d(0..self.largest_x_y().0, 0..self.largest_x_y().1)
-            .unwrap();
+            .x_label_area_size(50)
+            .y_label_area_size(70);
+        let window = state.zoom.unwrap_or_else(|| self.x_bounds());
+        let points = (width / PIXELS_PER_POINT).max(3) as usize;
+        let (y_min, y_max) = self.y_bounds();
+        if self.log_scale {
+            // A logarithmic axis can not contain zero or negative values
+            let chart = builder
+                .build_cartesian_2d(window.0..window.1, (y_min.max(1)..y_max.max(2)).log_scale())
+                .unwrap();
+            self.draw_series(chart, window, points);
+        } else {
+            let chart = builder
+                .build_cartesian_2d(window.0..window.1, y_min..y_max)
+                .unwrap();
+            self.draw_series(chart, window, points);
+        }
+    }
+}
+
+impl<M: 'static> PlantCharts<M> {
+    /// Draws the mesh, the target band, the charts and the legend into the given chart
+    ///
+    /// Charts marked as secondary are drawn against a second y axis on the right side.
+    /// Only the points inside `window` are drawn, reduced to at most `points` per chart.
+    fn draw_series<'a, DB, Y>(
+        &self,
+        chart: ChartContext<'a, DB, Cartesian2d<RangedCoordi32, Y>>,
+        window: (i32, i32),
+        points: usize,
+    ) where
+        DB: DrawingBackend + 'a,
+        Y: Ranged<ValueType = i32, FormatOption = DefaultFormatting> + ValueFormatter<i32>,
+    {
+        let (x_min, x_max) = window;
+        let (y_min, y_max) = self.y_bounds();
+        let (secondary_min, secondary_max) = self.secondary_y_bounds();
+        let mut chart = chart.set_secondary_coord(x_min..x_max, secondary_min..secondary_max);
         chart
             .configure_mesh()
             .bold_line_style(BLACK.mix(0.3))
             .light_line_style(BLACK.mix(0.3))
             .axis_style(BLACK.mix(0.5))
+            .x_labels(6)
+            .x_label_formatter(&|x| format_minutes(*x))
+            .x_desc("Zeit")
+            .y_desc(self.y_label.as_str())
+            .axis_desc_style(("sans-serif", TEXT_SIZE).into_font())
             .draw()
             .expect("failed to draw mesh");
+        if self.charts.iter().any(|chart| chart.secondary) {
+            chart
+                .configure_secondary_axes()
+                .axis_style(BLACK.mix(0.5))
+                .y_desc(self.secondary_label.as_str())
+                .axis_desc_style(("sans-serif", TEXT_SIZE).into_font())
+                .draw()
+                .expect("failed to draw secondary axis");
+        }
+
+        // The target band is drawn first so it stays behind the data
+        let band = self.valid_range().and_then(|(min, max)| {
+            let lower = if self.log_scale { min.max(1) } else { min };
+            SensorRange::checked((lower.max(y_min), max.min(y_max)))
+        });
+        if let Some((lower, upper)) = band {
+            chart
+                .draw_series(std::iter::once(Rectangle::new(
+                    [(x_min, lower), (x_max, upper)],
+                    BAND_COLOR.mix(0.2).filled(),
+                )))
+                .unwrap()
+                .label("Zielbereich")
+                .legend(|(x, y)| {
+                    Rectangle::new([(x, y - 5), (x + 20, y + 5)], BAND_COLOR.mix(0.2).filled())
+                });
+        }
 
-        for plantchart in self.charts.iter() {
+        for plantchart in self.displayed_charts().iter() {
             let color = plantchart.get_color();
+            if let Some((min, max)) = &plantchart.envelope {
+                let mut area = visible_points(&plantchart.x, min, window);
+                area.extend(visible_points(&plantchart.x, max, window).iter().rev());
+                let area = std::iter::once(Polygon::new(area, color.mix(0.2).filled()));
+                if plantchart.secondary {
+                    chart.draw_secondary_series(area).unwrap();
+                } else {
+                    chart.draw_series(area).unwrap();
+                }
+            }
+            let sampled = lttb(
+                &visible_points(&plantchart.x, &plantchart.y, window),
+                points,
+            );
+            let series = LineSeries::new(sampled.iter().copied(), &color).point_size(2);
+            if plantchart.secondary {
+                chart
+                    .draw_secondary_series(series)
+                    .unwrap()
+                    .label(format!("{} (rechts)", plantchart.name))
+                    .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+                continue;
+            }
             chart
-                .draw_series(
-                    LineSeries::new(
-                        plantchart
-                            .x
-                            .iter()
-                            .zip(plantchart.y.iter())
-                            .map(|(x, y)| (*x, *y)),
-                        &color,
-                    )
-                    .point_size(2),
-                )
+                .draw_series(series)
                 .unwrap()
                 .label(plantchart.name.as_str())
                 .legend(move |(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], color));
+            for segment in self.out_of_range(plantchart, window) {
+                chart
+                    .draw_series(LineSeries::new(segment, DANGER_COLOR.stroke_width(3)))
+                    .unwrap();
+            }
+        }
+        if !self.projection.is_empty() {
+            let dash = ((x_max - x_min) / DASHES_PER_WINDOW).max(1);
+            chart
+                .draw_series(
+                    dashes(&self.projection, dash)
+                        .into_iter()
+                        .map(|dash| PathElement::new(dash, DANGER_COLOR.stroke_width(2))),
+                )
+                .unwrap()
+                .label("Prognose")
+                .legend(|(x, y)| {
+                    PathElement::new(vec![(x, y), (x + 8, y)], DANGER_COLOR.stroke_width(2))
+                });
+        }
+        let lower = if self.log_scale { y_min.max(1) } else { y_min };
+        for (label, markers) in &self
+            .markers
+            .iter()
+            .filter(|marker| marker.x >= x_min && marker.x <= x_max)
+            .sorted_by(|a, b| a.label.cmp(&b.label))
+            .group_by(|marker| marker.label.clone())
+        {
+            let markers = markers.collect_vec();
+            let color = markers[0].color;
+            chart
+                .draw_series(markers.iter().map(|marker| {
+                    PathElement::new(
+                        vec![(marker.x, lower), (marker.x, y_max)],
+                        marker.color.stroke_width(2),
+                    )
+                }))
+                .unwrap()
+                .label(label)
+                .legend(move |(x, y)| {
+                    PathElement::new(vec![(x + 10, y - 5), (x + 10, y + 5)], color)
+                });
         }
         chart
             .configure_series_labels()
@@ -174,8 +570,158 @@ impl<M: 'static + Clone> Chart<M> for PlantCharts<M> {
     }
 }
 
+/// Returns the bounds of the given values with a padding of 10% on both sides
+///
+/// Negative values are kept, a flat series still gets a visible range.
+fn padded_bounds(values: &[i32]) -> (i32, i32) {
+    let (min, max) = match values.iter().minmax().into_option() {
+        Some((min, max)) => (*min, *max),
+        None => return (0, 1),
+    };
+    let padding = ((max - min) / 10).max(1);
+    let lower = if min >= 0 {
+        (min - padding).max(0)
+    } else {
+        min - padding
+    };
+    (lower, max + padding)
+}
+
+/// Returns how many units of the server make up one unit shown to the user
+///
+/// The server sends the values and ranges of all sensors except the temperature multiplied by
+/// 100, so they keep two decimals as integers.
+pub fn factor(sensor: Sensortypes) -> i32 {
+    match sensor {
+        Sensortypes::Temperatur => 1,
+        _ => 100,
+    }
+}
+
+/// Returns the unit shown next to the values of the sensor
+pub fn unit_symbol(sensor: Sensortypes) -> &'static str {
+    match sensor {
+        Sensortypes::Feuchtigkeit | Sensortypes::Luftfeuchtigkeit => "%",
+        Sensortypes::Temperatur => "°C",
+        Sensortypes::Licht => "lx",
+    }
+}
+
+/// Formats a value of the server as number in the unit shown to the user
+///
+/// Whole numbers have no decimals, all other values two.
+pub fn format_number(sensor: Sensortypes, value: i32) -> String {
+    let factor = factor(sensor);
+    if value % factor == 0 {
+        (value / factor).to_string()
+    } else {
+        format!("{:.2}", value as f64 / factor as f64)
+    }
+}
+
+/// Formats a value of the server with the unit of the sensor, e.g. `42.50 %`
+pub fn format_value(sensor: Sensortypes, value: i32) -> String {
+    format!("{} {}", format_number(sensor, value), unit_symbol(sensor))
+}
+
+/// Formats a range of the server with the unit of the sensor, e.g. `30 – 70 %`
+pub fn format_range(sensor: Sensortypes, (min, max): (i32, i32)) -> String {
+    format!(
+        "{} – {} {}",
+        format_number(sensor, min),
+        format_number(sensor, max),
+        unit_symbol(sensor)
+    )
+}
+
+/// Converts a timestamp of the server into minutes since the unix epoch
+///
+/// Minutes are used as x values, they fit into an i32 and are precise enough for sensor data.
+pub fn timestamp_to_minutes(timestamp: &str) -> Option<i32> {
+    let time = match DateTime::parse_from_rfc3339(timestamp) {
+        Ok(time) => time.with_timezone(&Utc),
+        Err(_) => {
+            let naive = NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%dT%H:%M:%S%.f").ok()?;
+            Utc.from_utc_datetime(&naive)
+        }
+    };
+    i32::try_from(time.timestamp() / 60).ok()
+}
+
+/// Returns the readings of the server as points of minutes and values, sorted by time
+///
+/// The series are merged and readings with an invalid timestamp are skipped.
+pub fn graph_points(graph_data: &[GraphData]) -> Vec<(i32, i32)> {
+    graph_data
+        .iter()
+        .flat_map(|data| {
+            data.timestamps
+                .iter()
+                .zip(data.values.iter())
+                .filter_map(|(timestamp, value)| Some((timestamp_to_minutes(timestamp)?, *value)))
+        })
+        .sorted_by_key(|point| point.0)
+        .collect()
+}
+
+/// Formats minutes since the unix epoch as date and time of the selected time zone for the x axis
+pub fn format_minutes(minutes: i32) -> String {
+    TimeZoneChoice::current().format(&from_minutes(minutes), "%d.%m. %H:%M")
+}
+
+/// Splits a line into dashes of the given length on the x axis with gaps of the same length
+pub fn dashes(points: &[(i32, i32)], dash: i32) -> Vec<Vec<(i32, i32)>> {
+    let mut dashes = vec![];
+    for (start, end) in points.iter().tuple_windows() {
+        let width = end.0 - start.0;
+        if width <= 0 {
+            continue;
+        }
+        let at = |x: i32| {
+            let y = start.1 as f64 + (end.1 - start.1) as f64 * (x - start.0) as f64 / width as f64;
+            (x, y.round() as i32)
+        };
+        let mut x = start.0;
+        while x < end.0 {
+            dashes.push(vec![at(x), at((x + dash).min(end.0))]);
+            x += 2 * dash;
+        }
+    }
+    dashes
+}
+
+/// Splits a series into the parts that leave the given range
+///
+/// Every line segment with at least one end outside of `range` belongs to an out of range part,
+/// consecutive segments are merged so they can be drawn as one line.
+pub fn out_of_range_segments(x: &[i32], y: &[i32], range: (i32, i32)) -> Vec<Vec<(i32, i32)>> {
+    let (min, max) = range;
+    let points = x.iter().zip(y.iter()).map(|(x, y)| (*x, *y)).collect_vec();
+    let outside = |point: &(i32, i32)| point.1 < min || point.1 > max;
+    let mut segments = vec![];
+    let mut current: Vec<(i32, i32)> = vec![];
+    if points.len() == 1 && outside(&points[0]) {
+        segments.push(points.clone());
+    }
+    for pair in points.windows(2) {
+        if outside(&pair[0]) || outside(&pair[1]) {
+            if current.is_empty() {
+                current.push(pair[0]);
+            }
+            current.push(pair[1]);
+        } else if !current.is_empty() {
+            segments.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.is_empty() {
+        segments.push(current);
+    }
+    segments
+}
+
 impl<M: 'static + Clone> PlantCharts<M> {
     /// Shows the chart
+    #[allow(dead_code)]
     fn view(&self) -> Element<'_, M> {
         ChartWidget::new(self)
             .width(Length::Fill)
@@ -189,6 +735,19 @@ mod tests {
     use super::*;
     use plotters::style::RED;
 
+    #[test]
+    fn test_format_value() {
+        assert_eq!(format_number(Sensortypes::Feuchtigkeit, 4250), "42.50");
+        assert_eq!(format_number(Sensortypes::Licht, 30000), "300");
+        assert_eq!(format_value(Sensortypes::Feuchtigkeit, 4512), "45.12 %");
+        assert_eq!(format_value(Sensortypes::Temperatur, 21), "21 °C");
+        assert_eq!(format_value(Sensortypes::Licht, 123456), "1234.56 lx");
+        assert_eq!(
+            format_range(Sensortypes::Feuchtigkeit, (3000, 7000)),
+            "30 – 70 %"
+        );
+    }
+
     #[test]
     fn test_plant_chart_new() {
         let chart = PlantChart::new("Test".to_string(), vec![1, 2, 3], vec![4, 5, 6], RED);
@@ -232,4 +791,174 @@ mod tests {
         let charts = PlantCharts::new(vec![chart1, chart2], "Message".to_string());
         assert_eq!(charts.largest_x_y(), (9, 12));
     }
+
+    #[test]
+    fn test_y_bounds_with_range() {
+        let chart = PlantChart::new("Test".to_string(), vec![1, 2, 3], vec![40, 50, 60], RED);
+        let mut charts = PlantCharts::new(vec![chart], "Message".to_string());
+        assert_eq!(charts.y_bounds(), (38, 62));
+        charts.range = Some((20, 100));
+        assert_eq!(charts.y_bounds(), (12, 108));
+        // An unset range does not stretch the axis to zero
+        charts.range = Some((0, 0));
+        assert_eq!(charts.y_bounds(), (38, 62));
+    }
+
+    #[test]
+    fn test_out_of_range_uses_all_readings() {
+        let x = (0..1000).collect_vec();
+        let mut y = vec![50; 1000];
+        y[500] = 90;
+        let chart = PlantChart::new("Test".to_string(), x, y, RED);
+        let mut charts = PlantCharts::new(vec![chart.clone()], "Message".to_string());
+        assert!(charts.out_of_range(&chart, (0, 999)).is_empty());
+
+        charts.range = Some((30, 70));
+        assert_eq!(
+            charts.out_of_range(&chart, (0, 999)),
+            vec![vec![(499, 50), (500, 90), (501, 50)]]
+        );
+        assert!(charts.out_of_range(&chart, (600, 999)).is_empty());
+        charts.range = Some((70, 30));
+        assert!(charts.out_of_range(&chart, (0, 999)).is_empty());
+    }
+
+    #[test]
+    fn test_y_bounds_negative_values() {
+        let chart = PlantChart::new("Test".to_string(), vec![1, 2, 3], vec![-5, 0, 15], RED);
+        let charts = PlantCharts::new(vec![chart], "Message".to_string());
+        assert_eq!(charts.y_bounds(), (-7, 17));
+    }
+
+    #[test]
+    fn test_y_bounds_flat_series() {
+        let chart = PlantChart::new("Test".to_string(), vec![1, 2, 3], vec![20, 20, 20], RED);
+        let charts = PlantCharts::new(vec![chart], "Message".to_string());
+        assert_eq!(charts.y_bounds(), (19, 21));
+        assert_eq!(charts.x_bounds(), (1, 3));
+    }
+
+    #[test]
+    fn test_secondary_y_bounds() {
+        let chart = PlantChart::new("Test".to_string(), vec![1, 2, 3], vec![40, 50, 60], RED);
+        let mut secondary = PlantChart::new("Test2".to_string(), vec![1, 2], vec![-10, 10], RED);
+        secondary.secondary = true;
+        let charts = PlantCharts::new(vec![chart, secondary], "Message".to_string());
+        assert_eq!(charts.y_bounds(), (38, 62));
+        assert_eq!(charts.secondary_y_bounds(), (-12, 12));
+    }
+
+    #[test]
+    fn test_timestamp_to_minutes() {
+        assert_eq!(timestamp_to_minutes("1970-01-01T01:00:00Z"), Some(60));
+        assert_eq!(timestamp_to_minutes("1970-01-01T01:00:00+01:00"), Some(0));
+        assert_eq!(timestamp_to_minutes("1970-01-01T00:02:59.500"), Some(2));
+        assert_eq!(timestamp_to_minutes("gestern"), None);
+    }
+
+    #[test]
+    fn test_graph_points() {
+        let data = |values: Vec<i32>, timestamps: Vec<&str>| GraphData {
+            values,
+            timestamps: timestamps.into_iter().map(String::from).collect(),
+        };
+        let points = graph_points(&[
+            data(
+                vec![30, 10, 99],
+                vec!["1970-01-01T00:30:00Z", "1970-01-01T00:10:00Z", "gestern"],
+            ),
+            data(vec![20], vec!["1970-01-01T00:20:00Z"]),
+        ]);
+        assert_eq!(points, vec![(10, 10), (20, 20), (30, 30)]);
+        assert!(graph_points(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_create_charts_uses_timestamps() {
+        let data = GraphData {
+            values: vec![10, 20],
+            timestamps: vec![
+                "1970-01-01T00:10:00Z".to_string(),
+                "1970-01-01T00:20:00Z".to_string(),
+            ],
+        };
+        let charts = PlantCharts::create_charts(
+            "Message".to_string(),
+            vec![data],
+            Sensortypes::Temperatur,
+            vec!["Pflanze".to_string()],
+        );
+        assert_eq!(charts.charts[0].x, vec![10, 20]);
+        assert_eq!(charts.charts[0].name, "Pflanze-Temperatur");
+        assert_eq!(charts.y_label, "Temperatur [celcius]");
+    }
+
+    #[test]
+    fn test_plant_chart_aggregated() {
+        let chart = PlantChart::new(
+            "Test".to_string(),
+            vec![0, 30, 60, 90],
+            vec![10, 20, 4, 8],
+            RED,
+        );
+        let aggregated = chart.aggregated(Aggregation::Stuendlich);
+        assert_eq!(aggregated.x, vec![0, 60]);
+        assert_eq!(aggregated.y, vec![15, 6]);
+        assert_eq!(aggregated.envelope, Some((vec![10, 4], vec![20, 8])));
+        assert_eq!(chart.aggregated(Aggregation::Keine), chart);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let chart = PlantChart::new("Test".to_string(), vec![0, 30], vec![10, 20], RED);
+        let mut charts = PlantCharts::new(vec![chart], "Message".to_string());
+        assert_eq!(
+            charts.to_csv(),
+            "Serie;Zeit;Wert\nTest;1970-01-01T00:00:00Z;10\nTest;1970-01-01T00:30:00Z;20\n"
+        );
+        charts.aggregation = Aggregation::Stuendlich;
+        assert_eq!(
+            charts.to_csv(),
+            "Serie;Intervall (Stündlich);Mittelwert;Minimum;Maximum\nTest;1970-01-01T00:00:00Z;15;10;20\n"
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_segments() {
+        let x = vec![0, 1, 2, 3, 4, 5, 6];
+        let y = vec![5, 5, 12, 11, 5, 5, 1];
+        let segments = out_of_range_segments(&x, &y, (2, 10));
+        assert_eq!(
+            segments,
+            vec![vec![(1, 5), (2, 12), (3, 11), (4, 5)], vec![(5, 5), (6, 1)]]
+        );
+    }
+
+    #[test]
+    fn test_out_of_range_segments_inside() {
+        let segments = out_of_range_segments(&[0, 1, 2], &[3, 4, 5], (0, 10));
+        assert!(segments.is_empty());
+    }
+
+    #[test]
+    fn test_dashes() {
+        let dashes = dashes(&[(0, 0), (10, 100)], 3);
+        assert_eq!(dashes, vec![vec![(0, 0), (3, 30)], vec![(6, 60), (9, 90)]]);
+    }
+
+    #[test]
+    fn test_projection_extends_bounds() {
+        let mut charts = PlantCharts::new(
+            vec![PlantChart::new(
+                "a".to_string(),
+                vec![0, 10],
+                vec![50, 40],
+                BLUE,
+            )],
+            (),
+        );
+        charts.projection = vec![(10, 40), (30, 20)];
+        assert_eq!(charts.x_bounds(), (0, 30));
+        assert!(charts.y_bounds().0 < 20);
+    }
 }
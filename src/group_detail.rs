@@ -0,0 +1,648 @@
+use crate::aggregation::{aggregate, Aggregation, Bucket};
+use crate::detail::{Sensortypes, URGENT_COLOR};
+use crate::graphs::{format_range, graph_points, PlantChart, PlantCharts};
+use crate::group_picker::{GroupOption, GroupPicker, GroupPickerMessage};
+use crate::requests::{GraphData, PlantGroupMetadata, PlantMetadata, RequestResult, SensorRange};
+use crate::timezone::TimeZoneChoice;
+use crate::{MyStylesheet, API_CLIENT, TEXT_SIZE};
+use iced::widget::{pick_list, scrollable, Button, Column, Container, Row, Text};
+use iced::{theme, Command, Element, Length};
+use iced_core::Length::FillPortion;
+use itertools::Itertools;
+use plotters_iced::ChartWidget;
+use std::collections::BTreeMap;
+
+/// The aggregations of the group charts, the readings of the members only line up in intervals
+const AGGREGATIONS: [Aggregation; 3] = [
+    Aggregation::Stuendlich,
+    Aggregation::Taeglich,
+    Aggregation::Woechentlich,
+];
+/// The height of one aggregate chart in pixels
+const CHART_HEIGHT: u16 = 300;
+
+/// The id of a member with the minutes and values of its readings
+type MemberSeries = (String, Vec<i32>, Vec<i32>);
+
+/// Contains all messages of the group detail view
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupDetailMessage {
+    /// Loads the group, the groups to move to and the plants of the group
+    Load,
+    /// The metadata of the group was loaded
+    GroupLoaded(RequestResult<PlantGroupMetadata>),
+    /// The groups the members can be moved to were loaded
+    GroupsLoaded(RequestResult<Vec<GroupOption>>),
+    /// The metadata of all plants was loaded
+    Loaded(RequestResult<Vec<(String, String, RequestResult<PlantMetadata>)>>),
+    /// The readings of every sensor of the members were loaded
+    ReadingsLoaded(Vec<(Sensortypes, Vec<(GraphData, String)>)>),
+    /// Returns to the dashboard
+    Back,
+    /// Opens the plant on the detail page
+    OpenPlant(String),
+    /// Handles the messages of the picker of the group the members are moved to
+    Target(GroupPickerMessage),
+    /// Moves the plant with the given id into the selected group
+    Move(String),
+    /// The plant with the given id was moved into the named group, or the move failed
+    Moved(String, String, RequestResult<()>),
+    /// Switches the aggregation of the charts
+    SwitchAggregation(Aggregation),
+    /// An empty message of the charts
+    Chart,
+}
+
+/// Shows a group with its members and the mean of their readings
+///
+/// Fields:
+/// * `group` - The metadata of the group
+/// * `members` - The ids and metadata of the plants in the group
+/// * `target` - The picker of the group the members are moved to, without the group itself
+/// * `time_range` - The time range of the charts
+/// * `series` - The readings of every member per sensor, as ids with minutes and values
+/// * `charts` - The mean and spread of the members for every sensor with readings
+/// * `aggregation` - The interval the readings of the members are averaged over
+/// * `status` - The state of the loading or the result of the last move
+/// * `error` - The reason the plants could not be loaded or moved
+#[derive(Debug, Clone)]
+pub struct GroupDetail {
+    pub group: PlantGroupMetadata,
+    pub members: Vec<(String, PlantMetadata)>,
+    pub target: GroupPicker,
+    time_range: (String, String),
+    series: Vec<(Sensortypes, Vec<MemberSeries>)>,
+    charts: Vec<PlantCharts<GroupDetailMessage>>,
+    aggregation: Aggregation,
+    pub status: String,
+    pub error: String,
+}
+
+/// Combines the readings of several plants into the mean and spread of every interval
+///
+/// The readings of every plant are averaged per interval first, so a plant that reports more
+/// often does not outweigh the others. Min and max are the lowest and highest of these averages.
+pub fn member_spread(
+    series: &[(&[i32], &[i32])],
+    aggregation: Aggregation,
+    time_zone: TimeZoneChoice,
+) -> Vec<Bucket> {
+    let mut means: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+    for (x, y) in series {
+        for bucket in aggregate(x, y, aggregation, time_zone) {
+            means.entry(bucket.start).or_default().push(bucket.mean);
+        }
+    }
+    means
+        .into_iter()
+        .map(|(start, means)| {
+            let sum: i64 = means.iter().map(|mean| *mean as i64).sum();
+            let (min, max) = means
+                .iter()
+                .copied()
+                .minmax()
+                .into_option()
+                .unwrap_or((0, 0));
+            Bucket {
+                start,
+                mean: (sum as f64 / means.len() as f64).round() as i32,
+                min,
+                max,
+            }
+        })
+        .collect()
+}
+
+/// Returns the plants of the group and the number of plants that could not be loaded
+///
+/// The plants are given with their ids, names and the result of loading their metadata.
+pub fn group_members(
+    group_id: i32,
+    plants: &[(String, String, RequestResult<PlantMetadata>)],
+) -> (Vec<(String, PlantMetadata)>, usize) {
+    let members = plants
+        .iter()
+        .filter_map(|(id, _, metadata)| match metadata {
+            Ok(metadata) if metadata.plantGroup.id == group_id => {
+                Some((id.clone(), metadata.clone()))
+            }
+            _ => None,
+        })
+        .collect_vec();
+    let failed = plants
+        .iter()
+        .filter(|(_, _, metadata)| metadata.is_err())
+        .count();
+    (members, failed)
+}
+
+impl GroupDetail {
+    /// Creates the view of the group, the plants and groups are loaded by `Load`
+    pub fn new(group: GroupOption, time_range: (String, String)) -> Self {
+        GroupDetail {
+            group: PlantGroupMetadata {
+                id: group.id,
+                name: group.name,
+                description: group.description,
+                careTips: Vec::new(),
+                sensorRanges: Vec::new(),
+            },
+            members: Vec::new(),
+            target: GroupPicker::new(),
+            time_range,
+            series: Vec::new(),
+            charts: Vec::new(),
+            aggregation: Aggregation::Stuendlich,
+            status: String::new(),
+            error: String::new(),
+        }
+    }
+
+    /// Replaces the plants of the group with the plants of the overview that belong to it
+    ///
+    /// The metadata of the group is loaded by itself, so an empty group keeps its care tips and
+    /// ranges.
+    fn set_members(&mut self, plants: &[(String, String, RequestResult<PlantMetadata>)]) {
+        let (members, failed) = group_members(self.group.id, plants);
+        self.members = members;
+        self.error = match failed {
+            0 => String::new(),
+            1 => "1 Pflanze konnte nicht geladen werden".to_string(),
+            failed => format!("{} Pflanzen konnten nicht geladen werden", failed),
+        };
+    }
+
+    /// Updates the groups the members can be moved to
+    pub fn set_groups(&mut self, groups: Vec<GroupOption>) {
+        let id = self.group.id;
+        self.target
+            .set_groups(groups.into_iter().filter(|group| group.id != id).collect());
+    }
+
+    /// Loads the readings of all members in the time range
+    fn load_readings(&self) -> Command<GroupDetailMessage> {
+        let ids = self.members.iter().map(|(id, _)| id.clone()).collect_vec();
+        Command::perform(
+            API_CLIENT
+                .get()
+                .unwrap()
+                .clone()
+                .get_sensor_graphs(ids, self.time_range.clone()),
+            GroupDetailMessage::ReadingsLoaded,
+        )
+    }
+
+    /// Replaces the readings of the members and creates the charts
+    fn set_readings(&mut self, graphs: Vec<(Sensortypes, Vec<(GraphData, String)>)>) {
+        self.series = graphs
+            .into_iter()
+            .map(|(sensor, data)| {
+                let members = data
+                    .into_iter()
+                    .map(|(graph, id)| {
+                        let (x, y) = graph_points(std::slice::from_ref(&graph))
+                            .into_iter()
+                            .unzip();
+                        (id, x, y)
+                    })
+                    .collect();
+                (sensor, members)
+            })
+            .collect();
+        self.update_charts();
+    }
+
+    /// Returns the min and max of the group's range for the sensor, `None` if it is unset
+    pub fn sensor_range(&self, sensor: Sensortypes) -> Option<(i32, i32)> {
+        self.group
+            .sensorRanges
+            .iter()
+            .find(|range| range.sensorType.name == sensor.get_name())
+            .and_then(SensorRange::bounds)
+    }
+
+    /// Creates the charts of all sensors the members have readings of
+    fn update_charts(&mut self) {
+        self.charts = self
+            .series
+            .iter()
+            .filter(|(_, members)| members.iter().any(|(_, x, _)| !x.is_empty()))
+            .map(|(sensor, members)| {
+                let series = members
+                    .iter()
+                    .map(|(_, x, y)| (x.as_slice(), y.as_slice()))
+                    .collect_vec();
+                let buckets = member_spread(&series, self.aggregation, TimeZoneChoice::current());
+                let mut chart = PlantChart::new(
+                    format!("Mittelwert {}", sensor),
+                    buckets.iter().map(|bucket| bucket.start).collect(),
+                    buckets.iter().map(|bucket| bucket.mean).collect(),
+                    sensor.get_color(),
+                );
+                chart.envelope = Some((
+                    buckets.iter().map(|bucket| bucket.min).collect(),
+                    buckets.iter().map(|bucket| bucket.max).collect(),
+                ));
+                let mut charts = PlantCharts::new(vec![chart], GroupDetailMessage::Chart);
+                charts.y_label = format!("{} [{}]", sensor, sensor.get_unit());
+                charts.range = self.sensor_range(*sensor);
+                charts
+            })
+            .collect();
+    }
+
+    /// Handles the messages of the group detail view
+    ///
+    /// Going back and opening a plant are handled by the home page.
+    pub fn update(&mut self, message: GroupDetailMessage) -> Command<GroupDetailMessage> {
+        match message {
+            GroupDetailMessage::Load => {
+                self.status = String::from("Gruppe wird geladen...");
+                let client = API_CLIENT.get().unwrap();
+                return Command::batch(vec![
+                    Command::perform(
+                        client.clone().get_group(self.group.id),
+                        GroupDetailMessage::GroupLoaded,
+                    ),
+                    Command::perform(
+                        client.clone().get_groups(),
+                        GroupDetailMessage::GroupsLoaded,
+                    ),
+                    Command::perform(client.clone().get_all_plants(), GroupDetailMessage::Loaded),
+                ]);
+            }
+            GroupDetailMessage::GroupLoaded(result) => match result {
+                Ok(group) => {
+                    self.group = group;
+                    self.update_charts();
+                }
+                Err(e) => self.error = format!("Gruppe nicht geladen: {}", e),
+            },
+            GroupDetailMessage::GroupsLoaded(result) => match result {
+                Ok(groups) => self.set_groups(groups),
+                Err(e) => self.error = format!("Gruppen nicht geladen: {}", e),
+            },
+            GroupDetailMessage::Loaded(result) => {
+                self.status = String::new();
+                match result {
+                    Ok(plants) => {
+                        self.set_members(&plants);
+                        return self.load_readings();
+                    }
+                    Err(e) => self.error = format!("Pflanzen nicht geladen: {}", e),
+                }
+            }
+            GroupDetailMessage::ReadingsLoaded(graphs) => self.set_readings(graphs),
+            GroupDetailMessage::Back
+            | GroupDetailMessage::OpenPlant(_)
+            | GroupDetailMessage::Chart => {}
+            GroupDetailMessage::Target(message) => self.target.update(message),
+            GroupDetailMessage::SwitchAggregation(aggregation) => {
+                self.aggregation = aggregation;
+                self.update_charts();
+            }
+            GroupDetailMessage::Move(id) => {
+                let (group_id, group_name) = match self.target.validate() {
+                    Some(group_id) => (
+                        group_id,
+                        self.target
+                            .selected
+                            .as_ref()
+                            .map(|group| group.name.clone())
+                            .unwrap_or_default(),
+                    ),
+                    None => return Command::none(),
+                };
+                // The update replaces the plant, so all of its metadata is sent again
+                let plant = match self.members.iter().find(|(member, _)| *member == id) {
+                    Some((_, plant)) => plant.clone(),
+                    None => return Command::none(),
+                };
+                return Command::perform(
+                    API_CLIENT.get().unwrap().clone().create_plant(
+                        plant,
+                        group_id,
+                        Some(id.clone()),
+                    ),
+                    move |result| GroupDetailMessage::Moved(id, group_name, result),
+                );
+            }
+            GroupDetailMessage::Moved(id, group_name, result) => {
+                let name = self
+                    .members
+                    .iter()
+                    .find(|(member, _)| *member == id)
+                    .map(|(_, plant)| plant.name.clone())
+                    .unwrap_or_default();
+                match result {
+                    Ok(()) => {
+                        self.status = format!("„{}“ nach „{}“ verschoben", name, group_name);
+                        self.error = String::new();
+                        self.members.retain(|(member, _)| *member != id);
+                        for (_, members) in self.series.iter_mut() {
+                            members.retain(|(member, _, _)| *member != id);
+                        }
+                        self.update_charts();
+                    }
+                    Err(e) => {
+                        self.status = String::new();
+                        self.error = format!("„{}“ wurde nicht verschoben: {}", name, e);
+                    }
+                }
+            }
+        }
+        Command::none()
+    }
+
+    /// Returns the metadata and members of the group next to the charts
+    pub fn view(&self) -> Element<'_, GroupDetailMessage> {
+        let mut info = Column::new()
+            .spacing(10)
+            .push(
+                Row::new()
+                    .spacing(20)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Button::new(Text::new("Zurück").size(TEXT_SIZE))
+                            .style(theme::Button::Secondary)
+                            .on_press(GroupDetailMessage::Back),
+                    )
+                    .push(Text::new(&self.group.name).size(TEXT_SIZE + 10)),
+            )
+            .push(Text::new(&self.group.description).size(TEXT_SIZE));
+        info = info.push(Text::new("Pflegehinweise").size(TEXT_SIZE));
+        if self.group.careTips.is_empty() {
+            info = info.push(Text::new("Keine Pflegehinweise").size(TEXT_SIZE - 8));
+        }
+        for tip in &self.group.careTips {
+            info = info.push(Text::new(format!("• {}", tip)).size(TEXT_SIZE - 8));
+        }
+        info = info.push(Text::new("Zielbereiche").size(TEXT_SIZE));
+        for sensor in Sensortypes::iter() {
+            let range = match self.sensor_range(sensor) {
+                Some(range) => format_range(sensor, range),
+                None => String::from("nicht festgelegt"),
+            };
+            info = info.push(Text::new(format!("{}: {}", sensor, range)).size(TEXT_SIZE - 8));
+        }
+
+        let mut members = Column::new()
+            .spacing(10)
+            .push(Text::new(format!("Pflanzen ({})", self.members.len())).size(TEXT_SIZE))
+            .push(Text::new("Verschieben nach").size(TEXT_SIZE - 8))
+            .push(self.target.view().map(GroupDetailMessage::Target));
+        if self.members.is_empty() && self.status.is_empty() {
+            members = members.push(Text::new("Die Gruppe enthält keine Pflanzen").size(TEXT_SIZE));
+        }
+        for (id, plant) in &self.members {
+            let mut move_button = Button::new(Text::new("Verschieben").size(TEXT_SIZE));
+            if self.target.selected.is_some() {
+                move_button = move_button.on_press(GroupDetailMessage::Move(id.clone()));
+            }
+            members = members.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(iced::Alignment::Center)
+                    .push(
+                        Column::new()
+                            .width(Length::Fill)
+                            .push(Text::new(&plant.name).size(TEXT_SIZE))
+                            .push(
+                                Text::new(format!("{} · {}", plant.species, plant.location))
+                                    .size(TEXT_SIZE - 8),
+                            ),
+                    )
+                    .push(
+                        Button::new(Text::new("Öffnen").size(TEXT_SIZE))
+                            .on_press(GroupDetailMessage::OpenPlant(id.clone())),
+                    )
+                    .push(move_button),
+            );
+        }
+        if !self.status.is_empty() {
+            members = members.push(Text::new(&self.status).size(TEXT_SIZE - 8));
+        }
+        if !self.error.is_empty() {
+            members = members.push(
+                Text::new(&self.error)
+                    .size(TEXT_SIZE - 8)
+                    .style(URGENT_COLOR),
+            );
+        }
+
+        let mut charts = Column::new().spacing(10).push(
+            Row::new()
+                .spacing(20)
+                .align_items(iced::Alignment::Center)
+                .push(Text::new("Mittelwert und Spanne der Pflanzen").size(TEXT_SIZE))
+                .push(
+                    pick_list(
+                        &AGGREGATIONS[..],
+                        Some(self.aggregation),
+                        GroupDetailMessage::SwitchAggregation,
+                    )
+                    .text_size(TEXT_SIZE),
+                ),
+        );
+        if self.charts.is_empty() {
+            charts = charts.push(
+                Text::new("Keine Messwerte der Pflanzen im Zeitraum")
+                    .size(TEXT_SIZE)
+                    .style(URGENT_COLOR),
+            );
+        }
+        for chart in &self.charts {
+            charts = charts.push(
+                Container::new(ChartWidget::new(chart.clone()))
+                    .style(theme::Container::Custom(Box::new(MyStylesheet)))
+                    .width(Length::Fill)
+                    .height(Length::from(CHART_HEIGHT)),
+            );
+        }
+
+        scrollable::Scrollable::new(
+            Row::new()
+                .spacing(20)
+                .push(info.push(members).width(FillPortion(1)))
+                .push(charts.width(FillPortion(2))),
+        )
+        .height(Length::Fill)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Tz;
+
+    const UTC: TimeZoneChoice = TimeZoneChoice::Zone(Tz::UTC);
+
+    fn plant(id: &str, group: i32) -> (String, String, RequestResult<PlantMetadata>) {
+        let name = format!("Pflanze {}", id);
+        let metadata = PlantMetadata {
+            name: name.clone(),
+            plantGroup: PlantGroupMetadata {
+                id: group,
+                name: format!("Gruppe {}", group),
+                careTips: vec!["Gießen".to_string()],
+                ..PlantGroupMetadata::default()
+            },
+            ..PlantMetadata::default()
+        };
+        (id.to_string(), name, Ok(metadata))
+    }
+
+    fn groups() -> Vec<GroupOption> {
+        (1..=3)
+            .map(|id| GroupOption {
+                id,
+                name: format!("Gruppe {}", id),
+                description: String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_member_spread() {
+        let first = (vec![0, 30, 60], vec![10, 30, 50]);
+        let second = (vec![10, 70, 130], vec![40, 60, 80]);
+        let series = [
+            (first.0.as_slice(), first.1.as_slice()),
+            (second.0.as_slice(), second.1.as_slice()),
+        ];
+        let buckets = member_spread(&series, Aggregation::Stuendlich, UTC);
+        assert_eq!(
+            buckets,
+            vec![
+                Bucket {
+                    start: 0,
+                    mean: 30,
+                    min: 20,
+                    max: 40
+                },
+                Bucket {
+                    start: 60,
+                    mean: 55,
+                    min: 50,
+                    max: 60
+                },
+                // Only one plant reported, there is no spread
+                Bucket {
+                    start: 120,
+                    mean: 80,
+                    min: 80,
+                    max: 80
+                },
+            ]
+        );
+        assert!(member_spread(&[], Aggregation::Stuendlich, UTC).is_empty());
+    }
+
+    #[test]
+    fn test_members() {
+        let mut plants = vec![plant("1", 1), plant("2", 2), plant("3", 1)];
+        plants.push((
+            "4".to_string(),
+            "Pflanze 4".to_string(),
+            Err("500 Internal Server Error".to_string()),
+        ));
+        let (members, failed) = group_members(1, &plants);
+        assert_eq!(
+            members.iter().map(|(id, _)| id).collect_vec(),
+            vec!["1", "3"]
+        );
+        assert_eq!(failed, 1);
+        let (members, _) = group_members(3, &plants);
+        assert!(members.is_empty());
+
+        let mut detail = GroupDetail::new(groups()[0].clone(), Default::default());
+        detail.set_groups(groups());
+        detail.set_members(&plants);
+        assert_eq!(detail.members.len(), 2);
+        assert_eq!(detail.error, "1 Pflanze konnte nicht geladen werden");
+        // A plant can not be moved into its own group
+        assert!(detail.target.groups.iter().all(|group| group.id != 1));
+        assert_eq!(detail.target.groups.len(), 2);
+    }
+
+    #[test]
+    fn test_empty_group_shows_its_metadata() {
+        let mut detail = GroupDetail::new(groups()[2].clone(), Default::default());
+        let mut group = PlantGroupMetadata {
+            id: 3,
+            name: "Gruppe 3".to_string(),
+            careTips: vec!["Halbschatten".to_string()],
+            ..PlantGroupMetadata::default()
+        };
+        group.sensorRanges[0].min = 3000;
+        group.sensorRanges[0].max = 6000;
+        let _ = detail.update(GroupDetailMessage::GroupLoaded(Ok(group)));
+        detail.set_members(&[plant("1", 1)]);
+        assert!(detail.members.is_empty());
+        assert_eq!(detail.group.careTips, vec!["Halbschatten"]);
+        assert_eq!(
+            detail.sensor_range(Sensortypes::Feuchtigkeit),
+            Some((3000, 6000))
+        );
+
+        let _ = detail.update(GroupDetailMessage::GroupLoaded(Err(
+            "404 Not Found".to_string()
+        )));
+        assert_eq!(detail.error, "Gruppe nicht geladen: 404 Not Found");
+    }
+
+    /// Returns the detail of the first group with two plants and their temperatures
+    fn detail() -> GroupDetail {
+        let mut detail = GroupDetail::new(groups()[0].clone(), Default::default());
+        detail.set_groups(groups());
+        detail.set_members(&[plant("1", 1), plant("3", 1)]);
+        let reading = |value: i32| GraphData {
+            values: vec![value],
+            timestamps: vec!["1970-01-01T00:00:00Z".to_string()],
+        };
+        let _ = detail.update(GroupDetailMessage::ReadingsLoaded(vec![(
+            Sensortypes::Temperatur,
+            vec![
+                (reading(20), "1".to_string()),
+                (reading(24), "3".to_string()),
+            ],
+        )]));
+        detail
+    }
+
+    #[test]
+    fn test_move_requires_target_and_removes_member() {
+        let mut detail = detail();
+        assert_eq!(detail.charts[0].charts[0].y, vec![22]);
+
+        let _ = detail.update(GroupDetailMessage::Move("1".to_string()));
+        assert_eq!(detail.target.error, "Bitte eine Gruppe auswählen");
+
+        let _ = detail.update(GroupDetailMessage::Moved(
+            "1".to_string(),
+            "Gruppe 2".to_string(),
+            Ok(()),
+        ));
+        assert_eq!(detail.members.len(), 1);
+        assert_eq!(detail.status, "„Pflanze 1“ nach „Gruppe 2“ verschoben");
+        assert_eq!(detail.charts[0].charts[0].y, vec![24]);
+    }
+
+    #[test]
+    fn test_failed_move_keeps_member() {
+        let mut detail = detail();
+        let _ = detail.update(GroupDetailMessage::Moved(
+            "1".to_string(),
+            "Gruppe 2".to_string(),
+            Err("403 Forbidden".to_string()),
+        ));
+        assert_eq!(detail.members.len(), 2);
+        assert!(detail.status.is_empty());
+        assert_eq!(
+            detail.error,
+            "„Pflanze 1“ wurde nicht verschoben: 403 Forbidden"
+        );
+        assert_eq!(detail.charts[0].charts[0].y, vec![22]);
+    }
+}
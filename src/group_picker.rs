@@ -0,0 +1,223 @@
+use crate::detail::URGENT_COLOR;
+use crate::plant_table::fuzzy_score;
+use crate::TEXT_SIZE;
+use iced::widget::{pick_list, Column, Text, TextInput};
+use iced::Element;
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+/// A group that can be picked
+///
+/// Fields:
+/// * `id` - The id of the group
+/// * `name` - The name of the group
+/// * `description` - The description of the group, empty if the server sent none
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GroupOption {
+    pub id: i32,
+    pub name: String,
+    pub description: String,
+}
+
+impl Display for GroupOption {
+    /// Returns the name of the group with its description
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.description.is_empty() {
+            write!(f, "{}", self.name)
+        } else {
+            write!(f, "{} – {}", self.name, self.description)
+        }
+    }
+}
+
+/// Contains all messages of the group picker
+#[derive(Debug, Clone, PartialEq)]
+pub enum GroupPickerMessage {
+    /// Updates the search that filters the groups
+    Search(String),
+    /// Selects the group
+    Selected(GroupOption),
+}
+
+/// A pick list of the groups of the server that can be filtered by a search
+///
+/// Fields:
+/// * `groups` - The groups of the server, sorted by name
+/// * `search` - The search that filters the groups
+/// * `selected` - The selected group
+/// * `error` - The reason the selection was rejected
+#[derive(Debug, Clone, Default)]
+pub struct GroupPicker {
+    pub groups: Vec<GroupOption>,
+    pub search: String,
+    pub selected: Option<GroupOption>,
+    pub error: String,
+}
+
+impl GroupPicker {
+    /// Creates a picker without groups
+    pub fn new() -> Self {
+        GroupPicker::default()
+    }
+
+    /// Updates the known groups, keeping the selection if the group still exists
+    pub fn set_groups(&mut self, mut groups: Vec<GroupOption>) {
+        groups.sort_by(|a, b| a.name.cmp(&b.name));
+        groups.dedup();
+        if let Some(selected) = &self.selected {
+            self.selected = groups.iter().find(|group| group.id == selected.id).cloned();
+        }
+        self.groups = groups;
+    }
+
+    /// Selects the group with the given id, nothing is selected if it is unknown
+    pub fn select_id(&mut self, id: i32) {
+        self.selected = self.groups.iter().find(|group| group.id == id).cloned();
+        self.error = String::new();
+    }
+
+    /// Returns the groups matching the search, the best match first
+    ///
+    /// The selected group is always included, so the pick list can show it.
+    pub fn options(&self) -> Vec<GroupOption> {
+        self.groups
+            .iter()
+            .filter_map(|group| {
+                let score = fuzzy_score(&self.search, &group.to_string())
+                    .or_else(|| (self.selected.as_ref() == Some(group)).then_some(0))?;
+                Some((score, group))
+            })
+            .sorted_by(|(a, _), (b, _)| b.cmp(a))
+            .map(|(_, group)| group.clone())
+            .collect()
+    }
+
+    /// Returns the id of the selected group, or sets the error if none or an unknown one is selected
+    pub fn validate(&mut self) -> Option<i32> {
+        let result = match &self.selected {
+            None => Err("Bitte eine Gruppe auswählen".to_string()),
+            Some(selected) if !self.groups.contains(selected) => {
+                Err(format!("Die Gruppe {} existiert nicht mehr", selected.name))
+            }
+            Some(selected) => Ok(selected.id),
+        };
+        match result {
+            Ok(id) => {
+                self.error = String::new();
+                Some(id)
+            }
+            Err(e) => {
+                self.error = e;
+                None
+            }
+        }
+    }
+
+    /// Handles the messages of the picker
+    pub fn update(&mut self, message: GroupPickerMessage) {
+        match message {
+            GroupPickerMessage::Search(search) => self.search = search,
+            GroupPickerMessage::Selected(group) => {
+                self.selected = Some(group);
+                self.error = String::new();
+            }
+        }
+    }
+
+    /// Returns the search above the pick list and the error below it
+    pub fn view(&self) -> Element<'_, GroupPickerMessage> {
+        let mut column = Column::new()
+            .spacing(5)
+            .push(
+                TextInput::new("Gruppe suchen", &self.search)
+                    .size(TEXT_SIZE)
+                    .on_input(GroupPickerMessage::Search),
+            )
+            .push(
+                pick_list(
+                    self.options(),
+                    self.selected.clone(),
+                    GroupPickerMessage::Selected,
+                )
+                .placeholder("Gruppe auswählen")
+                .text_size(TEXT_SIZE),
+            );
+        if self.groups.is_empty() {
+            column = column.push(Text::new("Keine Gruppen vorhanden").size(TEXT_SIZE));
+        }
+        if !self.error.is_empty() {
+            column = column.push(Text::new(&self.error).size(TEXT_SIZE).style(URGENT_COLOR));
+        }
+        column.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn group(id: i32, name: &str, description: &str) -> GroupOption {
+        GroupOption {
+            id,
+            name: name.to_string(),
+            description: description.to_string(),
+        }
+    }
+
+    fn picker() -> GroupPicker {
+        let mut picker = GroupPicker::new();
+        picker.set_groups(vec![
+            group(2, "Zimmerpflanzen", "Wohnzimmer und Flur"),
+            group(1, "Kräuter", "Küchenfenster"),
+            group(3, "Kakteen", ""),
+        ]);
+        picker
+    }
+
+    #[test]
+    fn test_group_option_display() {
+        assert_eq!(
+            group(1, "Kräuter", "Küchenfenster").to_string(),
+            "Kräuter – Küchenfenster"
+        );
+        assert_eq!(group(3, "Kakteen", "").to_string(), "Kakteen");
+    }
+
+    #[test]
+    fn test_search_filters_names_and_descriptions() {
+        let mut picker = picker();
+        assert_eq!(
+            picker.options().iter().map(|group| group.id).collect_vec(),
+            vec![3, 1, 2]
+        );
+        picker.update(GroupPickerMessage::Search("küche".to_string()));
+        assert_eq!(picker.options(), vec![group(1, "Kräuter", "Küchenfenster")]);
+        // The selected group stays in the list
+        picker.select_id(2);
+        assert_eq!(picker.options().len(), 2);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut picker = picker();
+        assert_eq!(picker.validate(), None);
+        assert_eq!(picker.error, "Bitte eine Gruppe auswählen");
+        picker.update(GroupPickerMessage::Selected(group(
+            1,
+            "Kräuter",
+            "Küchenfenster",
+        )));
+        assert_eq!(picker.validate(), Some(1));
+        assert!(picker.error.is_empty());
+        // A group that is not on the server can not be sent
+        picker.update(GroupPickerMessage::Selected(group(9, "Alt", "")));
+        assert_eq!(picker.validate(), None);
+        assert_eq!(picker.error, "Die Gruppe Alt existiert nicht mehr");
+        // Deleted groups are unselected
+        picker.select_id(1);
+        picker.set_groups(vec![group(2, "Zimmerpflanzen", "")]);
+        assert_eq!(picker.selected, None);
+        picker.select_id(7);
+        assert_eq!(picker.selected, None);
+    }
+}
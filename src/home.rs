@@ -1,20 +1,40 @@
-use crate::detail::Sensortypes;
+use crate::aggregation::Aggregation;
+use crate::care_tips::{CareTipsEditor, CareTipsMessage};
+use crate::confirm::{dismiss, undo_timeout, Confirmation, UndoToast};
+use crate::detail::{Sensortypes, URGENT_COLOR, URGENT_HOURS};
+use crate::forecast::{most_urgent, Forecast, FIT_MINUTES};
+use crate::forms::{Form, GroupField, PlantField};
 use crate::graphs::PlantCharts;
+use crate::group_detail::{group_members, GroupDetail, GroupDetailMessage};
+use crate::group_picker::{GroupOption, GroupPicker, GroupPickerMessage};
+use crate::plant_cards::PlantCard;
+use crate::range_editor::{RangeEditor, RangeEditorMessage};
+use crate::range_selector::{RangeMessage, RangePreset, RangeSelector};
 
-use crate::requests::{GraphData, PlantGroupMetadata, PlantMetadata};
+use crate::requests::{GraphData, PlantGroupMetadata, PlantMetadata, PlantReadings, RequestResult};
+use crate::schedule::{TaskForm, TaskFormMessage, TaskTarget};
+use crate::sensor_health::{check_plant, SensorIssue};
+use crate::timezone::to_minutes;
+use chrono::{Duration, SecondsFormat, Utc};
 
 use crate::{Icon, Message, MyStylesheet, Tab, API_CLIENT, TEXT_SIZE};
 use iced::alignment::{Horizontal, Vertical};
 use iced::futures::TryFutureExt;
-use iced::widget::{Button, Column, Container, Row, Text, TextInput};
+use iced::widget::{pick_list, scrollable, Button, Column, Container, Row, Text};
 use iced::{theme, Command, Element, Length, Renderer};
 use iced_aw::{Card, Modal, TabLabel};
 use iced_core::Length::FillPortion;
-use itertools::{enumerate, Itertools};
+use itertools::Itertools;
 use log::info;
 use plotters_iced::ChartWidget;
 use std::collections::HashMap;
 
+/// The loaded group together with the ids, names and metadata of all plants
+type GroupWithPlants = (
+    PlantGroupMetadata,
+    Vec<(String, String, RequestResult<PlantMetadata>)>,
+);
+
 #[derive(Debug, Clone)]
 /// The message of the home page
 pub enum HomeMessage {
@@ -28,56 +48,148 @@ pub enum HomeMessage {
     CancelButtonPressed,
     /// The ok button was pressed, the data is sent to the server
     OkButtonPressed,
+    /// The new plant or group was created, or creating it failed
+    Created(RequestResult<()>),
     /// An empty message to do nothing
     Plant,
-    /// Deletes the selected group
+    /// Asks for the confirmation of deleting the selected group
     DeleteGroup,
+    /// The group with the given id and all plants were loaded for the confirmation
+    GroupMembersLoaded(i32, RequestResult<GroupWithPlants>),
+    /// Deletes the group after it was confirmed
+    ConfirmDeleteGroup,
+    /// Closes the confirmation without deleting the group
+    CancelDeleteGroup,
+    /// The group was deleted, or deleting it failed
+    GroupDeleted(RequestResult<()>),
+    /// Recreates the deleted group from its cached metadata
+    UndoDeleteGroup,
+    /// The group with the given name was recreated, or recreating it failed
+    GroupRestored(String, RequestResult<()>),
+    /// Hides the undo toast of the group with the given id
+    DismissUndo(String),
     /// Refresh the page
     Refresh,
+    /// The groups were loaded, or loading them failed
+    GroupsLoaded(RequestResult<Vec<GroupOption>>),
+    /// The ids and names of all plants were loaded, or loading them failed
+    PlantsLoaded(RequestResult<Vec<(String, String)>>),
     /// Change the graphs to the selected sensor
     SwitchGraph(Sensortypes),
-    /// Updates the variable to match the input
-    FieldUpdated(u8, String),
+    /// Updates a field of the form of the new plant
+    PlantForm(PlantField, String),
+    /// Updates a field of the form of the new group
+    GroupForm(GroupField, String),
+    /// Handles the messages of the care tips editor of the new plant
+    PlantTips(CareTipsMessage),
+    /// Handles the messages of the care tips editor of the new group
+    GroupTips(CareTipsMessage),
+    /// Handles the messages of the range editor of the new group
+    Ranges(RangeEditorMessage),
+    /// Toggles the logarithmic y axis for the light sensor
+    ToggleLogScale,
+    /// Switches the aggregation of the graphs
+    SwitchAggregation(Aggregation),
+    /// Exports the graphs as CSV file
+    ExportCsv,
+    /// Handles the messages of the range selector
+    Range(RangeMessage),
+    /// Handles the messages of the care tasks of the selected group
+    Tasks(TaskFormMessage),
+    /// The recent readings of all plants were loaded for the forecasts and sensor checks
+    ReadingsLoaded(Vec<(String, String, RequestResult<PlantReadings>)>),
+    /// Opens the plant on the detail page
+    OpenPlant(String),
+    /// Handles the messages of the group picker of the new plant
+    NewPlantGroup(GroupPickerMessage),
+    /// Handles the messages of the group picker of the group to delete
+    GroupToDelete(GroupPickerMessage),
+    /// Opens the detail view of the group with the given id
+    OpenGroup(String),
+    /// Handles the messages of the group detail view
+    GroupDetail(GroupDetailMessage),
 }
 
+/// The number of plants listed as needing water soonest
+const URGENT_PLANTS: usize = 5;
+/// The number of plant cards in one row of the grid
+const CARDS_PER_ROW: usize = 4;
+/// The height of the grid of plant cards in pixels
+const CARDS_HEIGHT: u16 = 330;
+
 /// The home page
 ///
 /// Fields:
-/// - `timerange`: The timerange of the graphs
-/// - `selected_group`: The selected group
+/// - `range`: The selector of the timerange of the graphs
+/// - `group_to_delete`: The picker of the group to delete
 /// - `group_name_id`: The names and ids of the groups
 /// - `show_modal`: If the modal is shown
 /// - `modal_is_plant`: If the modal is for a plant
+/// - `saving`: If the new plant or group is being sent, the modal stays open until it is created
+/// - `modal_error`: The reason the new plant or group was not created
 /// - `new_plant`: The data of the new plant
 /// - `new_group`: The data of the new group
-/// - `additionalCareTips`: The additional care tips of the new plant only for the plant
-/// - `sensor_border`: The border of the sensor
-/// - `careTips`: The care tips of the new plant for the plant and the group
-/// - `group`: The group of the new plant
+/// - `plant_form`: The inputs of the new plant
+/// - `group_form`: The inputs of the new group
+/// - `plant_tips`: The care tips of the new plant
+/// - `group_tips`: The care tips of the new group
+/// - `ranges`: The sensor ranges of the new group
+/// - `new_plant_group`: The picker of the group of the new plant
 /// - `charts`: The charts of all groups for the selected sensor
 /// - `active_sensor`: The active sensor
 /// - `group_ids`: The ids of the groups
 /// - `id_names`: The ids and names of the plants
 ///  - `group_names`: The names of the groups
 /// - `sensor_data`: The graph data of the sensors if the sensor was already selected
+/// - `log_scale`: If the light sensor is displayed with a logarithmic y axis
+/// - `aggregation`: The aggregation of the graphs
+/// - `export_message`: The result of the last CSV export
+/// - `tasks`: The recurring care tasks of the group chosen in the group list
+/// - `urgent`: The ids, names and forecasts of the plants that need water soonest
+/// - `sensor_issues`: The ids, names and sensor issues of the plants with faulty sensors, or
+///   the reason their check failed
+/// - `cards`: The status cards of all plants
+/// - `readings`: The recent readings of all plants with their groups
+/// - `confirm_delete`: The confirmation of deleting a group, with the metadata of the group
+/// - `deleting`: The metadata of the group that is being deleted
+/// - `undo`: The toast to restore the last deleted group
+/// - `group_detail`: The detail view of a group, shown instead of the dashboard while it is open
+/// - `error`: The reason the groups or plants could not be loaded
 pub(crate) struct HomePage {
-    timerange: (String, String),
-    selected_group: String,
+    range: RangeSelector,
+    group_to_delete: GroupPicker,
     group_name_id: Vec<(String, String)>,
     show_modal: bool,
     modal_is_plant: bool,
+    saving: bool,
+    modal_error: String,
     new_plant: PlantMetadata,
     new_group: PlantGroupMetadata,
-    additionalCareTips: String,
-    sensor_border: Vec<String>,
-    careTips: String,
-    group: String,
+    plant_form: Form<PlantField>,
+    group_form: Form<GroupField>,
+    plant_tips: CareTipsEditor,
+    group_tips: CareTipsEditor,
+    ranges: RangeEditor,
+    new_plant_group: GroupPicker,
     charts: PlantCharts<HomeMessage>,
     active_sensor: Sensortypes,
     group_ids: Vec<String>,
     id_names: Vec<(String, String)>,
     group_names: Vec<String>,
     sensor_data: HashMap<String, (Vec<GraphData>, Vec<String>)>,
+    log_scale: bool,
+    aggregation: Aggregation,
+    export_message: String,
+    pub tasks: TaskForm,
+    urgent: Vec<(String, String, Forecast)>,
+    sensor_issues: Vec<(String, String, RequestResult<Vec<SensorIssue>>)>,
+    cards: Vec<PlantCard>,
+    readings: Vec<PlantReadings>,
+    confirm_delete: Option<Confirmation<PlantGroupMetadata>>,
+    deleting: Option<PlantGroupMetadata>,
+    undo: Option<UndoToast<PlantGroupMetadata>>,
+    group_detail: Option<GroupDetail>,
+    error: String,
 }
 
 impl HomePage {
@@ -86,33 +198,40 @@ impl HomePage {
         let vec_chart = Vec::new();
         let charts = PlantCharts::new(vec_chart, HomeMessage::Plant);
         HomePage {
-            timerange: (
-                "2019-01-01T00:00:00.000Z".to_string(),
-                chrono::offset::Local::now()
-                    .format("%Y-%m-%dT%H:%M:%S.000Z")
-                    .to_string(),
-            ),
-            selected_group: String::new(),
+            range: RangeSelector::new(RangePreset::Gesamt),
+            group_to_delete: GroupPicker::new(),
             group_name_id: Vec::new(),
             show_modal: false,
             modal_is_plant: true,
+            saving: false,
+            modal_error: String::new(),
             new_plant: PlantMetadata::default(),
-            additionalCareTips: String::new(),
-            careTips: String::new(),
+            plant_form: Form::new(),
+            group_form: Form::new(),
+            plant_tips: CareTipsEditor::new(),
+            group_tips: CareTipsEditor::new(),
+            ranges: RangeEditor::new(),
             charts,
             group_names: Vec::new(),
             id_names: Vec::new(),
             active_sensor: Sensortypes::Luftfeuchtigkeit,
-            group: String::new(),
+            new_plant_group: GroupPicker::new(),
             group_ids: Vec::new(),
             new_group: PlantGroupMetadata::default(),
-            sensor_border: vec![
-                "".to_string(),
-                "".to_string(),
-                "".to_string(),
-                "".to_string(),
-            ],
             sensor_data: HashMap::new(),
+            log_scale: false,
+            aggregation: Aggregation::default(),
+            export_message: String::new(),
+            tasks: TaskForm::new(),
+            urgent: Vec::new(),
+            sensor_issues: Vec::new(),
+            cards: Vec::new(),
+            readings: Vec::new(),
+            confirm_delete: None,
+            deleting: None,
+            undo: None,
+            group_detail: None,
+            error: String::new(),
         }
     }
 
@@ -120,33 +239,230 @@ impl HomePage {
     pub fn update(&mut self, message: HomeMessage) -> Command<HomeMessage> {
         match message {
             HomeMessage::DeleteGroup => {
+                if self.group_to_delete.validate().is_none() {
+                    return Command::none();
+                }
+                let group = match &self.group_to_delete.selected {
+                    Some(group) => group.clone(),
+                    None => return Command::none(),
+                };
+                // The group and its plants are loaded first, so the count and the cached metadata
+                // are complete
+                let title = format!("Gruppe „{}“ löschen?", group.name);
+                let metadata = PlantGroupMetadata {
+                    id: group.id,
+                    name: group.name.clone(),
+                    description: group.description.clone(),
+                    ..PlantGroupMetadata::default()
+                };
+                self.confirm_delete = Some(Confirmation::pending(
+                    metadata,
+                    title,
+                    vec!["Die Gruppe und ihre Pflanzen werden geladen...".to_string()],
+                ));
+                let client = API_CLIENT.get().unwrap().clone();
+                let id = group.id;
+                return Command::perform(
+                    async move {
+                        let metadata = client.clone().get_group(id).await?;
+                        let plants = client.get_all_plants().await?;
+                        Ok::<_, String>((metadata, plants))
+                    },
+                    move |result| HomeMessage::GroupMembersLoaded(id, result),
+                );
+            }
+            HomeMessage::GroupMembersLoaded(id, result) => {
+                let confirmation = match &mut self.confirm_delete {
+                    Some(confirmation) if confirmation.item.id == id => confirmation,
+                    _ => return Command::none(),
+                };
+                match result {
+                    Ok((group, plants)) => *confirmation = group_confirmation(&group, &plants),
+                    Err(e) => {
+                        confirmation.consequences = vec![format!(
+                            "Die Gruppe und ihre Pflanzen wurden nicht geladen: {}",
+                            e
+                        )]
+                    }
+                }
+            }
+            HomeMessage::ConfirmDeleteGroup => {
+                let group = match self.confirm_delete.take() {
+                    Some(confirmation) if confirmation.ready => confirmation.item,
+                    confirmation => {
+                        self.confirm_delete = confirmation;
+                        return Command::none();
+                    }
+                };
+                let group_id = group.id.to_string();
+                self.deleting = Some(group);
                 return Command::perform(
                     API_CLIENT
                         .get()
                         .unwrap()
                         .clone()
-                        .delete_group(self.selected_group.clone())
-                        .unwrap_or_else(|e| {
-                            info!("Error: {}", e);
-                        }),
-                    |_| HomeMessage::Refresh,
-                )
+                        .delete_group(group_id)
+                        .map_err(|e| e.to_string()),
+                    HomeMessage::GroupDeleted,
+                );
+            }
+            HomeMessage::CancelDeleteGroup => self.confirm_delete = None,
+            // A failed deletion offers no undo, the group still exists
+            HomeMessage::GroupDeleted(Err(e)) => {
+                if let Some(group) = self.deleting.take() {
+                    self.group_to_delete.error =
+                        format!("Gruppe „{}“ wurde nicht gelöscht: {}", group.name, e);
+                }
+            }
+            HomeMessage::GroupDeleted(Ok(())) => {
+                if let Some(group) = self.deleting.take() {
+                    let key = group.id.to_string();
+                    let text = format!("Gruppe „{}“ gelöscht", group.name);
+                    self.undo = Some(UndoToast::new(key.clone(), group, text));
+                    return Command::batch(vec![
+                        self.update(HomeMessage::Refresh),
+                        undo_timeout(HomeMessage::DismissUndo(key)),
+                    ]);
+                }
+                return self.update(HomeMessage::Refresh);
+            }
+            HomeMessage::UndoDeleteGroup => {
+                if let Some(toast) = self.undo.take() {
+                    let name = toast.item.name.clone();
+                    return Command::perform(
+                        API_CLIENT
+                            .get()
+                            .unwrap()
+                            .clone()
+                            .create_group(toast.item, None),
+                        move |result| HomeMessage::GroupRestored(name, result),
+                    );
+                }
+            }
+            HomeMessage::GroupRestored(name, Err(e)) => {
+                self.group_to_delete.error =
+                    format!("Gruppe „{}“ wurde nicht wiederhergestellt: {}", name, e);
             }
+            HomeMessage::GroupRestored(_, Ok(())) => return self.update(HomeMessage::Refresh),
+            HomeMessage::DismissUndo(key) => dismiss(&mut self.undo, &key),
             HomeMessage::Plant => (),
             HomeMessage::Refresh => {
-                self.group_name_id = API_CLIENT
-                    .get()
-                    .unwrap()
-                    .clone()
-                    .get_all_group_ids_names()
-                    .unwrap();
-                self.id_names = API_CLIENT
-                    .get()
-                    .unwrap()
-                    .clone()
-                    .get_all_plant_ids_names()
-                    .unwrap();
+                self.error = String::new();
+                let client = API_CLIENT.get().unwrap();
+                return Command::batch(vec![
+                    Command::perform(client.clone().get_groups(), HomeMessage::GroupsLoaded),
+                    Command::perform(
+                        client.clone().get_plant_overview(),
+                        HomeMessage::PlantsLoaded,
+                    ),
+                ]);
+            }
+            HomeMessage::GroupsLoaded(Ok(groups)) => {
+                self.group_name_id = groups
+                    .iter()
+                    .map(|group| (group.id.to_string(), group.name.clone()))
+                    .collect();
                 self.group_ids = self.group_name_id.iter().map(|x| x.0.clone()).collect_vec();
+                self.new_plant_group.set_groups(groups.clone());
+                if let Some(detail) = &mut self.group_detail {
+                    detail.set_groups(groups.clone());
+                }
+                self.group_to_delete.set_groups(groups);
+            }
+            HomeMessage::GroupsLoaded(Err(e)) => {
+                self.error = format!("Gruppen nicht geladen: {}", e);
+            }
+            HomeMessage::PlantsLoaded(Ok(id_names)) => {
+                self.id_names = id_names;
+                // The cards show their readings as soon as they are loaded
+                self.cards = self
+                    .id_names
+                    .iter()
+                    .map(|(id, name)| PlantCard::new(id.clone(), name.clone(), None))
+                    .collect();
+                let now = Utc::now();
+                let time_range = (
+                    (now - Duration::minutes(FIT_MINUTES as i64))
+                        .to_rfc3339_opts(SecondsFormat::Millis, true),
+                    now.to_rfc3339_opts(SecondsFormat::Millis, true),
+                );
+                return Command::perform(
+                    API_CLIENT
+                        .get()
+                        .unwrap()
+                        .clone()
+                        .get_plant_readings(self.id_names.clone(), time_range),
+                    HomeMessage::ReadingsLoaded,
+                );
+            }
+            HomeMessage::PlantsLoaded(Err(e)) => {
+                self.error = format!("Pflanzen nicht geladen: {}", e);
+            }
+            HomeMessage::ReadingsLoaded(results) => {
+                let now = to_minutes(&Utc::now());
+                // A plant whose readings failed to load is listed as a failed check
+                self.sensor_issues = results
+                    .iter()
+                    .map(|(id, name, result)| {
+                        let issues = match result {
+                            Ok(plant) => Ok(check_plant(plant, now)),
+                            Err(e) => Err(format!("Prüfung fehlgeschlagen: {}", e)),
+                        };
+                        (id.clone(), name.clone(), issues)
+                    })
+                    .filter(|(_, _, issues)| !matches!(issues, Ok(issues) if issues.is_empty()))
+                    .collect();
+                let plants: Vec<PlantReadings> = results
+                    .into_iter()
+                    .filter_map(|(_, _, result)| result.ok())
+                    .collect();
+                self.urgent = most_urgent(&plants, URGENT_PLANTS);
+                self.cards = self
+                    .id_names
+                    .iter()
+                    .map(|(id, name)| {
+                        let readings = plants.iter().find(|plant| plant.id == *id);
+                        PlantCard::new(id.clone(), name.clone(), readings)
+                    })
+                    .collect();
+                self.readings = plants;
+            }
+            // Handled by the application, which switches to the detail page
+            HomeMessage::OpenPlant(_) => {}
+            HomeMessage::NewPlantGroup(message) => self.new_plant_group.update(message),
+            HomeMessage::GroupToDelete(message) => self.group_to_delete.update(message),
+            HomeMessage::OpenGroup(id) => {
+                // The view loads the groups and plants itself, the name is shown until then
+                if let Some((group_id, name)) = self
+                    .group_name_id
+                    .iter()
+                    .find(|group| group.0 == id)
+                    .and_then(|(id, name)| Some((id.parse().ok()?, name.clone())))
+                {
+                    let group = GroupOption {
+                        id: group_id,
+                        name,
+                        description: String::new(),
+                    };
+                    let mut detail = GroupDetail::new(group, self.range.timerange());
+                    let command = detail
+                        .update(GroupDetailMessage::Load)
+                        .map(HomeMessage::GroupDetail);
+                    self.group_detail = Some(detail);
+                    return command;
+                }
+            }
+            HomeMessage::GroupDetail(GroupDetailMessage::Back) => self.group_detail = None,
+            HomeMessage::GroupDetail(message) => {
+                // Moving a plant changes the groups of the dashboard as well
+                let moved = matches!(message, GroupDetailMessage::Moved(_, _, Ok(_)));
+                if let Some(detail) = &mut self.group_detail {
+                    let command = detail.update(message).map(HomeMessage::GroupDetail);
+                    if moved {
+                        return Command::batch(vec![command, self.update(HomeMessage::Refresh)]);
+                    }
+                    return command;
+                }
             }
             HomeMessage::SwitchGraph(sensortypes) => {
                 self.active_sensor = sensortypes;
@@ -163,7 +479,7 @@ impl HomePage {
                             self.group_ids.clone(),
                             false,
                             sensortypes.get_name(),
-                            self.timerange.clone(),
+                            self.range.timerange(),
                         )
                         .unwrap();
                     // Collect names from id_names if id is in data
@@ -197,110 +513,192 @@ impl HomePage {
                     sensortypes,
                     self.group_names.clone(),
                 );
+                self.charts.log_scale = self.log_scale && sensortypes == Sensortypes::Licht;
+                self.charts.aggregation = self.aggregation;
+            }
+            HomeMessage::ToggleLogScale => {
+                self.log_scale = !self.log_scale;
+                self.charts.log_scale = self.log_scale && self.active_sensor == Sensortypes::Licht;
+            }
+            HomeMessage::Range(message) => {
+                // The cached sensor data belongs to the previous timerange
+                if self.range.update(message) && !self.group_ids.is_empty() {
+                    self.sensor_data.clear();
+                    return self.update(HomeMessage::SwitchGraph(self.active_sensor));
+                }
+            }
+            // Handled by the application, which owns the schedule
+            HomeMessage::Tasks(_) => {}
+            HomeMessage::SwitchAggregation(aggregation) => {
+                self.aggregation = aggregation;
+                self.charts.aggregation = aggregation;
+            }
+            HomeMessage::ExportCsv => {
+                self.export_message = match self.charts.export_csv("gruppen") {
+                    Ok(path) => format!("Exportiert nach {}", path.display()),
+                    Err(e) => format!("Export fehlgeschlagen: {}", e),
+                };
             }
             HomeMessage::OpenModalPlant => {
                 self.modal_is_plant = true;
                 self.show_modal = true;
+                self.modal_error = String::new();
             }
             HomeMessage::OpenModalGroup => {
                 self.modal_is_plant = false;
                 self.show_modal = true;
+                self.modal_error = String::new();
             }
-            HomeMessage::FieldUpdated(index, value) => match index {
-                0 => {
-                    self.new_plant.name = value;
-                }
-                1 => {
-                    self.new_plant.description = value;
-                }
-                2 => {
-                    self.new_plant.location = value;
-                }
-                3 => {
-                    self.additionalCareTips = value;
-                }
-                4 => {
-                    self.new_plant.species = value;
-                }
-                5 => {
-                    self.group = value;
-                }
-                6 => {
-                    self.new_group.name = value;
-                }
-                7 => {
-                    self.new_group.description = value;
-                }
-                8 => {
-                    self.careTips = value;
-                }
-                9 => {
-                    self.sensor_border[0] = value;
-                }
-                10 => {
-                    self.sensor_border[1] = value;
-                }
-                11 => {
-                    self.sensor_border[2] = value;
-                }
-                12 => {
-                    self.selected_group = value;
-                }
-                13 => {
-                    self.sensor_border[3] = value;
-                }
-                _ => (),
-            },
+            HomeMessage::PlantForm(field, value) => self.plant_form.set(field, value),
+            HomeMessage::GroupForm(field, value) => self.group_form.set(field, value),
+            HomeMessage::PlantTips(message) => self.plant_tips.update(message),
+            HomeMessage::GroupTips(message) => self.group_tips.update(message),
+            HomeMessage::Ranges(message) => self.ranges.update(message),
             HomeMessage::CloseModal => self.show_modal = false,
             HomeMessage::CancelButtonPressed => self.show_modal = false,
             HomeMessage::OkButtonPressed => {
                 return if self.modal_is_plant {
-                    self.new_plant.additionalCareTips = self
-                        .additionalCareTips
-                        .split(';')
-                        .map(String::from)
-                        .collect();
-                    self.show_modal = false;
+                    let group_id = match self.new_plant_group.validate() {
+                        Some(group_id)
+                            if self.plant_form.is_valid() && self.plant_tips.is_valid() =>
+                        {
+                            group_id
+                        }
+                        _ => {
+                            self.plant_form.touch_all();
+                            return Command::none();
+                        }
+                    };
+                    self.plant_form.apply_to(&mut self.new_plant);
+                    self.plant_tips
+                        .apply_to(&mut self.new_plant.additionalCareTips);
+                    self.saving = true;
+                    self.modal_error = String::new();
                     Command::perform(
                         API_CLIENT.get().unwrap().clone().create_plant(
                             self.new_plant.clone(),
-                            self.group.clone().parse().unwrap_or_default(),
+                            group_id,
                             None,
                         ),
-                        |_| HomeMessage::Refresh,
+                        HomeMessage::Created,
                     )
                 } else {
-                    self.new_group.careTips = self.careTips.split(';').map(String::from).collect();
-                    for (i, sensor) in enumerate(self.new_group.sensorRanges.iter_mut()) {
-                        sensor.max = self.sensor_border.clone()[i]
-                            .split(';')
-                            .next()
-                            .unwrap()
-                            .parse()
-                            .unwrap_or(0);
-                        sensor.min = self.sensor_border.clone()[i]
-                            .split(';')
-                            .last()
-                            .unwrap()
-                            .parse()
-                            .unwrap_or(0);
+                    if !self.group_form.is_valid()
+                        || !self.group_tips.is_valid()
+                        || !self.ranges.is_valid()
+                    {
+                        self.group_form.touch_all();
+                        self.ranges.touch_all();
+                        return Command::none();
                     }
-                    self.show_modal = false;
+                    self.group_form.apply_to(&mut self.new_group);
+                    self.group_tips.apply_to(&mut self.new_group.careTips);
+                    self.ranges.apply_to(&mut self.new_group);
+                    self.saving = true;
+                    self.modal_error = String::new();
                     Command::perform(
                         API_CLIENT
                             .get()
                             .unwrap()
                             .clone()
                             .create_group(self.new_group.clone(), None),
-                        |_| HomeMessage::Refresh,
+                        HomeMessage::Created,
                     )
                 };
             }
+            HomeMessage::Created(Ok(())) => {
+                self.saving = false;
+                self.show_modal = false;
+                if self.modal_is_plant {
+                    self.plant_form = Form::new();
+                    self.plant_tips = CareTipsEditor::new();
+                } else {
+                    self.group_form = Form::new();
+                    self.group_tips = CareTipsEditor::new();
+                    self.ranges = RangeEditor::new();
+                }
+                return self.update(HomeMessage::Refresh);
+            }
+            HomeMessage::Created(Err(e)) => {
+                // The modal keeps the inputs, so they can be corrected and sent again
+                self.saving = false;
+                self.modal_error = if self.modal_is_plant {
+                    format!("Pflanze wurde nicht erstellt: {}", e)
+                } else {
+                    format!("Gruppe wurde nicht erstellt: {}", e)
+                };
+            }
         }
         Command::none()
     }
 }
 
+/// Returns the reason the new plant or group was not created, in the color of urgent hints
+fn modal_error(error: &str) -> Text<'_> {
+    Text::new(error).size(TEXT_SIZE).style(URGENT_COLOR)
+}
+
+/// Returns the Ok button of the modals, it is disabled until the form is valid and while the
+/// new plant or group is sent
+fn ok_button<'a>(enabled: bool) -> Button<'a, HomeMessage> {
+    let button = Button::new(
+        Text::new("Ok")
+            .size(TEXT_SIZE)
+            .horizontal_alignment(Horizontal::Center),
+    )
+    .width(Length::Fill);
+    if enabled {
+        button.on_press(HomeMessage::OkButtonPressed)
+    } else {
+        button
+    }
+}
+
+/// Returns the confirmation of deleting the group, naming how many plants it contains
+///
+/// The plants are given with their ids, names and metadata. The metadata of the group is cached
+/// with its care tips and ranges, so they can be restored.
+fn group_confirmation(
+    group: &PlantGroupMetadata,
+    plants: &[(String, String, RequestResult<PlantMetadata>)],
+) -> Confirmation<PlantGroupMetadata> {
+    let (members, failed) = group_members(group.id, plants);
+    let mut consequences = match members.len() {
+        0 => vec!["Die Gruppe enthält keine Pflanzen".to_string()],
+        count => vec![
+            if count == 1 {
+                "Die Gruppe enthält 1 Pflanze".to_string()
+            } else {
+                format!("Die Gruppe enthält {} Pflanzen", count)
+            },
+            "Rückgängig stellt nur die Gruppe wieder her, die Pflanzen müssen neu zugeordnet werden"
+                .to_string(),
+        ],
+    };
+    match failed {
+        0 => {}
+        1 => consequences
+            .push("1 Pflanze wurde nicht geladen, die Gruppe kann weitere enthalten".to_string()),
+        failed => consequences.push(format!(
+            "{} Pflanzen wurden nicht geladen, die Gruppe kann weitere enthalten",
+            failed
+        )),
+    }
+    Confirmation::new(
+        group.clone(),
+        format!("Gruppe „{}“ löschen?", group.name),
+        consequences,
+    )
+}
+
+/// Forwards the messages of the group detail view, opening a plant is handled by the application
+fn group_detail_message(message: GroupDetailMessage) -> HomeMessage {
+    match message {
+        GroupDetailMessage::OpenPlant(id) => HomeMessage::OpenPlant(id),
+        message => HomeMessage::GroupDetail(message),
+    }
+}
+
 impl Tab for HomePage {
     type Message = Message;
 
@@ -330,52 +728,14 @@ impl Tab for HomePage {
                                 .size(TEXT_SIZE)
                                 .horizontal_alignment(Horizontal::Center),
                             Column::new()
-                                .push(
-                                    TextInput::new("Pflanzenname", &self.new_plant.name)
-                                        .size(TEXT_SIZE)
-                                        .on_input(|input| HomeMessage::FieldUpdated(0, input)),
-                                )
-                                .spacing(20)
-                                .push(
-                                    TextInput::new(
-                                        "Beschreibung der Pflanze",
-                                        &self.new_plant.description,
-                                    )
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| HomeMessage::FieldUpdated(1, input)),
-                                )
-                                .spacing(20)
-                                .push(
-                                    TextInput::new(
-                                        "Position der Pflanze",
-                                        &self.new_plant.location,
-                                    )
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| HomeMessage::FieldUpdated(2, input)),
-                                )
-                                .spacing(20)
-                                .push(Text::new("Die Hinweise werden mit einem ';' getrennt").size(TEXT_SIZE))
-                                .spacing(20)
-                                .push(
-                                    TextInput::new("Pflegehinweise", &self.additionalCareTips)
-                                        .size(TEXT_SIZE)
-                                        .on_input(|input| HomeMessage::FieldUpdated(3, input)),
-                                )
+                                .push(self.plant_form.view(HomeMessage::PlantForm))
                                 .spacing(20)
-                                .push(
-                                    TextInput::new("Pflanzenspecies", &self.new_plant.species)
-                                        .size(TEXT_SIZE)
-                                        .on_input(|input| HomeMessage::FieldUpdated(4, input)),
-                                )
+                                .push(self.plant_tips.view().map(HomeMessage::PlantTips))
+                                .push(Text::new("Pflanzengruppe").size(TEXT_SIZE))
                                 .spacing(20)
-                                .push(Text::new("Die Pflanzengruppen ID kann auf der Startseite eingesehen werden").size(TEXT_SIZE))
+                                .push(self.new_plant_group.view().map(HomeMessage::NewPlantGroup))
                                 .spacing(20)
-                                .push(
-                                    TextInput::new("PflanzengruppenID", &self.group)
-                                        .size(TEXT_SIZE)
-                                        .on_input(|input| HomeMessage::FieldUpdated(5, input)),
-                                )
-                                .spacing(20),
+                                .push(modal_error(&self.modal_error)),
                         )
                         .foot(
                             Row::new()
@@ -391,17 +751,14 @@ impl Tab for HomePage {
                                     .width(Length::Fill)
                                     .on_press(HomeMessage::CancelButtonPressed),
                                 )
-                                .push(
-                                    Button::new(
-                                        Text::new("Ok")
-                                            .size(TEXT_SIZE)
-                                            .horizontal_alignment(Horizontal::Center),
-                                    )
-                                    .width(Length::Fill)
-                                    .on_press(HomeMessage::OkButtonPressed),
-                                ),
+                                .push(ok_button(
+                                    !self.saving
+                                        && self.plant_form.is_valid()
+                                        && self.plant_tips.is_valid()
+                                        && self.new_plant_group.selected.is_some(),
+                                )),
                         )
-                        .max_width(300.0)
+                        .max_width(500.0)
                         .on_close(HomeMessage::CloseModal)
                         .into()
                     })
@@ -423,57 +780,11 @@ impl Tab for HomePage {
                                 .size(TEXT_SIZE)
                                 .horizontal_alignment(Horizontal::Center),
                             Column::new()
-                                .push(
-                                    TextInput::new("Gruppennamen", &self.new_group.name)
-                                        .size(TEXT_SIZE)
-                                        .on_input(|input| HomeMessage::FieldUpdated(6, input)),
-                                )
-                                .spacing(20)
-                                .push(
-                                    TextInput::new(
-                                        "Beschreibung der Gruppe",
-                                        &self.new_group.description,
-                                    )
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| HomeMessage::FieldUpdated(7, input)),
-                                )
-                                .spacing(20)
-                                .push(
-                                    TextInput::new("Pflegehinweise", &self.careTips)
-                                        .size(TEXT_SIZE)
-                                        .on_input(|input| HomeMessage::FieldUpdated(8, input)),
-                                )
                                 .spacing(20)
-                                .push(
-                                    Text::new("Die Grenzen werden so eingetragen: max;min")
-                                        .size(TEXT_SIZE),
-                                )
-                                .push(
-                                    TextInput::new(
-                                        "Feuchtigkeitsgrenzwerte",
-                                        &self.sensor_border[0],
-                                    )
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| HomeMessage::FieldUpdated(9, input)),
-                                )
-                                .push(
-                                    TextInput::new(
-                                        "Luftfeuchtigkeitsgrenzwerte",
-                                        &self.sensor_border[1],
-                                    )
-                                    .size(TEXT_SIZE)
-                                    .on_input(|input| HomeMessage::FieldUpdated(10, input)),
-                                )
-                                .push(
-                                    TextInput::new("Temperaturgrenzwerte", &self.sensor_border[2])
-                                        .size(TEXT_SIZE)
-                                        .on_input(|input| HomeMessage::FieldUpdated(11, input)),
-                                )
-                                .push(
-                                    TextInput::new("Lichtgrenzwerte", &self.sensor_border[3])
-                                        .size(TEXT_SIZE)
-                                        .on_input(|input| HomeMessage::FieldUpdated(13, input)),
-                                ),
+                                .push(self.group_form.view(HomeMessage::GroupForm))
+                                .push(self.group_tips.view().map(HomeMessage::GroupTips))
+                                .push(self.ranges.view().map(HomeMessage::Ranges))
+                                .push(modal_error(&self.modal_error)),
                         )
                         .foot(
                             Row::new()
@@ -489,17 +800,14 @@ impl Tab for HomePage {
                                     .width(Length::Fill)
                                     .on_press(HomeMessage::CancelButtonPressed),
                                 )
-                                .push(
-                                    Button::new(
-                                        Text::new("Ok")
-                                            .size(TEXT_SIZE)
-                                            .horizontal_alignment(Horizontal::Center),
-                                    )
-                                    .width(Length::Fill)
-                                    .on_press(HomeMessage::OkButtonPressed),
-                                ),
+                                .push(ok_button(
+                                    !self.saving
+                                        && self.group_form.is_valid()
+                                        && self.group_tips.is_valid()
+                                        && self.ranges.is_valid(),
+                                )),
                         )
-                        .max_width(300.0)
+                        .max_width(500.0)
                         .on_close(HomeMessage::CloseModal)
                         .into()
                     })
@@ -508,6 +816,14 @@ impl Tab for HomePage {
                     .into();
                 content.map(Message::Home)
             }
+        } else if let Some(detail) = &self.group_detail {
+            let content: Element<'_, HomeMessage> =
+                Container::new(detail.view().map(group_detail_message))
+                    .width(Length::Fill)
+                    .height(Length::Fill)
+                    .padding(10)
+                    .into();
+            content.map(Message::Home)
         } else {
             let chart_widget = ChartWidget::new(self.charts.clone());
             let container: Container<HomeMessage> = Container::new(chart_widget)
@@ -541,6 +857,36 @@ impl Tab for HomePage {
                     Button::new(Text::new("Licht").size(TEXT_SIZE))
                         .on_press(HomeMessage::SwitchGraph(Sensortypes::Licht)),
                 );
+            let row = if self.active_sensor == Sensortypes::Licht {
+                row.spacing(20).push(
+                    Button::new(
+                        Text::new(if self.log_scale {
+                            "Lineare Skala"
+                        } else {
+                            "Logarithmische Skala"
+                        })
+                        .size(TEXT_SIZE),
+                    )
+                    .on_press(HomeMessage::ToggleLogScale),
+                )
+            } else {
+                row
+            };
+            let row = row
+                .spacing(20)
+                .push(
+                    pick_list(
+                        &Aggregation::ALL[..],
+                        Some(self.aggregation),
+                        HomeMessage::SwitchAggregation,
+                    )
+                    .text_size(TEXT_SIZE),
+                )
+                .spacing(20)
+                .push(
+                    Button::new(Text::new("CSV exportieren").size(TEXT_SIZE))
+                        .on_press(HomeMessage::ExportCsv),
+                );
             let lower_row: Row<HomeMessage, Renderer> = Row::new()
                 .push(
                     Button::new(Text::new("Neue Pflanze erstellen").size(TEXT_SIZE))
@@ -550,8 +896,26 @@ impl Tab for HomePage {
                 .push(
                     Button::new(Text::new("Gruppe hinzufügen").size(TEXT_SIZE))
                         .on_press(HomeMessage::OpenModalGroup),
-                );
-            let column = Column::new().push(row).push(container).push(lower_row);
+                )
+                .spacing(20)
+                .push(Text::new(&self.export_message).size(TEXT_SIZE));
+            let mut cards = Column::new().spacing(10);
+            for chunk in self.cards.chunks(CARDS_PER_ROW) {
+                cards = cards.push(chunk.iter().fold(Row::new().spacing(10), |row, card| {
+                    row.push(card.view(HomeMessage::OpenPlant(card.id.clone())))
+                }));
+            }
+            let column = Column::new()
+                .push(
+                    scrollable::Scrollable::new(cards)
+                        .width(Length::Fill)
+                        .height(Length::from(CARDS_HEIGHT)),
+                )
+                .push(row)
+                .push(container)
+                .push(self.range.view().map(HomeMessage::Range))
+                .push(lower_row)
+                .spacing(10);
             let mut group_column: Column<HomeMessage> = Column::new().push(
                 Text::new("Gruppen")
                     .size(TEXT_SIZE)
@@ -559,31 +923,115 @@ impl Tab for HomePage {
             );
             for group in self.group_name_id.iter() {
                 group_column = group_column.push(
-                    Text::new(format!("{}: {}", group.0, group.1))
-                        .size(TEXT_SIZE)
-                        .horizontal_alignment(Horizontal::Center),
+                    Row::new()
+                        .push(
+                            Text::new(format!("{}: {}", group.0, group.1))
+                                .size(TEXT_SIZE)
+                                .horizontal_alignment(Horizontal::Center),
+                        )
+                        .push(
+                            Button::new(Text::new("Details").size(TEXT_SIZE))
+                                .on_press(HomeMessage::OpenGroup(group.0.clone())),
+                        )
+                        .push(
+                            Button::new(Text::new("Pflegeplan").size(TEXT_SIZE)).on_press(
+                                HomeMessage::Tasks(TaskFormMessage::Select(
+                                    TaskTarget::Group(group.0.clone()),
+                                    group.1.clone(),
+                                )),
+                            ),
+                        )
+                        .spacing(10)
+                        .align_items(iced::Alignment::Center),
                 );
             }
             let delete_row = Row::new()
-                .push(
-                    TextInput::new("GruppenId", &self.selected_group)
-                        .size(TEXT_SIZE)
-                        .on_input(|input| HomeMessage::FieldUpdated(12, input)),
-                )
+                .push(self.group_to_delete.view().map(HomeMessage::GroupToDelete))
                 .push(
                     Button::new(Text::new("Gruppe löschen").size(TEXT_SIZE))
                         .on_press(HomeMessage::DeleteGroup),
                 );
             group_column = group_column.push(delete_row);
+            if !self.urgent.is_empty() {
+                let now = to_minutes(&Utc::now());
+                group_column = group_column.push(Text::new("Braucht bald Wasser").size(TEXT_SIZE));
+                for (id, name, forecast) in &self.urgent {
+                    let text =
+                        Text::new(format!("{}: {}", name, forecast.badge(now))).size(TEXT_SIZE);
+                    group_column = group_column.push(
+                        Row::new()
+                            .push(if forecast.hours_until(now) < URGENT_HOURS {
+                                text.style(URGENT_COLOR)
+                            } else {
+                                text
+                            })
+                            .push(
+                                Button::new(Text::new("Öffnen").size(TEXT_SIZE))
+                                    .on_press(HomeMessage::OpenPlant(id.clone())),
+                            )
+                            .spacing(10)
+                            .align_items(iced::Alignment::Center),
+                    );
+                }
+            }
+            if !self.sensor_issues.is_empty() {
+                group_column = group_column.push(
+                    Text::new("Sensorprobleme")
+                        .size(TEXT_SIZE)
+                        .style(URGENT_COLOR),
+                );
+                for (id, name, issues) in &self.sensor_issues {
+                    group_column = group_column.push(
+                        Row::new()
+                            .push(Text::new(name).size(TEXT_SIZE))
+                            .push(
+                                Button::new(Text::new("Öffnen").size(TEXT_SIZE))
+                                    .on_press(HomeMessage::OpenPlant(id.clone())),
+                            )
+                            .spacing(10)
+                            .align_items(iced::Alignment::Center),
+                    );
+                    match issues {
+                        Ok(issues) => {
+                            for issue in issues {
+                                group_column = group_column
+                                    .push(Text::new(issue.description()).size(TEXT_SIZE));
+                            }
+                        }
+                        Err(e) => group_column = group_column.push(Text::new(e).size(TEXT_SIZE)),
+                    }
+                }
+            }
+            if self.tasks.target.is_some() {
+                group_column = group_column.push(self.tasks.view().map(HomeMessage::Tasks));
+            }
             let row = Row::new()
                 .push(group_column.width(FillPortion(1)))
                 .push(column.width(FillPortion(3)));
-            let content: Element<'_, HomeMessage> = Container::new(row)
+            let mut page = Column::new().spacing(10);
+            if !self.error.is_empty() {
+                page = page.push(Text::new(&self.error).size(TEXT_SIZE).style(URGENT_COLOR));
+            }
+            if let Some(toast) = &self.undo {
+                page = page.push(toast.view(
+                    HomeMessage::UndoDeleteGroup,
+                    HomeMessage::DismissUndo(toast.key.clone()),
+                ));
+            }
+            let content: Element<'_, HomeMessage> = Container::new(page.push(row))
                 .width(Length::Fill)
                 .height(Length::Fill)
                 .align_x(Horizontal::Center)
                 .align_y(Vertical::Center)
                 .into();
+            let content = match &self.confirm_delete {
+                Some(confirmation) => confirmation.view(
+                    content,
+                    HomeMessage::ConfirmDeleteGroup,
+                    HomeMessage::CancelDeleteGroup,
+                ),
+                None => content,
+            };
             content.map(Message::Home)
         }
     }
@@ -596,9 +1044,12 @@ mod tests {
     fn test_home_page_creation() {
         let page = HomePage::new();
 
-        assert_eq!(page.timerange.0, "2019-01-01T00:00:00.000Z".to_string());
-        assert_eq!(page.show_modal, false);
-        assert_eq!(page.modal_is_plant, true);
+        assert_eq!(
+            page.range.timerange().0,
+            "2019-01-01T00:00:00.000Z".to_string()
+        );
+        assert!(!page.show_modal);
+        assert!(page.modal_is_plant);
         assert_eq!(page.new_plant, PlantMetadata::default());
         assert_eq!(page.new_group, PlantGroupMetadata::default());
         assert_eq!(page.active_sensor, Sensortypes::Luftfeuchtigkeit);
@@ -607,32 +1058,134 @@ mod tests {
     #[test]
     fn test_field_updated() {
         let mut page = HomePage::new();
-        let index = 0;
         let value = String::from("My plant");
 
-        page.update(HomeMessage::FieldUpdated(index, value.clone()));
+        let _ = page.update(HomeMessage::PlantForm(PlantField::Name, value.clone()));
+
+        assert_eq!(page.plant_form.value(PlantField::Name), value);
+        assert!(page.plant_form.is_valid());
+        let _ = page.update(HomeMessage::Ranges(RangeEditorMessage::Min(
+            Sensortypes::Temperatur,
+            String::from("20"),
+        )));
+        let _ = page.update(HomeMessage::Ranges(RangeEditorMessage::Max(
+            Sensortypes::Temperatur,
+            String::from("10"),
+        )));
+        assert!(!page.ranges.is_valid());
+    }
+
+    #[test]
+    fn test_unknown_group_is_blocked() {
+        let mut page = HomePage::new();
+        let _ = page.update(HomeMessage::OpenModalPlant);
+        let _ = page.update(HomeMessage::PlantForm(
+            PlantField::Name,
+            String::from("My plant"),
+        ));
+
+        // No group is selected, the plant is not sent and the modal stays open
+        let _ = page.update(HomeMessage::OkButtonPressed);
+        assert!(page.show_modal);
+        assert_eq!(page.new_plant_group.error, "Bitte eine Gruppe auswählen");
 
-        assert_eq!(page.new_plant.name, value);
+        let _ = page.update(HomeMessage::DeleteGroup);
+        assert_eq!(page.group_to_delete.error, "Bitte eine Gruppe auswählen");
+        assert!(page.confirm_delete.is_none());
+    }
+
+    #[test]
+    fn test_failed_create_keeps_modal_open() {
+        let mut page = HomePage::new();
+        let _ = page.update(HomeMessage::OpenModalGroup);
+        let _ = page.update(HomeMessage::GroupForm(
+            GroupField::Name,
+            String::from("Kräuter"),
+        ));
+        page.saving = true;
+
+        let _ = page.update(HomeMessage::Created(Err(
+            "400 Bad Request: Name existiert bereits".to_string(),
+        )));
+        assert!(page.show_modal);
+        assert!(!page.saving);
+        assert_eq!(
+            page.modal_error,
+            "Gruppe wurde nicht erstellt: 400 Bad Request: Name existiert bereits"
+        );
+        // The inputs are kept to correct them
+        assert!(page.group_form.is_valid());
+    }
+
+    #[test]
+    fn test_open_and_close_group() {
+        let mut page = HomePage::new();
+        page.group_name_id = vec![("2".to_string(), "Kräuter".to_string())];
+
+        let _ = page.update(HomeMessage::OpenGroup("7".to_string()));
+        assert!(page.group_detail.is_none());
+
+        page.group_detail = Some(GroupDetail::new(
+            GroupOption {
+                id: 2,
+                name: "Kräuter".to_string(),
+                description: String::new(),
+            },
+            page.range.timerange(),
+        ));
+        let _ = page.update(HomeMessage::GroupDetail(GroupDetailMessage::Back));
+        assert!(page.group_detail.is_none());
     }
 
     #[test]
     fn test_open_modal_plant() {
         let mut page = HomePage::new();
 
-        page.update(HomeMessage::OpenModalPlant);
+        let _ = page.update(HomeMessage::OpenModalPlant);
 
-        assert_eq!(page.show_modal, true);
-        assert_eq!(page.modal_is_plant, true);
+        assert!(page.show_modal);
+        assert!(page.modal_is_plant);
     }
 
     #[test]
     fn test_open_modal_group() {
         let mut page = HomePage::new();
 
-        page.update(HomeMessage::OpenModalGroup);
+        let _ = page.update(HomeMessage::OpenModalGroup);
+
+        assert!(page.show_modal);
+        assert!(!page.modal_is_plant);
+    }
+
+    #[test]
+    fn test_toggle_log_scale() {
+        let mut page = HomePage::new();
+        page.active_sensor = Sensortypes::Licht;
+
+        let _ = page.update(HomeMessage::ToggleLogScale);
+
+        assert!(page.log_scale);
+        assert!(page.charts.log_scale);
+    }
+
+    #[test]
+    fn test_switch_aggregation() {
+        let mut page = HomePage::new();
+
+        let _ = page.update(HomeMessage::SwitchAggregation(Aggregation::Woechentlich));
 
-        assert_eq!(page.show_modal, true);
-        assert_eq!(page.modal_is_plant, false);
+        assert_eq!(page.aggregation, Aggregation::Woechentlich);
+        assert_eq!(page.charts.aggregation, Aggregation::Woechentlich);
+    }
+
+    #[test]
+    fn test_range_preset() {
+        let mut page = HomePage::new();
+
+        let _ = page.update(HomeMessage::Range(RangeMessage::Preset(RangePreset::Monat)));
+
+        assert_eq!(page.range.preset, Some(RangePreset::Monat));
+        assert_eq!(page.range.to - page.range.from, chrono::Duration::days(30));
     }
 
     #[test]
@@ -640,8 +1193,199 @@ mod tests {
         let mut page = HomePage::new();
         page.show_modal = true;
 
-        page.update(HomeMessage::CloseModal);
+        let _ = page.update(HomeMessage::CloseModal);
+
+        assert!(!page.show_modal);
+    }
+
+    #[test]
+    fn test_readings_loaded_flags_sensor_issues() {
+        let mut page = HomePage::new();
+        let plant = PlantReadings {
+            id: "3".to_string(),
+            name: "Basilikum".to_string(),
+            readings: vec![(
+                "temperature".to_string(),
+                GraphData {
+                    values: vec![20, 21, 22],
+                    timestamps: vec![
+                        "2023-05-01T08:00:00Z".to_string(),
+                        "2023-05-01T08:10:00Z".to_string(),
+                        "2023-05-01T08:20:00Z".to_string(),
+                    ],
+                },
+            )],
+            ..PlantReadings::default()
+        };
+
+        let _ = page.update(HomeMessage::ReadingsLoaded(vec![
+            ("3".to_string(), "Basilikum".to_string(), Ok(plant)),
+            (
+                "5".to_string(),
+                "Minze".to_string(),
+                Err("503 Service Unavailable".to_string()),
+            ),
+        ]));
+
+        // The sensor stopped reporting long ago
+        assert_eq!(page.sensor_issues.len(), 2);
+        assert_eq!(page.sensor_issues[0].0, "3");
+        assert_eq!(
+            page.sensor_issues[0].2.as_ref().unwrap()[0].sensor,
+            Sensortypes::Temperatur
+        );
+        // A failed request is no silent sensor, the check itself failed
+        assert_eq!(
+            page.sensor_issues[1].2,
+            Err("Prüfung fehlgeschlagen: 503 Service Unavailable".to_string())
+        );
+        // Without a soil moisture range there is no forecast
+        assert!(page.urgent.is_empty());
+        // Only plants of the plant list get a card
+        assert!(page.cards.is_empty());
+        page.id_names = vec![("3".to_string(), "Basilikum".to_string())];
+        let _ = page.update(HomeMessage::ReadingsLoaded(vec![]));
+        assert_eq!(page.cards.len(), 1);
+        assert!(page.cards[0].latest.is_empty());
+    }
+
+    #[test]
+    fn test_failed_refresh_is_shown() {
+        let mut page = HomePage::new();
+        let _ = page.update(HomeMessage::GroupsLoaded(Err("timeout".to_string())));
+        assert_eq!(page.error, "Gruppen nicht geladen: timeout");
+        let _ = page.update(HomeMessage::PlantsLoaded(Err(
+            "500 Internal Server Error".to_string()
+        )));
+        assert_eq!(
+            page.error,
+            "Pflanzen nicht geladen: 500 Internal Server Error"
+        );
+
+        let _ = page.update(HomeMessage::GroupsLoaded(Ok(vec![GroupOption {
+            id: 4,
+            name: "Kräuter".to_string(),
+            description: String::new(),
+        }])));
+        assert_eq!(
+            page.group_name_id,
+            vec![("4".to_string(), "Kräuter".to_string())]
+        );
+        assert_eq!(page.group_ids, vec!["4"]);
+    }
+
+    #[test]
+    fn test_group_confirmation_counts_plants() {
+        let group = PlantGroupMetadata {
+            id: 4,
+            name: "Kräuter".to_string(),
+            description: "Küchenfenster".to_string(),
+            careTips: vec!["Sonnig".to_string()],
+            ..PlantGroupMetadata::default()
+        };
+        let mut member = PlantMetadata::default();
+        member.plantGroup.id = 4;
+        let plant = |id: &str, metadata: RequestResult<PlantMetadata>| {
+            (id.to_string(), format!("Pflanze {}", id), metadata)
+        };
+
+        let confirmation = group_confirmation(
+            &group,
+            &[
+                plant("1", Ok(member.clone())),
+                plant("2", Ok(member.clone())),
+                plant("3", Ok(PlantMetadata::default())),
+            ],
+        );
+        assert!(confirmation.ready);
+        assert_eq!(confirmation.title, "Gruppe „Kräuter“ löschen?");
+        assert_eq!(
+            confirmation.consequences[0],
+            "Die Gruppe enthält 2 Pflanzen"
+        );
+        // The care tips of the group are cached for the undo, even without plants
+        assert_eq!(confirmation.item.careTips, vec!["Sonnig"]);
+
+        let empty = group_confirmation(&group, &[]);
+        assert_eq!(
+            empty.consequences,
+            vec!["Die Gruppe enthält keine Pflanzen"]
+        );
+        assert_eq!(empty.item.careTips, vec!["Sonnig"]);
+        assert_eq!(empty.item.description, "Küchenfenster");
+
+        let incomplete = group_confirmation(&group, &[plant("1", Err("timeout".to_string()))]);
+        assert_eq!(
+            incomplete.consequences[1],
+            "1 Pflanze wurde nicht geladen, die Gruppe kann weitere enthalten"
+        );
+    }
+
+    #[test]
+    fn test_failed_group_delete_offers_no_undo() {
+        let mut page = HomePage::new();
+        page.deleting = Some(PlantGroupMetadata {
+            id: 4,
+            name: "Kräuter".to_string(),
+            ..PlantGroupMetadata::default()
+        });
+        let _ = page.update(HomeMessage::GroupDeleted(Err("409 Conflict".to_string())));
+        assert!(page.deleting.is_none());
+        assert!(page.undo.is_none());
+        assert_eq!(
+            page.group_to_delete.error,
+            "Gruppe „Kräuter“ wurde nicht gelöscht: 409 Conflict"
+        );
+    }
+
+    #[test]
+    fn test_failed_group_restore_is_shown() {
+        let mut page = HomePage::new();
+        let _ = page.update(HomeMessage::GroupRestored(
+            "Kräuter".to_string(),
+            Err("500 Internal Server Error".to_string()),
+        ));
+        assert_eq!(
+            page.group_to_delete.error,
+            "Gruppe „Kräuter“ wurde nicht wiederhergestellt: 500 Internal Server Error"
+        );
+    }
+
+    #[test]
+    fn test_group_confirmation_waits_for_members() {
+        let mut page = HomePage::new();
+        page.confirm_delete = Some(Confirmation::pending(
+            PlantGroupMetadata {
+                id: 4,
+                name: "Kräuter".to_string(),
+                ..PlantGroupMetadata::default()
+            },
+            "Gruppe „Kräuter“ löschen?".to_string(),
+            vec!["Die Gruppe und ihre Pflanzen werden geladen...".to_string()],
+        ));
+        // The group is not deleted before its plants are known
+        let _ = page.update(HomeMessage::ConfirmDeleteGroup);
+        assert!(page.deleting.is_none());
+        assert!(page.confirm_delete.is_some());
+
+        let _ = page.update(HomeMessage::GroupMembersLoaded(
+            4,
+            Err("500 Internal Server Error".to_string()),
+        ));
+        let confirmation = page.confirm_delete.as_ref().unwrap();
+        assert!(!confirmation.ready);
+        assert_eq!(
+            confirmation.consequences,
+            vec!["Die Gruppe und ihre Pflanzen wurden nicht geladen: 500 Internal Server Error"]
+        );
 
-        assert_eq!(page.show_modal, false);
+        let group = page.confirm_delete.as_ref().unwrap().item.clone();
+        let _ = page.update(HomeMessage::GroupMembersLoaded(4, Ok((group, vec![]))));
+        let confirmation = page.confirm_delete.as_ref().unwrap();
+        assert!(confirmation.ready);
+        assert_eq!(
+            confirmation.consequences,
+            vec!["Die Gruppe enthält keine Pflanzen"]
+        );
     }
 }
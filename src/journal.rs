@@ -0,0 +1,504 @@
+use crate::graphs::{timestamp_to_minutes, ChartMarker};
+use crate::range_selector::{parse_input, INPUT_FORMAT};
+use crate::requests::RequestResult;
+use crate::storage;
+use crate::timezone::{from_minutes, TimeZoneChoice};
+use crate::{API_CLIENT, TEXT_SIZE};
+use chrono::{DateTime, SecondsFormat, Utc};
+use iced::widget::{pick_list, scrollable, Button, Column, Row, Text, TextInput};
+use iced::Alignment::Center;
+use iced::{Color, Command, Element, Length};
+use plotters::prelude::RGBColor;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+
+/// The name of the file that contains the journals that are stored locally
+const LOCAL_FILE: &str = "journal.json";
+
+/// Contains the kinds of care that can be logged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum CareType {
+    /// The plant was watered
+    #[default]
+    #[serde(rename = "watering")]
+    Giessen,
+    /// The plant was fertilized
+    #[serde(rename = "fertilizing")]
+    Duengen,
+    /// The plant was moved into a new pot
+    #[serde(rename = "repotting")]
+    Umtopfen,
+    /// Any other care
+    #[serde(rename = "other")]
+    Sonstiges,
+}
+
+impl CareType {
+    pub const ALL: [CareType; 4] = [
+        CareType::Giessen,
+        CareType::Duengen,
+        CareType::Umtopfen,
+        CareType::Sonstiges,
+    ];
+
+    /// Returns the unit of the amount, empty if the care has no amount
+    pub fn unit(&self) -> &'static str {
+        match self {
+            CareType::Giessen => "ml",
+            CareType::Duengen => "ml",
+            CareType::Umtopfen => "",
+            CareType::Sonstiges => "",
+        }
+    }
+
+    /// Returns the color of the markers in the charts
+    pub fn get_color(&self) -> RGBColor {
+        match self {
+            CareType::Giessen => RGBColor(30, 144, 255),
+            CareType::Duengen => RGBColor(124, 179, 66),
+            CareType::Umtopfen => RGBColor(141, 110, 99),
+            CareType::Sonstiges => RGBColor(128, 128, 128),
+        }
+    }
+}
+
+impl Display for CareType {
+    /// Returns the name of the care
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CareType::Giessen => write!(f, "Gießen"),
+            CareType::Duengen => write!(f, "Düngen"),
+            CareType::Umtopfen => write!(f, "Umtopfen"),
+            CareType::Sonstiges => write!(f, "Sonstiges"),
+        }
+    }
+}
+
+/// An entry of the care journal of a plant
+///
+/// Fields:
+/// * `kind` - The kind of care
+/// * `timestamp` - The time of the care in UTC, formatted as RFC 3339 like the sensor data
+/// * `amount` - The amount of water or fertilizer, in the unit of the kind
+/// * `note` - A free note
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CareEntry {
+    #[serde(rename = "type")]
+    pub kind: CareType,
+    pub timestamp: String,
+    #[serde(default)]
+    pub amount: Option<f64>,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl CareEntry {
+    /// Creates a new entry at the given time
+    pub fn new(kind: CareType, time: DateTime<Utc>, amount: Option<f64>, note: String) -> Self {
+        CareEntry {
+            kind,
+            timestamp: time.to_rfc3339_opts(SecondsFormat::Secs, true),
+            amount,
+            note,
+        }
+    }
+
+    /// Returns the time of the entry in minutes since the unix epoch
+    pub fn minutes(&self) -> Option<i32> {
+        timestamp_to_minutes(&self.timestamp)
+    }
+
+    /// Returns the amount with its unit, empty if there is no amount
+    pub fn amount_text(&self) -> String {
+        match self.amount {
+            Some(amount) => format!("{} {}", amount, self.kind.unit())
+                .trim()
+                .to_string(),
+            None => String::new(),
+        }
+    }
+}
+
+/// Contains all messages of the care journal
+#[derive(Debug, Clone, PartialEq)]
+pub enum JournalMessage {
+    /// Loads the journal of the plant with the given id
+    Load(String),
+    /// The journal of the plant was loaded, `None` if the server has no journals
+    Loaded(String, RequestResult<Option<Vec<CareEntry>>>),
+    /// Selects the kind of the new entry
+    KindSelected(CareType),
+    /// Updates the time of the new entry
+    TimeChanged(String),
+    /// Updates the amount of the new entry
+    AmountChanged(String),
+    /// Updates the note of the new entry
+    NoteChanged(String),
+    /// Adds the new entry to the journal
+    Add,
+    /// The entry was sent to the server, `false` if the server has no journals
+    Added(CareEntry, RequestResult<bool>),
+}
+
+/// The care journal of the plant on the detail page
+///
+/// Fields:
+/// * `plant_id` - The id of the plant
+/// * `entries` - The entries of the journal, sorted by time
+/// * `on_server` - Indicates if the journal is stored on the server or locally
+/// * `kind` - The kind of the new entry
+/// * `time_input` - The input of the time of the new entry, empty for now
+/// * `amount_input` - The input of the amount of the new entry
+/// * `note_input` - The input of the note of the new entry
+/// * `error` - The error of the inputs or the last request
+/// * `local_path` - The file the local journals are stored in
+pub struct CareJournal {
+    pub plant_id: String,
+    pub entries: Vec<CareEntry>,
+    pub on_server: bool,
+    pub kind: CareType,
+    pub time_input: String,
+    pub amount_input: String,
+    pub note_input: String,
+    pub error: String,
+    local_path: PathBuf,
+}
+
+impl CareJournal {
+    /// Creates an empty journal that is stored in the local data directory
+    pub fn new() -> Self {
+        CareJournal::with_path(storage::data_file(LOCAL_FILE))
+    }
+
+    /// Creates an empty journal that is stored in the given file
+    pub fn with_path(local_path: PathBuf) -> Self {
+        CareJournal {
+            plant_id: String::new(),
+            entries: Vec::new(),
+            on_server: false,
+            kind: CareType::default(),
+            time_input: String::new(),
+            amount_input: String::new(),
+            note_input: String::new(),
+            error: String::new(),
+            local_path,
+        }
+    }
+
+    /// Returns the entries of the plant that are stored locally
+    fn local_entries(&self) -> Vec<CareEntry> {
+        let journals: HashMap<String, Vec<CareEntry>> = storage::load(&self.local_path);
+        journals.get(&self.plant_id).cloned().unwrap_or_default()
+    }
+
+    /// Stores the entry in the local journal of the plant
+    fn store_locally(&mut self, entry: CareEntry) -> Result<(), String> {
+        let mut journals: HashMap<String, Vec<CareEntry>> = storage::load(&self.local_path);
+        journals
+            .entry(self.plant_id.clone())
+            .or_default()
+            .push(entry.clone());
+        storage::save(&self.local_path, &journals).map_err(|e| e.to_string())?;
+        self.push(entry);
+        Ok(())
+    }
+
+    /// Adds the entry to the displayed entries, keeping them sorted by time
+    fn push(&mut self, entry: CareEntry) {
+        self.entries.push(entry);
+        self.entries.sort_by_key(|entry| entry.minutes());
+    }
+
+    /// Creates the new entry from the inputs
+    pub fn entry_from_inputs(&self) -> Result<CareEntry, String> {
+        let time = if self.time_input.trim().is_empty() {
+            Utc::now()
+        } else {
+            parse_input(&self.time_input, TimeZoneChoice::current())
+                .ok_or("Zeit im Format TT.MM.JJJJ HH:MM eingeben")?
+        };
+        let amount = match self.amount_input.trim() {
+            "" => None,
+            amount => match amount.replace(',', ".").parse::<f64>() {
+                Ok(amount) if amount >= 0.0 => Some(amount),
+                _ => return Err("Ungültige Menge".to_string()),
+            },
+        };
+        Ok(CareEntry::new(
+            self.kind,
+            time,
+            amount,
+            self.note_input.trim().to_string(),
+        ))
+    }
+
+    /// Stores the entry on the server if it has journals, otherwise locally
+    pub fn submit(&mut self, entry: CareEntry) -> Command<JournalMessage> {
+        match API_CLIENT.get() {
+            Some(client) if self.on_server => Command::perform(
+                client
+                    .clone()
+                    .add_care_entry(self.plant_id.clone(), entry.clone()),
+                move |result| JournalMessage::Added(entry, result),
+            ),
+            _ => {
+                if let Err(e) = self.store_locally(entry) {
+                    self.error = format!("Speichern fehlgeschlagen: {}", e);
+                }
+                Command::none()
+            }
+        }
+    }
+
+    /// Returns the entries as markers for the charts
+    pub fn markers(&self) -> Vec<ChartMarker> {
+        self.entries
+            .iter()
+            .filter_map(|entry| {
+                Some(ChartMarker {
+                    x: entry.minutes()?,
+                    color: entry.kind.get_color(),
+                    label: entry.kind.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    /// Handles the messages of the care journal
+    pub fn update(&mut self, message: JournalMessage) -> Command<JournalMessage> {
+        match message {
+            JournalMessage::Load(plant_id) => {
+                self.plant_id = plant_id.clone();
+                self.entries = Vec::new();
+                self.on_server = false;
+                self.error = String::new();
+                match API_CLIENT.get() {
+                    Some(client) => {
+                        return Command::perform(
+                            client.clone().get_care_journal(plant_id.clone()),
+                            move |result| JournalMessage::Loaded(plant_id, result),
+                        )
+                    }
+                    None => self.entries = self.local_entries(),
+                }
+            }
+            JournalMessage::Loaded(plant_id, result) => {
+                // The answer belongs to a plant that is no longer displayed
+                if plant_id != self.plant_id {
+                    return Command::none();
+                }
+                match result {
+                    Ok(Some(entries)) => {
+                        self.on_server = true;
+                        self.entries = Vec::new();
+                        entries.into_iter().for_each(|entry| self.push(entry));
+                    }
+                    Ok(None) => {
+                        self.on_server = false;
+                        self.entries = self.local_entries();
+                    }
+                    Err(e) => {
+                        self.on_server = false;
+                        self.entries = self.local_entries();
+                        self.error = format!("Tagebuch nicht geladen, lokal gespeichert: {}", e);
+                    }
+                }
+            }
+            JournalMessage::KindSelected(kind) => self.kind = kind,
+            JournalMessage::TimeChanged(value) => self.time_input = value,
+            JournalMessage::AmountChanged(value) => self.amount_input = value,
+            JournalMessage::NoteChanged(value) => self.note_input = value,
+            JournalMessage::Add => match self.entry_from_inputs() {
+                Ok(entry) => {
+                    self.error = String::new();
+                    self.time_input = String::new();
+                    self.amount_input = String::new();
+                    self.note_input = String::new();
+                    return self.submit(entry);
+                }
+                Err(e) => self.error = e,
+            },
+            JournalMessage::Added(entry, result) => match result {
+                Ok(true) => self.push(entry),
+                Ok(false) => {
+                    self.on_server = false;
+                    return self.submit(entry);
+                }
+                Err(e) => self.error = format!("Eintrag nicht gespeichert: {}", e),
+            },
+        }
+        Command::none()
+    }
+
+    /// Returns the form for new entries and the timeline of the journal, the newest entry first
+    pub fn view(&self) -> Element<'_, JournalMessage> {
+        let form = Row::new()
+            .spacing(10)
+            .align_items(Center)
+            .push(
+                pick_list(
+                    &CareType::ALL[..],
+                    Some(self.kind),
+                    JournalMessage::KindSelected,
+                )
+                .text_size(TEXT_SIZE),
+            )
+            .push(
+                TextInput::new("Jetzt", &self.time_input)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(230))
+                    .on_input(JournalMessage::TimeChanged),
+            )
+            .push(
+                TextInput::new(&format!("Menge {}", self.kind.unit()), &self.amount_input)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(130))
+                    .on_input(JournalMessage::AmountChanged),
+            );
+        let note = Row::new()
+            .spacing(10)
+            .align_items(Center)
+            .push(
+                TextInput::new("Notiz", &self.note_input)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(400))
+                    .on_input(JournalMessage::NoteChanged)
+                    .on_submit(JournalMessage::Add),
+            )
+            .push(
+                Button::new(Text::new("Eintragen").size(TEXT_SIZE)).on_press(JournalMessage::Add),
+            );
+
+        let time_zone = TimeZoneChoice::current();
+        let mut timeline = Column::new().spacing(5);
+        for entry in self.entries.iter().rev() {
+            let RGBColor(r, g, b) = entry.kind.get_color();
+            let time = entry
+                .minutes()
+                .map(|minutes| time_zone.format(&from_minutes(minutes), INPUT_FORMAT))
+                .unwrap_or_else(|| entry.timestamp.clone());
+            timeline = timeline.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(time).size(TEXT_SIZE))
+                    .push(
+                        Text::new(entry.kind.to_string())
+                            .size(TEXT_SIZE)
+                            .style(Color::from_rgb8(r, g, b)),
+                    )
+                    .push(Text::new(entry.amount_text()).size(TEXT_SIZE))
+                    .push(Text::new(&entry.note).size(TEXT_SIZE)),
+            );
+        }
+        if self.entries.is_empty() {
+            timeline = timeline.push(Text::new("Noch keine Einträge").size(TEXT_SIZE));
+        }
+
+        Column::new()
+            .spacing(10)
+            .push(
+                Text::new(if self.on_server {
+                    "Pflegetagebuch:"
+                } else {
+                    "Pflegetagebuch (lokal):"
+                })
+                .size(TEXT_SIZE),
+            )
+            .push(form)
+            .push(note)
+            .push(
+                Text::new(&self.error)
+                    .size(TEXT_SIZE)
+                    .style(Color::from_rgb(1.0, 0.0, 0.0)),
+            )
+            .push(scrollable::Scrollable::new(timeline).height(Length::from(250)))
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn journal(name: &str) -> CareJournal {
+        let path = std::env::temp_dir()
+            .join("plantbuddy_journal_test")
+            .join(format!("{}.json", name));
+        let _ = std::fs::remove_file(&path);
+        let mut journal = CareJournal::with_path(path);
+        journal.plant_id = "1".to_string();
+        journal
+    }
+
+    #[test]
+    fn test_care_entry_json() {
+        let time = Utc.with_ymd_and_hms(2023, 5, 1, 8, 0, 0).unwrap();
+        let entry = CareEntry::new(CareType::Giessen, time, Some(250.0), "Morgens".to_string());
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["type"], "watering");
+        assert_eq!(json["timestamp"], "2023-05-01T08:00:00Z");
+        let parsed: CareEntry =
+            serde_json::from_str(r#"{"type":"repotting","timestamp":"2023-05-01T08:00:00Z"}"#)
+                .unwrap();
+        assert_eq!(parsed.kind, CareType::Umtopfen);
+        assert_eq!(parsed.amount, None);
+        assert_eq!(entry.amount_text(), "250 ml");
+    }
+
+    #[test]
+    fn test_entry_from_inputs() {
+        let mut journal = journal("inputs");
+        journal.amount_input = "1,5".to_string();
+        assert_eq!(journal.entry_from_inputs().unwrap().amount, Some(1.5));
+        journal.amount_input = "viel".to_string();
+        assert_eq!(
+            journal.entry_from_inputs(),
+            Err("Ungültige Menge".to_string())
+        );
+        journal.amount_input = String::new();
+        journal.time_input = "gestern".to_string();
+        assert!(journal.entry_from_inputs().is_err());
+    }
+
+    #[test]
+    fn test_local_journal() {
+        let mut journal = journal("local");
+        let later = CareEntry::new(
+            CareType::Duengen,
+            Utc.with_ymd_and_hms(2023, 5, 2, 8, 0, 0).unwrap(),
+            None,
+            String::new(),
+        );
+        let earlier = CareEntry::new(
+            CareType::Giessen,
+            Utc.with_ymd_and_hms(2023, 5, 1, 8, 0, 0).unwrap(),
+            Some(200.0),
+            String::new(),
+        );
+        journal.store_locally(later.clone()).unwrap();
+        journal.store_locally(earlier.clone()).unwrap();
+        assert_eq!(journal.entries, vec![earlier.clone(), later.clone()]);
+        assert_eq!(journal.local_entries(), vec![later, earlier]);
+        journal.plant_id = "2".to_string();
+        assert!(journal.local_entries().is_empty());
+    }
+
+    #[test]
+    fn test_markers() {
+        let mut journal = journal("markers");
+        journal.push(CareEntry::new(
+            CareType::Giessen,
+            Utc.with_ymd_and_hms(1970, 1, 1, 1, 0, 0).unwrap(),
+            None,
+            String::new(),
+        ));
+        let markers = journal.markers();
+        assert_eq!(markers.len(), 1);
+        assert_eq!(markers[0].x, 60);
+        assert_eq!(markers[0].label, "Gießen");
+        assert_eq!(markers[0].color, CareType::Giessen.get_color());
+    }
+}
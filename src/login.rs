@@ -32,9 +32,9 @@ pub enum PlantBuddyRole {
     NotLoggedIn,
 }
 /// This impl provides a conversion from `PlantBuddyRole` to `u64`.
-impl Into<u64> for PlantBuddyRole {
-    fn into(self) -> u64 {
-        match self {
+impl From<PlantBuddyRole> for u64 {
+    fn from(val: PlantBuddyRole) -> Self {
+        match val {
             PlantBuddyRole::Admin => 0,
             PlantBuddyRole::User => 1,
             PlantBuddyRole::NotLoggedIn => 2,
@@ -259,7 +259,7 @@ mod tests {
         let login_tab = LoginTab::new();
         assert_eq!(login_tab.username, "");
         assert_eq!(login_tab.password, "");
-        assert_eq!(login_tab.login_failed, false);
+        assert!(!login_tab.login_failed);
         assert_eq!(login_tab.last_error_massage, "");
     }
 
@@ -267,18 +267,18 @@ mod tests {
     fn test_login_tab_update_username_changed() {
         let mut login_tab = LoginTab::new();
         let message = LoginMessage::UsernameChanged("test".to_string());
-        login_tab.update(message);
+        let _ = login_tab.update(message);
         assert_eq!(login_tab.username, "test");
-        assert_eq!(login_tab.login_failed, false);
+        assert!(!login_tab.login_failed);
     }
 
     #[test]
     fn test_login_tab_update_password_changed() {
         let mut login_tab = LoginTab::new();
         let message = LoginMessage::PasswordChanged("test".to_string());
-        login_tab.update(message);
+        let _ = login_tab.update(message);
         assert_eq!(login_tab.password, "test");
-        assert_eq!(login_tab.login_failed, false);
+        assert!(!login_tab.login_failed);
     }
 
     #[test]
@@ -289,10 +289,10 @@ mod tests {
         login_tab.login_failed = true;
         login_tab.last_error_massage = "test".to_string();
         let message = LoginMessage::ClearPressed;
-        login_tab.update(message);
+        let _ = login_tab.update(message);
         assert_eq!(login_tab.username, "");
         assert_eq!(login_tab.password, "");
-        assert_eq!(login_tab.login_failed, false);
+        assert!(!login_tab.login_failed);
         assert_eq!(login_tab.last_error_massage, "");
     }
 
@@ -302,7 +302,7 @@ mod tests {
         login_tab.password = "test".to_string();
         let message = LoginMessage::LoginPressed;
         let _command = login_tab.update(message);
-        assert_eq!(login_tab.login_failed, true);
+        assert!(login_tab.login_failed);
         assert_eq!(
             login_tab.last_error_massage,
             "Nutzername oder Passwort ist leer"
@@ -315,7 +315,7 @@ mod tests {
         login_tab.username = "test".to_string();
         let message = LoginMessage::LoginPressed;
         let _command = login_tab.update(message);
-        assert_eq!(login_tab.login_failed, true);
+        assert!(login_tab.login_failed);
         assert_eq!(
             login_tab.last_error_massage,
             "Nutzername oder Passwort ist leer"
@@ -329,7 +329,7 @@ mod tests {
         login_tab.password = "test".to_string();
         let message = LoginMessage::Login(Err("test".to_string()));
         let _command = login_tab.update(message);
-        assert_eq!(login_tab.login_failed, true);
+        assert!(login_tab.login_failed);
         assert_eq!(login_tab.last_error_massage, "Server-Fehler");
     }
 
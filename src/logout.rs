@@ -191,19 +191,19 @@ mod tests {
     fn test_logout_tab_update() {
         let mut tab = LogoutTab::new();
         tab.update(LogoutMessage::OpenModal);
-        assert_eq!(tab.show_modal, true);
+        assert!(tab.show_modal);
         assert_eq!(tab.last_message, Some(LogoutMessage::OpenModal));
 
         tab.update(LogoutMessage::CloseModal);
-        assert_eq!(tab.show_modal, false);
+        assert!(!tab.show_modal);
         assert_eq!(tab.last_message, Some(LogoutMessage::CloseModal));
 
         tab.update(LogoutMessage::CancelButtonPressed);
-        assert_eq!(tab.show_modal, false);
+        assert!(!tab.show_modal);
         assert_eq!(tab.last_message, Some(LogoutMessage::CancelButtonPressed));
 
         tab.update(LogoutMessage::OkButtonPressed);
-        assert_eq!(tab.show_modal, false);
+        assert!(!tab.show_modal);
         assert_eq!(tab.last_message, Some(LogoutMessage::OkButtonPressed));
     }
 }
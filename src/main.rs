@@ -10,22 +10,48 @@
 //!  everal utility functions and constants, such as the Icon enum, which defines the icons used in the
 //! application, and the EXTERNAL_ICON_FONT constant, which defines the font used for the icons.
 
+mod aggregation;
+mod alerts;
+mod bulk;
 mod buttons;
+mod care_tips;
+mod channels;
+mod confirm;
 mod detail;
+mod downsample;
+mod forecast;
+mod forms;
+mod gauge;
 mod graphs;
+mod group_detail;
+mod group_picker;
 mod home;
+mod journal;
 mod login;
 mod logout;
 mod management;
+mod notifications;
+mod plant_cards;
+mod plant_table;
+mod range_editor;
+mod range_selector;
 mod requests;
+mod schedule;
+mod sensor_health;
 mod settings;
+mod storage;
+mod timezone;
+mod watering;
 
+use crate::alerts::{AlertsMessage, AlertsTab, CHECK_INTERVAL};
 use crate::detail::{DetailMessage, DetailPage};
 use crate::home::{HomeMessage, HomePage};
 use crate::login::{LoginMessage, LoginTab, PlantBuddyRole};
 use crate::logout::{LogoutMessage, LogoutTab};
 use crate::management::{ManagementMessage, ManagementTab};
+use crate::range_selector::RangeMessage;
 use crate::requests::{RequestResult, TempCreationUser};
+use crate::schedule::{ScheduleMessage, ScheduleTab};
 use iced::alignment::{Horizontal, Vertical};
 use iced::theme::{Custom, Palette};
 use iced::widget::container::{Appearance, StyleSheet};
@@ -33,7 +59,7 @@ use iced::widget::container::{Appearance, StyleSheet};
 use iced::widget::{Column, Container, Text};
 use iced::{
     executor, window, Application, Background, Color, Command, Element, Font, Length, Settings,
-    Theme,
+    Subscription, Theme,
 };
 use iced_aw::{TabLabel, Tabs};
 use requests::ApiClient;
@@ -56,6 +82,9 @@ enum Icon {
     CogAlt,
     Logout,
     Management,
+    Alert,
+    Schedule,
+    #[allow(dead_code)]
     X,
 }
 pub struct MyStylesheet;
@@ -84,6 +113,8 @@ impl From<Icon> for char {
             Icon::Detailpage => '\u{e85c}',
             Icon::Logout => '\u{e9ba}',
             Icon::Management => '\u{f02e}',
+            Icon::Alert => '\u{e002}',
+            Icon::Schedule => '\u{e878}',
             Icon::X => '\u{e5cd}',
         }
     }
@@ -115,6 +146,8 @@ struct Plantbuddy {
     active_tab: usize,
     home_page: HomePage,
     detail_page: DetailPage,
+    alerts_tab: AlertsTab,
+    schedule_tab: ScheduleTab,
     login_page: LoginTab,
     settings_tab: SettingsTab,
     logout_tab: LogoutTab,
@@ -132,6 +165,8 @@ pub enum Message {
     Settings(SettingsMessage),
     Logout(LogoutMessage),
     Management(ManagementMessage),
+    Alerts(AlertsMessage),
+    Schedule(ScheduleMessage),
 }
 
 /// implementation of the Application trait for the Plantbuddy struct.
@@ -151,6 +186,8 @@ impl Application for Plantbuddy {
                 active_tab: 0,
                 home_page: HomePage::new(),
                 detail_page: DetailPage::new(),
+                alerts_tab: AlertsTab::new(),
+                schedule_tab: ScheduleTab::new(),
                 login_page: LoginTab::new(),
                 settings_tab: SettingsTab::new(),
                 logout_tab: LogoutTab::new(),
@@ -176,46 +213,86 @@ impl Application for Plantbuddy {
             Message::TabSelected(selected) => self.active_tab = selected,
             Message::Login(message) => {
                 // Check if login was successful and if so, update the user
-                if let LoginMessage::Login(result) = &message {
-                    if let RequestResult::Ok(user) = result {
-                        self.is_logged_in = LoginState::LoggedIn;
-                        self.user = user.clone();
-
-                        let res = API_CLIENT
-                            .set(ApiClient::new(user.name.clone(), user.password.clone()));
-                        if res.is_err() {
-                            API_CLIENT
-                                .get()
-                                .unwrap()
-                                .clone()
-                                .replace_inner(user.name.clone(), user.password.clone());
-                        }
-                        // Clear the LoginTab
-                        self.login_page = LoginTab::new();
-                        // Update the logged in user in the management tab
-                        self.management_tab.logged_in_user = user.clone();
-
-                        return Command::batch(vec![
-                            self.detail_page
-                                .update(DetailMessage::Load)
-                                .map(Message::Detail),
-                            // Get all users from the server and update the management tab
-                            self.management_tab
-                                .update(ManagementMessage::GetUsersPressed)
-                                .map(Message::Management),
-                            self.home_page
-                                .update(HomeMessage::Refresh)
-                                .map(Message::Home),
-                        ]);
+                if let LoginMessage::Login(RequestResult::Ok(user)) = &message {
+                    self.is_logged_in = LoginState::LoggedIn;
+                    self.user = user.clone();
+
+                    let res =
+                        API_CLIENT.set(ApiClient::new(user.name.clone(), user.password.clone()));
+                    if res.is_err() {
+                        API_CLIENT
+                            .get()
+                            .unwrap()
+                            .clone()
+                            .replace_inner(user.name.clone(), user.password.clone());
                     }
+                    // Clear the LoginTab
+                    self.login_page = LoginTab::new();
+                    // Update the logged in user in the management tab
+                    self.management_tab.logged_in_user = user.clone();
+
+                    return Command::batch(vec![
+                        self.detail_page
+                            .update(DetailMessage::Load)
+                            .map(Message::Detail),
+                        // Get all users from the server and update the management tab
+                        self.management_tab
+                            .update(ManagementMessage::GetUsersPressed)
+                            .map(Message::Management),
+                        self.home_page
+                            .update(HomeMessage::Refresh)
+                            .map(Message::Home),
+                        self.alerts_tab
+                            .update(AlertsMessage::Check)
+                            .map(Message::Alerts),
+                    ]);
                 }
                 return self.login_page.update(message).map(Message::Login);
             }
-            Message::Home(message) => return self.home_page.update(message).map(Message::Home),
+            Message::Home(HomeMessage::Tasks(message)) => {
+                if let Some(change) = self.home_page.tasks.update(message) {
+                    self.schedule_tab.update(change);
+                }
+                self.refresh_tasks();
+            }
+            Message::Home(HomeMessage::OpenPlant(id)) => {
+                self.active_tab = 1;
+                let command = self
+                    .detail_page
+                    .update(DetailMessage::PlantData(id))
+                    .map(Message::Detail);
+                self.refresh_tasks();
+                return command;
+            }
+            Message::Home(message) => {
+                let command = self.home_page.update(message).map(Message::Home);
+                self.refresh_tasks();
+                return command;
+            }
+            Message::Detail(DetailMessage::Tasks(message)) => {
+                if let Some(change) = self.detail_page.tasks.update(message) {
+                    self.schedule_tab.update(change);
+                }
+                self.refresh_tasks();
+            }
             Message::Detail(message) => {
-                return self.detail_page.update(message).map(Message::Detail)
+                let command = self.detail_page.update(message).map(Message::Detail);
+                self.refresh_tasks();
+                return command;
+            }
+            Message::Settings(message) => {
+                self.settings_tab.update(message);
+                // Show all times in the selected time zone
+                self.settings_tab.settings().time_zone.select();
+                return Command::batch(vec![
+                    self.home_page
+                        .update(HomeMessage::Range(RangeMessage::TimeZoneChanged))
+                        .map(Message::Home),
+                    self.detail_page
+                        .update(DetailMessage::Range(RangeMessage::TimeZoneChanged))
+                        .map(Message::Detail),
+                ]);
             }
-            Message::Settings(message) => self.settings_tab.update(message),
             Message::Logout(message) => {
                 self.logout_tab.update(message.clone());
                 // If the logout is approved, log out and return to the login screen
@@ -227,12 +304,45 @@ impl Application for Plantbuddy {
             Message::Management(message) => {
                 return self.management_tab.update(message).map(Message::Management);
             }
+            Message::Alerts(message) => {
+                if let AlertsMessage::OpenPlant(id) = message {
+                    self.active_tab = 1;
+                    return self
+                        .detail_page
+                        .update(DetailMessage::PlantData(id))
+                        .map(Message::Detail);
+                }
+                return self.alerts_tab.update(message).map(Message::Alerts);
+            }
+            Message::Schedule(message) => {
+                if let ScheduleMessage::OpenPlant(id) = message {
+                    self.active_tab = 1;
+                    let command = self
+                        .detail_page
+                        .update(DetailMessage::PlantData(id))
+                        .map(Message::Detail);
+                    self.refresh_tasks();
+                    return command;
+                }
+                self.schedule_tab.update(message);
+                self.refresh_tasks();
+            }
         }
         Command::none()
     }
 
+    /// Checks the readings of all plants in the background while a user is logged in.
+    fn subscription(&self) -> Subscription<Message> {
+        if self.is_logged_in == LoginState::LoggedIn {
+            iced::time::every(std::time::Duration::from_secs(CHECK_INTERVAL))
+                .map(|_| Message::Alerts(AlertsMessage::Check))
+        } else {
+            Subscription::none()
+        }
+    }
+
     /// Returns the view of the `Plantbuddy` application.
-    fn view(&self) -> Element<Self::Message> {
+    fn view(&self) -> Element<'_, Self::Message> {
         if self.is_logged_in == LoginState::LoggedIn {
             let position = self
                 .settings_tab
@@ -248,12 +358,13 @@ impl Application for Plantbuddy {
             let mut tabs = Tabs::new(self.active_tab, Message::TabSelected)
                 .push(self.home_page.tab_label(), self.home_page.view())
                 .push(self.detail_page.tab_label(), self.detail_page.view())
+                .push(self.alerts_tab.tab_label(), self.alerts_tab.view())
+                .push(self.schedule_tab.tab_label(), self.schedule_tab.view())
                 .push(self.settings_tab.tab_label(), self.settings_tab.view())
                 .tab_bar_style(theme)
                 .icon_font(EXTERNAL_ICON_FONT);
 
-            if let PlantBuddyRole::Admin = PlantBuddyRole::try_from(self.user.role.clone()).unwrap()
-            {
+            if let PlantBuddyRole::Admin = PlantBuddyRole::try_from(self.user.role).unwrap() {
                 tabs = tabs.push(self.management_tab.tab_label(), self.management_tab.view());
             }
 
@@ -283,6 +394,14 @@ impl Application for Plantbuddy {
     }
 }
 
+impl Plantbuddy {
+    /// Shows the current care tasks in the task forms of the home and detail page.
+    fn refresh_tasks(&mut self) {
+        self.home_page.tasks.refresh(&self.schedule_tab.schedule);
+        self.detail_page.tasks.refresh(&self.schedule_tab.schedule);
+    }
+}
+
 /// A trait representing a tab in the `Plantbuddy` application.
 /// # Types
 /// - `Message`: The type of message that this tab will use to communicate.
@@ -353,7 +472,7 @@ mod tests {
     fn test_active_tab() {
         let (mut plantbuddy, _) = Plantbuddy::new(());
         assert_eq!(plantbuddy.active_tab, 0);
-        plantbuddy.update(Message::TabSelected(2));
+        let _ = plantbuddy.update(Message::TabSelected(2));
         assert_eq!(plantbuddy.active_tab, 2);
     }
 
@@ -3,6 +3,7 @@ use crate::{Icon, Message, Tab, API_CLIENT};
 use iced::widget::{scrollable, Rule};
 use iced::Alignment::Center;
 
+use crate::confirm::Confirmation;
 use crate::login::PlantBuddyRole;
 use crate::requests::{ApiClient, RequestResult, TempCreationUser};
 use iced::{
@@ -17,8 +18,12 @@ use serde::Deserialize;
 ///This enum represents the various states or actions related to user `management`. process
 #[derive(Debug, Clone)]
 pub enum ManagementMessage {
-    /// Message sent when delete user button is pressed, includes the User ID.
+    /// Message sent when delete user button is pressed, includes the User ID. Asks for a confirmation first.
     DeleteUserPressed(u32),
+    /// Message sent when the deletion of the user is confirmed.
+    ConfirmDeleteUser,
+    /// Message sent when the deletion of the user is cancelled.
+    CancelDeleteUser,
     /// Message sent when username is changed, includes the new username as a string.
     UsernameChanged(String),
     /// Message sent when password is changed, includes the new password as a string.
@@ -55,6 +60,8 @@ pub struct User {
 /// The `error_message` field is used to show any error messages to the user.
 /// The `editing_user` field is used to store the user being edited (if any).
 /// The `notify_message` field is used to show any notifications to the user.
+/// The `confirm_delete` field holds the user waiting for the confirmation of the deletion.
+/// The `deleting` field holds the user that is being deleted, to name it once it is deleted.
 #[derive(Debug, Clone)]
 pub(crate) struct ManagementTab {
     username_input: String,
@@ -65,6 +72,8 @@ pub(crate) struct ManagementTab {
     notify_message: String,
     editing_user: Option<User>,
     pub logged_in_user: TempCreationUser,
+    confirm_delete: Option<Confirmation<User>>,
+    deleting: Option<User>,
 }
 
 impl ManagementTab {
@@ -79,6 +88,8 @@ impl ManagementTab {
             notify_message: String::new(),
             editing_user: None,
             logged_in_user: TempCreationUser::default(),
+            confirm_delete: None,
+            deleting: None,
         }
     }
 
@@ -123,12 +134,25 @@ impl ManagementTab {
             ManagementMessage::DeleteUserPressed(id) => {
                 self.error_message = String::new();
                 self.notify_message = String::new();
+                if let Some(user) = self.users.iter().find(|user| user.id == id) {
+                    self.confirm_delete =
+                        Some(user_confirmation(user.clone(), &self.logged_in_user));
+                }
+            }
+            ManagementMessage::ConfirmDeleteUser => {
+                let user = match self.confirm_delete.take() {
+                    Some(confirmation) => confirmation.item,
+                    None => return Command::none(),
+                };
                 if let Some(client) = API_CLIENT.get() {
-                    return delete_user_pressed(id.clone(), client.clone());
+                    let id = user.id;
+                    self.deleting = Some(user);
+                    return delete_user_pressed(id, client.clone());
                 }
                 self.error_message = String::from("Fehler beim Löschen des Nutzers");
                 return Command::none();
             }
+            ManagementMessage::CancelDeleteUser => self.confirm_delete = None,
             ManagementMessage::RoleChanged(role) => {
                 self.error_message = String::new();
                 self.notify_message = String::new();
@@ -163,10 +187,14 @@ impl ManagementTab {
             },
             ManagementMessage::UserDeleted(result) => match result {
                 Ok(_) => {
-                    self.notify_message = String::from("Nutzer gelöscht");
+                    self.notify_message = match self.deleting.take() {
+                        Some(user) => format!("Nutzer „{}“ gelöscht", user.name),
+                        None => String::from("Nutzer gelöscht"),
+                    };
                     return self.update(ManagementMessage::GetUsersPressed);
                 }
                 Err(e) => {
+                    self.deleting = None;
                     self.error_message = e;
                 }
             },
@@ -425,6 +453,14 @@ impl Tab for ManagementTab {
             .center_x()
             .center_y()
             .into();
+        let content = match &self.confirm_delete {
+            Some(confirmation) => confirmation.view(
+                content,
+                ManagementMessage::ConfirmDeleteUser,
+                ManagementMessage::CancelDeleteUser,
+            ),
+            None => content,
+        };
 
         content.map(Message::Management)
     }
@@ -463,6 +499,23 @@ fn delete_user_pressed(id: u32, client: ApiClient) -> Command<ManagementMessage>
     Command::perform(client.delete_user(id), ManagementMessage::UserDeleted)
 }
 
+/// Returns the confirmation of deleting the user, naming its role and if it is the own account
+fn user_confirmation(user: User, logged_in_user: &TempCreationUser) -> Confirmation<User> {
+    // The server does not return passwords, so a deleted user can not be restored
+    let mut consequences = vec![
+        String::from("Der Nutzer kann sich danach nicht mehr anmelden"),
+        String::from("Das Löschen kann nicht rückgängig gemacht werden"),
+    ];
+    if let PlantBuddyRole::Admin = user.role {
+        consequences.push(String::from("Der Nutzer hat Administratorrechte"));
+    }
+    if user.name == logged_in_user.name {
+        consequences.push(String::from("Sie löschen Ihr eigenes Konto"));
+    }
+    let title = format!("Nutzer „{}“ löschen?", user.name);
+    Confirmation::new(user, title, consequences)
+}
+
 fn get_all_users_pressed(client: ApiClient) -> Command<ManagementMessage> {
     Command::perform(client.get_all_users(), ManagementMessage::UsersReceived)
 }
@@ -495,7 +548,7 @@ mod tests {
     fn get_api_client() -> ApiClient {
         let username = "testuser".to_string();
         let password = "testpassword".to_string();
-        return ApiClient::new(username, password);
+        ApiClient::new(username, password)
     }
 
     #[tokio::test]
@@ -507,7 +560,7 @@ mod tests {
 
         let client = get_api_client();
 
-        create_user_pressed(tab, client.clone());
+        let _ = create_user_pressed(tab, client.clone());
     }
 
     #[tokio::test]
@@ -515,7 +568,7 @@ mod tests {
         let client = get_api_client();
         let id = 1;
 
-        delete_user_pressed(id, client.clone());
+        let _ = delete_user_pressed(id, client.clone());
     }
 
     #[tokio::test]
@@ -530,6 +583,36 @@ mod tests {
             role: PlantBuddyRole::User,
         });
 
-        edit_user_pressed(tab, client.clone());
+        let _ = edit_user_pressed(tab, client.clone());
+    }
+
+    #[test]
+    fn test_delete_user_asks_first() {
+        let mut tab = ManagementTab::new();
+        tab.logged_in_user.name = "admin".to_string();
+        tab.users = vec![User {
+            id: 3,
+            name: "admin".to_string(),
+            password: "secret".to_string(),
+            role: PlantBuddyRole::Admin,
+        }];
+
+        let _ = tab.update(ManagementMessage::DeleteUserPressed(9));
+        assert!(tab.confirm_delete.is_none());
+        let _ = tab.update(ManagementMessage::DeleteUserPressed(3));
+        let confirmation = tab.confirm_delete.clone().unwrap();
+        assert_eq!(confirmation.title, "Nutzer „admin“ löschen?");
+        assert_eq!(
+            confirmation.consequences,
+            vec![
+                "Der Nutzer kann sich danach nicht mehr anmelden",
+                "Das Löschen kann nicht rückgängig gemacht werden",
+                "Der Nutzer hat Administratorrechte",
+                "Sie löschen Ihr eigenes Konto",
+            ]
+        );
+        let _ = tab.update(ManagementMessage::CancelDeleteUser);
+        assert!(tab.confirm_delete.is_none());
+        assert!(tab.deleting.is_none());
     }
 }
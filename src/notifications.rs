@@ -0,0 +1,253 @@
+use crate::alerts::{Alert, Severity};
+use crate::detail::Sensortypes;
+use crate::graphs::{format_range, format_value};
+use crate::timezone::{from_minutes, TimeZoneChoice};
+use chrono::NaiveTime;
+use iced::futures::future::BoxFuture;
+use notify_rust::{Notification, Timeout};
+
+/// The format of the quiet hours inputs
+const TIME_FORMAT: &str = "%H:%M";
+
+/// The settings of the desktop notifications
+///
+/// Fields:
+/// * `sensors` - The sensors whose alerts are sent as notifications
+/// * `quiet_hours_enabled` - Indicates if notifications are held back during the quiet hours
+/// * `quiet_start` - The input of the start of the quiet hours
+/// * `quiet_end` - The input of the end of the quiet hours
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationSettings {
+    pub sensors: Vec<Sensortypes>,
+    pub quiet_hours_enabled: bool,
+    pub quiet_start: String,
+    pub quiet_end: String,
+}
+
+impl NotificationSettings {
+    /// Creates the default settings, notifying about soil moisture and temperature
+    pub fn new() -> Self {
+        NotificationSettings {
+            sensors: vec![Sensortypes::Feuchtigkeit, Sensortypes::Temperatur],
+            quiet_hours_enabled: false,
+            quiet_start: "22:00".to_string(),
+            quiet_end: "07:00".to_string(),
+        }
+    }
+
+    /// Enables or disables the notifications for the sensor
+    pub fn set_sensor(&mut self, sensor: Sensortypes, enabled: bool) {
+        self.sensors.retain(|s| *s != sensor);
+        if enabled {
+            self.sensors.push(sensor);
+        }
+    }
+
+    /// Returns the quiet hours, `None` if they are disabled or the inputs are invalid
+    pub fn quiet_hours(&self) -> Option<(NaiveTime, NaiveTime)> {
+        if !self.quiet_hours_enabled {
+            return None;
+        }
+        let start = NaiveTime::parse_from_str(self.quiet_start.trim(), TIME_FORMAT).ok()?;
+        let end = NaiveTime::parse_from_str(self.quiet_end.trim(), TIME_FORMAT).ok()?;
+        Some((start, end))
+    }
+
+    /// Returns if the quiet hours are enabled but can not be read
+    pub fn quiet_hours_invalid(&self) -> bool {
+        self.quiet_hours_enabled && self.quiet_hours().is_none()
+    }
+
+    /// Returns if the time lies within the quiet hours, which may span midnight
+    pub fn is_quiet(&self, time: NaiveTime) -> bool {
+        match self.quiet_hours() {
+            Some((start, end)) if start <= end => time >= start && time < end,
+            Some((start, end)) => time >= start || time < end,
+            None => false,
+        }
+    }
+
+    /// Returns the alerts to notify about at the given local time
+    ///
+    /// Alerts raised during the quiet hours are held back, once per plant and sensor, and are
+    /// returned with the alerts of the first call after the quiet hours end.
+    pub fn due(&self, held: &mut Vec<Alert>, raised: Vec<Alert>, time: NaiveTime) -> Vec<Alert> {
+        let raised = raised
+            .into_iter()
+            .filter(|alert| self.sensors.contains(&alert.sensor));
+        if self.is_quiet(time) {
+            raised.for_each(|alert| enqueue(held, alert));
+            return Vec::new();
+        }
+        let mut due = std::mem::take(held);
+        raised.for_each(|alert| enqueue(&mut due, alert));
+        due
+    }
+}
+
+/// Adds the alert to the queue, replacing an earlier alert of the same plant and sensor
+fn enqueue(queue: &mut Vec<Alert>, alert: Alert) {
+    queue.retain(|queued| queued.plant_id != alert.plant_id || queued.sensor != alert.sensor);
+    queue.push(alert);
+}
+
+/// Sends the notification of an alert
+pub trait Notifier {
+    /// Returns the future sending the notification
+    fn send(&self, alert: Alert) -> BoxFuture<'static, Result<(), String>>;
+}
+
+/// Sends the notifications to the desktop
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    fn send(&self, alert: Alert) -> BoxFuture<'static, Result<(), String>> {
+        Box::pin(send_notification(alert))
+    }
+}
+
+/// Returns the summary and body of the notification of an alert
+pub fn notification_text(alert: &Alert) -> (String, String) {
+    let summary = format!(
+        "{}: {} außerhalb des Bereichs",
+        alert.plant_name, alert.sensor
+    );
+    let body = format!(
+        "{} - Wert {} liegt außerhalb von {} seit {}",
+        alert.severity,
+        format_value(alert.sensor, alert.value),
+        format_range(alert.sensor, alert.range),
+        TimeZoneChoice::current().format(&from_minutes(alert.since), "%d.%m. %H:%M")
+    );
+    (summary, body)
+}
+
+/// Sends a desktop notification over the freedesktop notification service on D-Bus
+///
+/// Critical alerts stay visible until they are closed.
+pub async fn send_notification(alert: Alert) -> Result<(), String> {
+    let (summary, body) = notification_text(&alert);
+    let mut notification = Notification::new();
+    notification
+        .appname("Plantbuddy")
+        .summary(&summary)
+        .body(&body)
+        .icon("dialog-warning");
+    if alert.severity == Severity::Kritisch {
+        notification.timeout(Timeout::Never);
+    }
+    notification
+        .show_async()
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(sensor: Sensortypes) -> Alert {
+        Alert {
+            id: 0,
+            plant_id: "1".to_string(),
+            plant_name: "Basilikum".to_string(),
+            group_id: 1,
+            group_name: "Kräuter".to_string(),
+            sensor,
+            value: 12,
+            range: (30, 70),
+            severity: Severity::Kritisch,
+            since: 0,
+            resolved: None,
+        }
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    /// Returns if the alert is notified at once at the given time
+    fn notified(settings: &NotificationSettings, sensor: Sensortypes, time: NaiveTime) -> bool {
+        !settings
+            .due(&mut vec![], vec![alert(sensor)], time)
+            .is_empty()
+    }
+
+    #[test]
+    fn test_sensor_switches() {
+        let mut settings = NotificationSettings::new();
+        assert!(notified(&settings, Sensortypes::Feuchtigkeit, time(12, 0)));
+        assert!(!notified(&settings, Sensortypes::Licht, time(12, 0)));
+        settings.set_sensor(Sensortypes::Feuchtigkeit, false);
+        settings.set_sensor(Sensortypes::Licht, true);
+        assert!(!notified(&settings, Sensortypes::Feuchtigkeit, time(12, 0)));
+        assert!(notified(&settings, Sensortypes::Licht, time(12, 0)));
+    }
+
+    #[test]
+    fn test_quiet_hours_over_midnight() {
+        let mut settings = NotificationSettings::new();
+        assert!(!settings.is_quiet(time(23, 0)));
+        settings.quiet_hours_enabled = true;
+        assert!(settings.is_quiet(time(23, 0)));
+        assert!(settings.is_quiet(time(6, 59)));
+        assert!(!settings.is_quiet(time(7, 0)));
+        assert!(!notified(&settings, Sensortypes::Feuchtigkeit, time(2, 0)));
+    }
+
+    #[test]
+    fn test_quiet_hours_hold_alerts_back() {
+        let mut settings = NotificationSettings::new();
+        settings.quiet_hours_enabled = true;
+        settings.set_sensor(Sensortypes::Temperatur, true);
+        let mut held = vec![];
+        let mut later = alert(Sensortypes::Feuchtigkeit);
+        later.id = 1;
+        let raised = vec![
+            alert(Sensortypes::Feuchtigkeit),
+            alert(Sensortypes::Temperatur),
+            later,
+        ];
+        assert!(settings.due(&mut held, raised, time(23, 0)).is_empty());
+        // The later alert of the same plant and sensor replaces the earlier one
+        assert_eq!(held.len(), 2);
+        assert!(settings.due(&mut held, vec![], time(6, 0)).is_empty());
+
+        let due = settings.due(&mut held, vec![alert(Sensortypes::Temperatur)], time(7, 0));
+        assert!(held.is_empty());
+        assert_eq!(
+            due.iter()
+                .map(|alert| (alert.id, alert.sensor))
+                .collect::<Vec<_>>(),
+            vec![(1, Sensortypes::Feuchtigkeit), (0, Sensortypes::Temperatur)]
+        );
+    }
+
+    #[test]
+    fn test_quiet_hours_same_day() {
+        let mut settings = NotificationSettings::new();
+        settings.quiet_hours_enabled = true;
+        settings.quiet_start = "12:00".to_string();
+        settings.quiet_end = "14:30".to_string();
+        assert!(settings.is_quiet(time(13, 0)));
+        assert!(!settings.is_quiet(time(15, 0)));
+        settings.quiet_end = "halb drei".to_string();
+        assert!(settings.quiet_hours_invalid());
+        assert!(!settings.is_quiet(time(13, 0)));
+    }
+
+    #[test]
+    fn test_notification_text() {
+        let (summary, body) = notification_text(&alert(Sensortypes::Temperatur));
+        assert_eq!(summary, "Basilikum: Temperatur außerhalb des Bereichs");
+        assert!(body.starts_with("Kritisch - Wert 12 °C liegt außerhalb von 30 – 70 °C"));
+        // The server stores the soil moisture multiplied by 100
+        let (_, body) = notification_text(&Alert {
+            value: 1250,
+            range: (3000, 7000),
+            ..alert(Sensortypes::Feuchtigkeit)
+        });
+        assert!(body.starts_with("Kritisch - Wert 12.50 % liegt außerhalb von 30 – 70 %"));
+    }
+}
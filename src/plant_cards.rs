@@ -0,0 +1,358 @@
+use crate::detail::Sensortypes;
+use crate::downsample::lttb;
+use crate::graphs::{format_value, graph_points};
+use crate::requests::{PlantReadings, SensorRange};
+use crate::TEXT_SIZE;
+use iced::widget::canvas::{self, Canvas, Cursor, Frame, Geometry, Path, Stroke};
+use iced::widget::{Button, Column, Row, Text};
+use iced::{Color, Element, Length, Point, Rectangle, Theme};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+/// A value this close to the border of the range, relative to the width of the range, is a warning
+const WARNING_MARGIN: f64 = 0.1;
+/// The number of points drawn in a sparkline
+const SPARKLINE_POINTS: usize = 40;
+/// The width of a card in pixels
+pub const CARD_WIDTH: u16 = 300;
+
+/// Contains the traffic light states of a plant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Status {
+    /// There are no readings with a sensor range
+    Unbekannt,
+    /// All values are well within the ranges
+    Gut,
+    /// A value is close to the border of its range
+    Warnung,
+    /// A value is outside of its range
+    Kritisch,
+}
+
+impl Display for Status {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Status::Unbekannt => write!(f, "Unbekannt"),
+            Status::Gut => write!(f, "Gut"),
+            Status::Warnung => write!(f, "Warnung"),
+            Status::Kritisch => write!(f, "Kritisch"),
+        }
+    }
+}
+
+impl Status {
+    /// Returns the color of the traffic light, matching the colors of the theme
+    pub fn color(&self) -> Color {
+        match self {
+            Status::Unbekannt => Color::from_rgb(0.6, 0.6, 0.6),
+            Status::Gut => Color::from_rgb(13.0 / 255.0, 171.0 / 255.0, 118.0 / 255.0),
+            Status::Warnung => Color::from_rgb(230.0 / 255.0, 180.0 / 255.0, 30.0 / 255.0),
+            Status::Kritisch => Color::from_rgb(214.0 / 255.0, 73.0 / 255.0, 51.0 / 255.0),
+        }
+    }
+
+    /// Returns the status of a value against the range of its sensor
+    pub fn of(value: i32, (min, max): (i32, i32)) -> Status {
+        if value < min || value > max {
+            return Status::Kritisch;
+        }
+        let margin = (max - min) as f64 * WARNING_MARGIN;
+        if ((value - min) as f64) < margin || ((max - value) as f64) < margin {
+            Status::Warnung
+        } else {
+            Status::Gut
+        }
+    }
+}
+
+/// The latest reading of a sensor of a plant
+///
+/// Fields:
+/// * `sensor` - The sensor
+/// * `value` - The latest value in the unit of the server
+/// * `status` - The status of the value against the range of the group
+#[derive(Debug, Clone, PartialEq)]
+pub struct LatestValue {
+    pub sensor: Sensortypes,
+    pub value: i32,
+    pub status: Status,
+}
+
+/// The status of a plant shown on the dashboard
+///
+/// Fields:
+/// * `id` - The id of the plant
+/// * `name` - The name of the plant
+/// * `latest` - The latest value of every sensor with readings
+/// * `status` - The worst status of all sensors
+/// * `sparkline` - The recent readings of the soil moisture, or the first sensor with readings
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlantCard {
+    pub id: String,
+    pub name: String,
+    pub latest: Vec<LatestValue>,
+    pub status: Status,
+    pub sparkline: Sparkline,
+}
+
+impl PlantCard {
+    /// Creates the card of a plant from its recent readings, if there are any
+    pub fn new(id: String, name: String, readings: Option<&PlantReadings>) -> Self {
+        let mut latest = vec![];
+        let mut sparkline = Sparkline::default();
+        if let Some(plant) = readings {
+            for sensor in Sensortypes::iter() {
+                let series = match plant
+                    .readings
+                    .iter()
+                    .find(|(name, _)| *name == sensor.get_name())
+                {
+                    Some((_, graph_data)) => graph_points(std::slice::from_ref(graph_data)),
+                    None => continue,
+                };
+                let value = match series.last() {
+                    Some(point) => point.1,
+                    None => continue,
+                };
+                let range = plant
+                    .group
+                    .sensorRanges
+                    .iter()
+                    .find(|range| range.sensorType.name == sensor.get_name())
+                    .and_then(SensorRange::bounds);
+                latest.push(LatestValue {
+                    sensor,
+                    value,
+                    status: range.map_or(Status::Unbekannt, |range| Status::of(value, range)),
+                });
+                if sparkline.points.is_empty() {
+                    sparkline = Sparkline {
+                        points: lttb(&series, SPARKLINE_POINTS),
+                        color: sensor.get_color(),
+                    };
+                }
+            }
+        }
+        let status = latest
+            .iter()
+            .map(|value| value.status)
+            .max()
+            .unwrap_or(Status::Unbekannt);
+        PlantCard {
+            id,
+            name,
+            latest,
+            status,
+            sparkline,
+        }
+    }
+
+    /// Returns the card as button that sends the given message when it is clicked
+    pub fn view<'a, M: Clone + 'a>(&'a self, on_press: M) -> Element<'a, M> {
+        let header = Row::new()
+            .spacing(10)
+            .push(Text::new("●").size(TEXT_SIZE).style(self.status.color()))
+            .push(Text::new(&self.name).size(TEXT_SIZE));
+        let mut column = Column::new().spacing(5).push(header);
+        if self.latest.is_empty() {
+            column = column.push(Text::new("Keine aktuellen Messwerte").size(TEXT_SIZE - 8));
+        }
+        for value in &self.latest {
+            let text = Text::new(format!(
+                "{}: {}",
+                value.sensor,
+                format_value(value.sensor, value.value)
+            ))
+            .size(TEXT_SIZE - 8);
+            column = column.push(match value.status {
+                Status::Warnung | Status::Kritisch => text.style(value.status.color()),
+                _ => text,
+            });
+        }
+        column = column.push(
+            Canvas::new(self.sparkline.clone())
+                .width(Length::Fill)
+                .height(Length::from(40)),
+        );
+        Button::new(column)
+            .width(Length::from(CARD_WIDTH))
+            .on_press(on_press)
+            .into()
+    }
+}
+
+/// A small line chart without axes
+///
+/// Fields:
+/// * `points` - The points of the line
+/// * `color` - The color of the line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sparkline {
+    pub points: Vec<(i32, i32)>,
+    pub color: plotters::style::RGBColor,
+}
+
+impl Default for Sparkline {
+    fn default() -> Self {
+        Sparkline {
+            points: Vec::new(),
+            color: Sensortypes::Feuchtigkeit.get_color(),
+        }
+    }
+}
+
+impl Sparkline {
+    /// Returns the points scaled into the given size, the largest value at the top
+    pub fn scaled(&self, width: f32, height: f32) -> Vec<Point> {
+        let (x_min, x_max) = match self.points.iter().map(|p| p.0).minmax().into_option() {
+            Some((min, max)) => (min, max.max(min + 1)),
+            None => return Vec::new(),
+        };
+        let (y_min, y_max) = self
+            .points
+            .iter()
+            .map(|p| p.1)
+            .minmax()
+            .into_option()
+            .map(|(min, max)| (min, max.max(min + 1)))
+            .unwrap_or((0, 1));
+        self.points
+            .iter()
+            .map(|(x, y)| {
+                Point::new(
+                    (x - x_min) as f32 / (x_max - x_min) as f32 * width,
+                    height - (y - y_min) as f32 / (y_max - y_min) as f32 * height,
+                )
+            })
+            .collect()
+    }
+}
+
+impl<M> canvas::Program<M> for Sparkline {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let points = self.scaled(bounds.width, bounds.height - 2.0);
+        if points.len() > 1 {
+            let line = Path::new(|builder| {
+                builder.move_to(points[0]);
+                points[1..].iter().for_each(|point| builder.line_to(*point));
+            });
+            let plotters::style::RGBColor(r, g, b) = self.color;
+            frame.stroke(
+                &line,
+                Stroke::default()
+                    .with_color(Color::from_rgb8(r, g, b))
+                    .with_width(2.0),
+            );
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::requests::{GraphData, PlantGroupMetadata, SensorRange, SensorType};
+
+    fn plant() -> PlantReadings {
+        let range = |name: &str, min: i32, max: i32| SensorRange {
+            sensorType: SensorType {
+                name: name.to_string(),
+                unit: String::new(),
+            },
+            min,
+            max,
+        };
+        let data = |values: Vec<i32>| GraphData {
+            timestamps: (0..values.len())
+                .map(|minute| format!("2023-05-01T08:{:02}:00Z", minute))
+                .collect(),
+            values,
+        };
+        PlantReadings {
+            id: "1".to_string(),
+            name: "Basilikum".to_string(),
+            group: PlantGroupMetadata {
+                sensorRanges: vec![
+                    range("soil-moisture", 3000, 6000),
+                    range("temperature", 15, 30),
+                ],
+                ..PlantGroupMetadata::default()
+            },
+            readings: vec![
+                ("soil-moisture".to_string(), data(vec![5000, 4900, 4500])),
+                ("temperature".to_string(), data(vec![20, 21, 16])),
+                ("light".to_string(), data(vec![100000])),
+            ],
+            ..PlantReadings::default()
+        }
+    }
+
+    #[test]
+    fn test_status_of() {
+        assert_eq!(Status::of(50, (0, 100)), Status::Gut);
+        assert_eq!(Status::of(5, (0, 100)), Status::Warnung);
+        assert_eq!(Status::of(95, (0, 100)), Status::Warnung);
+        assert_eq!(Status::of(101, (0, 100)), Status::Kritisch);
+        assert!(Status::Kritisch > Status::Warnung && Status::Warnung > Status::Gut);
+    }
+
+    #[test]
+    fn test_plant_card() {
+        let plant = plant();
+        let card = PlantCard::new("1".to_string(), "Basilikum".to_string(), Some(&plant));
+        assert_eq!(
+            card.latest,
+            vec![
+                LatestValue {
+                    sensor: Sensortypes::Feuchtigkeit,
+                    value: 4500,
+                    status: Status::Gut,
+                },
+                LatestValue {
+                    sensor: Sensortypes::Temperatur,
+                    value: 16,
+                    status: Status::Warnung,
+                },
+                LatestValue {
+                    sensor: Sensortypes::Licht,
+                    value: 100000,
+                    status: Status::Unbekannt,
+                },
+            ]
+        );
+        assert_eq!(card.status, Status::Warnung);
+        assert_eq!(card.sparkline.points.len(), 3);
+        assert_eq!(card.sparkline.color, Sensortypes::Feuchtigkeit.get_color());
+
+        let empty = PlantCard::new("2".to_string(), "Minze".to_string(), None);
+        assert_eq!(empty.status, Status::Unbekannt);
+        assert!(empty.latest.is_empty());
+    }
+
+    #[test]
+    fn test_sparkline_scaled() {
+        let sparkline = Sparkline {
+            points: vec![(0, 10), (5, 20), (10, 0)],
+            ..Sparkline::default()
+        };
+        let points = sparkline.scaled(100.0, 40.0);
+        assert_eq!(
+            points,
+            vec![
+                Point::new(0.0, 20.0),
+                Point::new(50.0, 0.0),
+                Point::new(100.0, 40.0)
+            ]
+        );
+        assert!(Sparkline::default().scaled(100.0, 40.0).is_empty());
+    }
+}
@@ -0,0 +1,805 @@
+use crate::bulk::{BulkEditor, BulkMessage};
+use crate::detail::URGENT_COLOR;
+use crate::group_picker::GroupOption;
+use crate::plant_cards::{PlantCard, Status};
+use crate::requests::{PlantGroupMetadata, PlantReadings, RequestResult};
+use crate::{API_CLIENT, TEXT_SIZE};
+use chrono::{Duration, SecondsFormat, Utc};
+use iced::widget::{scrollable, Button, Checkbox, Column, Row, Text, TextInput};
+use iced::{theme, Command, Element, Length};
+use iced_core::Alignment::Center;
+use itertools::Itertools;
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fmt::{Display, Formatter};
+
+/// The number of rows shown on one page of the table
+pub const PAGE_SIZE: usize = 50;
+/// The status of a plant is calculated from the readings of this many minutes before now
+const STATUS_MINUTES: i64 = 6 * 60;
+/// The width of a column of the table in pixels
+const COLUMN_WIDTH: u16 = 220;
+
+/// Contains the columns of the plant table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableColumn {
+    Name,
+    Art,
+    Standort,
+    Gruppe,
+    Status,
+}
+
+impl Display for TableColumn {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TableColumn::Name => write!(f, "Name"),
+            TableColumn::Art => write!(f, "Art"),
+            TableColumn::Standort => write!(f, "Standort"),
+            TableColumn::Gruppe => write!(f, "Gruppe"),
+            TableColumn::Status => write!(f, "Status"),
+        }
+    }
+}
+
+impl TableColumn {
+    /// All columns in the order they are displayed
+    pub const ALL: [TableColumn; 5] = [
+        TableColumn::Name,
+        TableColumn::Art,
+        TableColumn::Standort,
+        TableColumn::Gruppe,
+        TableColumn::Status,
+    ];
+}
+
+/// Contains the loading states of the readings of a row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowState {
+    /// The readings were not requested yet
+    Offen,
+    /// The readings are requested
+    Laedt,
+    /// The readings were loaded and the status is calculated
+    Geladen,
+    /// The metadata or the readings could not be loaded
+    Fehler(String),
+}
+
+/// A plant in the table
+///
+/// Fields:
+/// * `id` - The id of the plant
+/// * `name` - The name of the plant
+/// * `species` - The species of the plant
+/// * `location` - The location of the plant
+/// * `group` - The group of the plant with its sensor ranges
+/// * `status` - The worst status of the recent readings against the ranges of the group
+/// * `state` - Indicates if the readings of the plant are loaded
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlantRow {
+    pub id: String,
+    pub name: String,
+    pub species: String,
+    pub location: String,
+    pub group: PlantGroupMetadata,
+    pub status: Status,
+    pub state: RowState,
+}
+
+impl PlantRow {
+    /// Creates the row of a plant from the overview, the metadata and readings are loaded later
+    pub fn new(id: String, name: String) -> Self {
+        PlantRow {
+            id,
+            name,
+            species: String::new(),
+            location: String::new(),
+            group: PlantGroupMetadata::default(),
+            status: Status::Unbekannt,
+            state: RowState::Offen,
+        }
+    }
+
+    /// Takes the metadata and calculates the status from the recent readings of the plant
+    ///
+    /// A plant whose metadata or readings could not be loaded keeps its name and shows the error.
+    pub fn set_readings(&mut self, readings: RequestResult<PlantReadings>) {
+        match readings {
+            Ok(plant) => {
+                self.status =
+                    PlantCard::new(self.id.clone(), self.name.clone(), Some(&plant)).status;
+                self.species = plant.species;
+                self.location = plant.location;
+                self.group = plant.group;
+                self.state = RowState::Geladen;
+            }
+            Err(e) => self.state = RowState::Fehler(e),
+        }
+    }
+
+    /// Returns the text of the given column
+    pub fn field(&self, column: TableColumn) -> String {
+        match column {
+            TableColumn::Name => self.name.clone(),
+            TableColumn::Art => self.species.clone(),
+            TableColumn::Standort => self.location.clone(),
+            TableColumn::Gruppe => self.group.name.clone(),
+            TableColumn::Status => match &self.state {
+                RowState::Offen | RowState::Laedt => "Lädt...".to_string(),
+                RowState::Geladen => self.status.to_string(),
+                RowState::Fehler(e) => format!("Fehler: {}", e),
+            },
+        }
+    }
+
+    /// Returns how well the row matches the search, `None` if a word of the search matches no column
+    pub fn score(&self, query: &str) -> Option<u32> {
+        query.split_whitespace().try_fold(0, |score, word| {
+            let best = TableColumn::ALL
+                .iter()
+                .filter_map(|column| fuzzy_score(word, &self.field(*column)))
+                .max()?;
+            Some(score + best)
+        })
+    }
+}
+
+/// Returns how well the text matches the search, `None` if it does not contain its characters in order
+///
+/// Every matched character counts, characters following the previous match and characters at the
+/// start of a word count more. The comparison ignores the case.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let mut query = query.chars().flat_map(char::to_lowercase).peekable();
+    let mut score = 0;
+    let mut previous_matched = false;
+    let mut previous: Option<char> = None;
+    for character in text.chars().flat_map(char::to_lowercase) {
+        let wanted = match query.peek() {
+            Some(wanted) => *wanted,
+            None => break,
+        };
+        if character == wanted {
+            score += 1;
+            if previous_matched {
+                score += 2;
+            }
+            if previous.is_none_or(|previous| !previous.is_alphanumeric()) {
+                score += 3;
+            }
+            query.next();
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous = Some(character);
+    }
+    match query.peek() {
+        Some(_) => None,
+        None => Some(score),
+    }
+}
+
+/// Contains all possible messages of the plant table
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlantTableMessage {
+    /// Loads all plants from the server
+    Load,
+    /// The plants of the overview were loaded
+    Loaded(RequestResult<Vec<PlantRow>>),
+    /// The groups the selected plants can be moved to were loaded
+    GroupsLoaded(RequestResult<Vec<GroupOption>>),
+    /// The metadata and recent readings of the plants with the given ids and names were loaded
+    ReadingsLoaded(Vec<(String, String, RequestResult<PlantReadings>)>),
+    /// Updates the search
+    Search(String),
+    /// Sorts by the given column, a second click reverses the order
+    Sort(TableColumn),
+    /// Shows the previous page
+    PreviousPage,
+    /// Shows the next page
+    NextPage,
+    /// Opens the plant with the given id
+    Open(String),
+    /// Selects or deselects the plant with the given id
+    Toggle(String, bool),
+    /// Selects all plants matching the search, or deselects all plants
+    SelectAll(bool),
+    /// Handles the messages of the bulk actions on the selected plants
+    Bulk(BulkMessage),
+}
+
+/// A searchable and sortable table of all plants
+///
+/// The matching rows are only recalculated when the plants, the search or the sorting change,
+/// and only one page of them is drawn, so the table stays fast with thousands of plants.
+/// The rows are shown with the names of the overview, the metadata and the readings behind the
+/// status are only requested for the rows of the displayed page.
+///
+/// Fields:
+/// * `rows` - All plants
+/// * `query` - The search
+/// * `sort` - The column the rows are sorted by, `None` sorts by the best match of the search
+/// * `ascending` - Indicates if the sorted column is in ascending order
+/// * `visible` - The indices of the rows matching the search in the displayed order
+/// * `page` - The displayed page, starting with 0
+/// * `status` - The state of the last request
+/// * `selected` - The ids of the selected plants, kept when the search or page changes
+/// * `bulk` - The actions on the selected plants
+#[derive(Debug, Clone, Default)]
+pub struct PlantTable {
+    pub rows: Vec<PlantRow>,
+    pub query: String,
+    pub sort: Option<TableColumn>,
+    pub ascending: bool,
+    pub visible: Vec<usize>,
+    pub page: usize,
+    pub status: String,
+    pub selected: BTreeSet<String>,
+    pub bulk: BulkEditor,
+}
+
+impl PlantTable {
+    /// Creates an empty table
+    pub fn new() -> Self {
+        PlantTable {
+            ascending: true,
+            ..PlantTable::default()
+        }
+    }
+
+    /// Replaces the plants of the table, plants that no longer exist are deselected
+    pub fn set_rows(&mut self, rows: Vec<PlantRow>) {
+        self.selected
+            .retain(|id| rows.iter().any(|row| row.id == *id));
+        self.rows = rows;
+        self.refresh();
+    }
+
+    /// Returns the ids and names of the selected plants in the order of the table
+    pub fn selected_plants(&self) -> Vec<(String, String)> {
+        self.rows
+            .iter()
+            .filter(|row| self.selected.contains(&row.id))
+            .map(|row| (row.id.clone(), row.name.clone()))
+            .collect()
+    }
+
+    /// Returns if all plants matching the search are selected
+    fn all_selected(&self) -> bool {
+        !self.visible.is_empty()
+            && self
+                .visible
+                .iter()
+                .all(|index| self.selected.contains(&self.rows[*index].id))
+    }
+
+    /// Recalculates the rows matching the search in the selected order and shows the first page
+    fn refresh(&mut self) {
+        let scored = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter_map(|(index, row)| Some((index, row.score(&self.query)?)))
+            .collect_vec();
+        let by_name = |a: &PlantRow, b: &PlantRow| compare_text(&a.name, &b.name);
+        self.visible = match self.sort {
+            Some(TableColumn::Status) => scored
+                .into_iter()
+                .map(|(index, _)| index)
+                .sorted_by(|a, b| {
+                    let (a, b) = (&self.rows[*a], &self.rows[*b]);
+                    a.status.cmp(&b.status).then_with(|| by_name(a, b))
+                })
+                .collect(),
+            Some(column) => scored
+                .into_iter()
+                .map(|(index, _)| index)
+                .sorted_by_cached_key(|index| self.rows[*index].field(column).to_lowercase())
+                .collect(),
+            None => scored
+                .into_iter()
+                .sorted_by(|(a, a_score), (b, b_score)| {
+                    b_score
+                        .cmp(a_score)
+                        .then_with(|| by_name(&self.rows[*a], &self.rows[*b]))
+                })
+                .map(|(index, _)| index)
+                .collect(),
+        };
+        if self.sort.is_some() && !self.ascending {
+            self.visible.reverse();
+        }
+        self.page = 0;
+    }
+
+    /// Returns the number of pages of the matching rows
+    pub fn pages(&self) -> usize {
+        self.visible.len().div_ceil(PAGE_SIZE).max(1)
+    }
+
+    /// Returns the rows of the displayed page
+    pub fn page_rows(&self) -> Vec<&PlantRow> {
+        self.visible
+            .iter()
+            .skip(self.page * PAGE_SIZE)
+            .take(PAGE_SIZE)
+            .map(|index| &self.rows[*index])
+            .collect()
+    }
+
+    /// Marks the rows of the displayed page without readings as loading and returns their ids and
+    /// names
+    fn take_unloaded(&mut self) -> Vec<(String, String)> {
+        let indices = self
+            .visible
+            .iter()
+            .skip(self.page * PAGE_SIZE)
+            .take(PAGE_SIZE)
+            .copied()
+            .filter(|index| self.rows[*index].state == RowState::Offen)
+            .collect_vec();
+        indices
+            .into_iter()
+            .map(|index| {
+                self.rows[index].state = RowState::Laedt;
+                (self.rows[index].id.clone(), self.rows[index].name.clone())
+            })
+            .collect()
+    }
+
+    /// Requests the metadata and recent readings of the rows of the displayed page that have none
+    /// yet
+    fn load_page(&mut self) -> Command<PlantTableMessage> {
+        let plants = self.take_unloaded();
+        if plants.is_empty() {
+            return Command::none();
+        }
+        let now = Utc::now();
+        let time_range = (
+            (now - Duration::minutes(STATUS_MINUTES)).to_rfc3339_opts(SecondsFormat::Millis, true),
+            now.to_rfc3339_opts(SecondsFormat::Millis, true),
+        );
+        Command::perform(
+            API_CLIENT
+                .get()
+                .unwrap()
+                .clone()
+                .get_plant_readings(plants, time_range),
+            PlantTableMessage::ReadingsLoaded,
+        )
+    }
+
+    /// Handles the messages of the table
+    ///
+    /// `Open` is handled by the detail page, which shows the plant.
+    pub fn update(&mut self, message: PlantTableMessage) -> Command<PlantTableMessage> {
+        match message {
+            PlantTableMessage::Load => {
+                self.status = String::from("Pflanzen werden geladen...");
+                return Command::batch(vec![
+                    Command::perform(
+                        API_CLIENT.get().unwrap().clone().get_plant_overview(),
+                        |result| {
+                            PlantTableMessage::Loaded(result.map(|plants| {
+                                plants
+                                    .into_iter()
+                                    .map(|(id, name)| PlantRow::new(id, name))
+                                    .collect()
+                            }))
+                        },
+                    ),
+                    Command::perform(
+                        API_CLIENT.get().unwrap().clone().get_groups(),
+                        PlantTableMessage::GroupsLoaded,
+                    ),
+                ]);
+            }
+            PlantTableMessage::Loaded(result) => match result {
+                Ok(rows) => {
+                    self.status = String::new();
+                    self.set_rows(rows);
+                }
+                Err(e) => self.status = format!("Pflanzen nicht geladen: {}", e),
+            },
+            PlantTableMessage::GroupsLoaded(result) => match result {
+                Ok(groups) => self.bulk.group.set_groups(groups),
+                Err(e) => self.bulk.group.error = format!("Gruppen nicht geladen: {}", e),
+            },
+            // The order is kept, so the rows of the page do not move while their status arrives
+            PlantTableMessage::ReadingsLoaded(readings) => {
+                for (id, _, result) in readings {
+                    if let Some(row) = self.rows.iter_mut().find(|row| row.id == id) {
+                        row.set_readings(result);
+                    }
+                }
+            }
+            PlantTableMessage::Search(query) => {
+                self.query = query;
+                self.refresh();
+            }
+            PlantTableMessage::Sort(column) => {
+                if self.sort == Some(column) {
+                    self.ascending = !self.ascending;
+                } else {
+                    self.sort = Some(column);
+                    self.ascending = true;
+                }
+                self.refresh();
+            }
+            PlantTableMessage::PreviousPage => self.page = self.page.saturating_sub(1),
+            PlantTableMessage::NextPage => self.page = (self.page + 1).min(self.pages() - 1),
+            PlantTableMessage::Open(_) => {}
+            PlantTableMessage::Toggle(id, selected) => {
+                if selected {
+                    self.selected.insert(id);
+                } else {
+                    self.selected.remove(&id);
+                }
+            }
+            PlantTableMessage::SelectAll(selected) => {
+                if selected {
+                    let ids = self
+                        .visible
+                        .iter()
+                        .map(|index| self.rows[*index].id.clone());
+                    self.selected.extend(ids.collect_vec());
+                } else {
+                    self.selected.clear();
+                }
+            }
+            PlantTableMessage::Bulk(BulkMessage::Run) => {
+                return self
+                    .bulk
+                    .start(self.selected_plants())
+                    .map(PlantTableMessage::Bulk);
+            }
+            PlantTableMessage::Bulk(message) => {
+                let done = matches!(message, BulkMessage::Done(..));
+                let command = self.bulk.update(message).map(PlantTableMessage::Bulk);
+                if done && !self.bulk.is_running() {
+                    // Failed plants stay selected, so the action can be repeated on them
+                    for id in self.bulk.succeeded() {
+                        self.selected.remove(&id);
+                    }
+                    return Command::batch(vec![command, self.update(PlantTableMessage::Load)]);
+                }
+                return command;
+            }
+        }
+        // A new page, search or order can show rows whose readings were not requested yet
+        self.load_page()
+    }
+
+    /// Returns the search, the table with the displayed page and the page buttons
+    pub fn view(&self) -> Element<'_, PlantTableMessage> {
+        let search = TextInput::new(
+            "Suche nach Name, Art, Standort, Gruppe oder Status",
+            &self.query,
+        )
+        .size(TEXT_SIZE)
+        .width(Length::from(COLUMN_WIDTH * 5))
+        .on_input(PlantTableMessage::Search);
+        let select_all = Checkbox::new("", self.all_selected(), PlantTableMessage::SelectAll)
+            .text_size(TEXT_SIZE);
+        let header = TableColumn::ALL.iter().fold(
+            Row::new().align_items(Center).push(select_all),
+            |row, column| {
+                let arrow = match (self.sort == Some(*column), self.ascending) {
+                    (true, true) => " ↑",
+                    (true, false) => " ↓",
+                    _ => "",
+                };
+                row.push(
+                    Button::new(Text::new(format!("{}{}", column, arrow)).size(TEXT_SIZE))
+                        .style(theme::Button::Secondary)
+                        .width(Length::from(COLUMN_WIDTH))
+                        .on_press(PlantTableMessage::Sort(*column)),
+                )
+            },
+        );
+        let rows = self
+            .page_rows()
+            .into_iter()
+            .fold(Column::new(), |column, plant| {
+                let cells = TableColumn::ALL.iter().fold(Row::new(), |row, field| {
+                    let text = Text::new(plant.field(*field))
+                        .size(TEXT_SIZE - 8)
+                        .width(Length::from(COLUMN_WIDTH));
+                    row.push(match field {
+                        TableColumn::Status => match plant.state {
+                            RowState::Fehler(_) => text.style(URGENT_COLOR),
+                            _ => text.style(plant.status.color()),
+                        },
+                        _ => text,
+                    })
+                });
+                let id = plant.id.clone();
+                column.push(
+                    Row::new()
+                        .align_items(Center)
+                        .push(
+                            Checkbox::new("", self.selected.contains(&plant.id), move |selected| {
+                                PlantTableMessage::Toggle(id.clone(), selected)
+                            })
+                            .text_size(TEXT_SIZE),
+                        )
+                        .push(
+                            Button::new(cells)
+                                .style(theme::Button::Text)
+                                .padding(5)
+                                .on_press(PlantTableMessage::Open(plant.id.clone())),
+                        ),
+                )
+            });
+        let pages = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(
+                Button::new(Text::new("Zurück").size(TEXT_SIZE))
+                    .on_press(PlantTableMessage::PreviousPage),
+            )
+            .push(
+                Text::new(format!(
+                    "Seite {} von {} ({} Pflanzen)",
+                    self.page + 1,
+                    self.pages(),
+                    self.visible.len()
+                ))
+                .size(TEXT_SIZE),
+            )
+            .push(
+                Button::new(Text::new("Weiter").size(TEXT_SIZE))
+                    .on_press(PlantTableMessage::NextPage),
+            )
+            .push(
+                Button::new(Text::new("Refresh").size(TEXT_SIZE)).on_press(PlantTableMessage::Load),
+            );
+        let mut table = Column::new().spacing(10).align_items(Center).push(search);
+        if !self.selected.is_empty() || !self.bulk.items.is_empty() {
+            table = table.push(
+                self.bulk
+                    .view(self.selected.len())
+                    .map(PlantTableMessage::Bulk),
+            );
+        }
+        let table: Element<'_, PlantTableMessage> = table
+            .push(header)
+            .push(scrollable::Scrollable::new(rows).height(Length::Fill))
+            .push(pages)
+            .push(Text::new(&self.status).size(TEXT_SIZE))
+            .into();
+        match &self.bulk.confirm_delete {
+            Some(confirmation) => confirmation.view(
+                table,
+                PlantTableMessage::Bulk(BulkMessage::ConfirmDelete),
+                PlantTableMessage::Bulk(BulkMessage::CancelDelete),
+            ),
+            None => table,
+        }
+    }
+}
+
+/// Compares two texts ignoring the case
+fn compare_text(a: &str, b: &str) -> Ordering {
+    a.to_lowercase().cmp(&b.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bulk::BulkItem;
+
+    fn row(id: &str, name: &str, species: &str, group: &str, status: Status) -> PlantRow {
+        PlantRow {
+            id: id.to_string(),
+            name: name.to_string(),
+            species: species.to_string(),
+            location: "Küche".to_string(),
+            group: PlantGroupMetadata {
+                name: group.to_string(),
+                ..PlantGroupMetadata::default()
+            },
+            status,
+            state: RowState::Geladen,
+        }
+    }
+
+    fn table() -> PlantTable {
+        let mut table = PlantTable::new();
+        table.set_rows(vec![
+            row("1", "Basilikum", "Ocimum basilicum", "Kräuter", Status::Gut),
+            row("2", "Minze", "Mentha", "Kräuter", Status::Kritisch),
+            row(
+                "3",
+                "Monstera",
+                "Monstera deliciosa",
+                "Zimmer",
+                Status::Warnung,
+            ),
+        ]);
+        table
+    }
+
+    fn ids(table: &PlantTable) -> Vec<&str> {
+        table
+            .page_rows()
+            .iter()
+            .map(|row| row.id.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "Basilikum"), Some(0));
+        assert!(fuzzy_score("bsl", "Basilikum").is_some());
+        assert_eq!(fuzzy_score("lsb", "Basilikum"), None);
+        // Consecutive characters at the start of a word are the best match
+        assert!(fuzzy_score("bas", "Basilikum") > fuzzy_score("bas", "Abendsonne Basis"));
+        assert_eq!(
+            fuzzy_score("BAS", "basilikum"),
+            fuzzy_score("bas", "Basilikum")
+        );
+    }
+
+    #[test]
+    fn test_search() {
+        let mut table = table();
+        assert_eq!(ids(&table), vec!["1", "2", "3"]);
+        let _ = table.update(PlantTableMessage::Search("krt".to_string()));
+        assert_eq!(ids(&table), vec!["1", "2"]);
+        // Every word has to match one of the columns
+        let _ = table.update(PlantTableMessage::Search("kräuter kritisch".to_string()));
+        assert_eq!(ids(&table), vec!["2"]);
+        let _ = table.update(PlantTableMessage::Search("monst".to_string()));
+        assert_eq!(ids(&table), vec!["3"]);
+        let _ = table.update(PlantTableMessage::Search("xyz".to_string()));
+        assert!(ids(&table).is_empty());
+        assert_eq!(table.pages(), 1);
+    }
+
+    #[test]
+    fn test_sort() {
+        let mut table = table();
+        let _ = table.update(PlantTableMessage::Sort(TableColumn::Status));
+        assert_eq!(ids(&table), vec!["1", "3", "2"]);
+        let _ = table.update(PlantTableMessage::Sort(TableColumn::Status));
+        assert_eq!(ids(&table), vec!["2", "3", "1"]);
+        let _ = table.update(PlantTableMessage::Sort(TableColumn::Art));
+        assert!(table.ascending);
+        assert_eq!(ids(&table), vec!["2", "3", "1"]);
+    }
+
+    #[test]
+    fn test_selection() {
+        let mut table = table();
+        let _ = table.update(PlantTableMessage::Search("krt".to_string()));
+        let _ = table.update(PlantTableMessage::SelectAll(true));
+        assert!(table.all_selected());
+        // The selection is kept when the search changes
+        let _ = table.update(PlantTableMessage::Search(String::new()));
+        assert!(!table.all_selected());
+        let _ = table.update(PlantTableMessage::Toggle("3".to_string(), true));
+        let _ = table.update(PlantTableMessage::Toggle("1".to_string(), false));
+        assert_eq!(
+            table.selected_plants(),
+            vec![
+                ("2".to_string(), "Minze".to_string()),
+                ("3".to_string(), "Monstera".to_string())
+            ]
+        );
+        // Removed plants are deselected
+        table.set_rows(vec![row("3", "Monstera", "", "Zimmer", Status::Gut)]);
+        assert_eq!(table.selected.len(), 1);
+        let _ = table.update(PlantTableMessage::SelectAll(false));
+        assert!(table.selected.is_empty());
+    }
+
+    #[test]
+    fn test_bulk_keeps_selection_while_running() {
+        let mut table = table();
+        let _ = table.update(PlantTableMessage::SelectAll(true));
+        table.bulk.items = table
+            .selected_plants()
+            .into_iter()
+            .map(|(id, name)| BulkItem {
+                id,
+                name,
+                result: None,
+            })
+            .collect();
+        let _ = table.update(PlantTableMessage::Bulk(BulkMessage::Done(
+            "1".to_string(),
+            Ok(()),
+        )));
+        let _ = table.update(PlantTableMessage::Bulk(BulkMessage::Done(
+            "2".to_string(),
+            Err("500 Internal Server Error".to_string()),
+        )));
+        assert!(table.bulk.is_running());
+        assert_eq!(table.selected.len(), 3);
+    }
+
+    #[test]
+    fn test_pages() {
+        let mut table = PlantTable::new();
+        table.set_rows(
+            (0..PAGE_SIZE * 2 + 5)
+                .map(|index| row(&index.to_string(), "Pflanze", "", "", Status::Gut))
+                .collect(),
+        );
+        assert_eq!(table.pages(), 3);
+        assert_eq!(table.page_rows().len(), PAGE_SIZE);
+        let _ = table.update(PlantTableMessage::NextPage);
+        let _ = table.update(PlantTableMessage::NextPage);
+        let _ = table.update(PlantTableMessage::NextPage);
+        assert_eq!(table.page, 2);
+        assert_eq!(table.page_rows().len(), 5);
+        // A new search starts on the first page
+        let _ = table.update(PlantTableMessage::Search("pfl".to_string()));
+        assert_eq!(table.page, 0);
+    }
+
+    #[test]
+    fn test_rows_load_lazily() {
+        let mut table = PlantTable::new();
+        let rows = (0..PAGE_SIZE + 5)
+            .map(|index| PlantRow::new(format!("{:03}", index), format!("Pflanze {:03}", index)))
+            .collect_vec();
+        table.set_rows(rows);
+        // The rows are shown with their names before any metadata is loaded
+        assert_eq!(table.page_rows().len(), PAGE_SIZE);
+        assert_eq!(table.rows[0].field(TableColumn::Name), "Pflanze 000");
+        assert_eq!(table.rows[0].field(TableColumn::Status), "Lädt...");
+
+        // Only the rows of the displayed page are requested, and only once
+        let plants = table.take_unloaded();
+        assert_eq!(plants.len(), PAGE_SIZE);
+        assert_eq!(plants[0], ("000".to_string(), "Pflanze 000".to_string()));
+        assert!(table.take_unloaded().is_empty());
+
+        let group = PlantGroupMetadata {
+            name: "Kräuter".to_string(),
+            ..PlantGroupMetadata::default()
+        };
+        let _ = table.update(PlantTableMessage::ReadingsLoaded(vec![
+            (
+                "000".to_string(),
+                "Pflanze 000".to_string(),
+                Ok(PlantReadings {
+                    species: "Ocimum basilicum".to_string(),
+                    group,
+                    ..PlantReadings::default()
+                }),
+            ),
+            (
+                "001".to_string(),
+                "Pflanze 001".to_string(),
+                Err("timeout".to_string()),
+            ),
+        ]));
+        assert_eq!(table.rows[0].state, RowState::Geladen);
+        assert_eq!(table.rows[0].field(TableColumn::Art), "Ocimum basilicum");
+        assert_eq!(table.rows[0].field(TableColumn::Gruppe), "Kräuter");
+        assert_eq!(table.rows[0].field(TableColumn::Status), "Unbekannt");
+        assert_eq!(table.rows[1].field(TableColumn::Name), "Pflanze 001");
+        assert_eq!(table.rows[1].field(TableColumn::Status), "Fehler: timeout");
+
+        table.page = 1;
+        assert_eq!(
+            table
+                .take_unloaded()
+                .into_iter()
+                .map(|(id, _)| id)
+                .collect_vec(),
+            vec!["050", "051", "052", "053", "054"]
+        );
+    }
+
+    #[test]
+    fn test_failed_groups_are_shown() {
+        let mut table = PlantTable::new();
+        let _ = table.update(PlantTableMessage::GroupsLoaded(Err("timeout".to_string())));
+        assert_eq!(table.bulk.group.error, "Gruppen nicht geladen: timeout");
+    }
+}
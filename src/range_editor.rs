@@ -0,0 +1,698 @@
+use crate::detail::{Sensortypes, URGENT_COLOR};
+use crate::graphs::{factor, format_number, graph_points, unit_symbol};
+use crate::requests::{GraphData, PlantGroupMetadata, SensorRange, SensorType};
+use crate::TEXT_SIZE;
+use iced::alignment::{Horizontal, Vertical};
+use iced::mouse;
+use iced::widget::canvas::{self, event, Cursor, Frame, Geometry, Path, Stroke};
+use iced::widget::{scrollable, Canvas, Column, Row, Text, TextInput};
+use iced::{Color, Element, Length, Point, Rectangle, Size, Theme};
+
+/// The preview shows the readings of this many minutes
+pub const PREVIEW_MINUTES: i64 = 7 * 24 * 60;
+/// The radius of the handles of the slider
+const HANDLE_RADIUS: f32 = 8.0;
+/// The height of the slider
+const SLIDER_HEIGHT: u16 = 30;
+/// The height of the preview
+const PREVIEW_HEIGHT: u16 = 70;
+/// The height of the scrollable list of all sensors
+const EDITOR_HEIGHT: u16 = 400;
+/// The color of the range
+const RANGE_COLOR: Color = Color {
+    r: 0.05,
+    g: 0.67,
+    b: 0.46,
+    a: 1.0,
+};
+
+/// Contains the two ends of a range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeHandle {
+    Min,
+    Max,
+}
+
+/// Contains all messages of the range editor
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeEditorMessage {
+    /// Updates the minimum input of the sensor
+    Min(Sensortypes, String),
+    /// Updates the maximum input of the sensor
+    Max(Sensortypes, String),
+    /// Moves a handle of the slider of the sensor to the value in the unit of the server
+    Dragged(Sensortypes, RangeHandle, i32),
+}
+
+/// Returns the usual scale of the sensor in the unit of the server
+fn default_scale(sensor: Sensortypes) -> (i32, i32) {
+    let (low, high) = match sensor {
+        Sensortypes::Feuchtigkeit | Sensortypes::Luftfeuchtigkeit => (0, 100),
+        Sensortypes::Temperatur => (-10, 50),
+        Sensortypes::Licht => (0, 20_000),
+    };
+    (low * factor(sensor), high * factor(sensor))
+}
+
+/// Parses an input in the unit shown to the user into the unit of the server
+pub fn parse_input(sensor: Sensortypes, input: &str) -> Result<i32, String> {
+    let input = input.trim();
+    input
+        .replace(',', ".")
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())
+        .map(|value| (value * factor(sensor) as f64).round() as i32)
+        .ok_or_else(|| format!("{} ist keine Zahl", input))
+}
+
+/// Returns the part of the readings within the range, `None` if there are no readings
+pub fn share_inside(points: &[(i32, i32)], (min, max): (i32, i32)) -> Option<f64> {
+    if points.is_empty() {
+        return None;
+    }
+    let inside = points
+        .iter()
+        .filter(|(_, value)| (min..=max).contains(value))
+        .count();
+    Some(inside as f64 / points.len() as f64)
+}
+
+/// The inputs of the range of one sensor
+///
+/// Fields:
+/// * `sensor` - The sensor
+/// * `min` - The input of the minimum in the unit shown to the user
+/// * `max` - The input of the maximum in the unit shown to the user
+/// * `preview` - The readings of the last week as minutes and values of the server
+/// * `touched` - Indicates if the user changed the range, errors are only shown afterwards
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorRangeInput {
+    pub sensor: Sensortypes,
+    pub min: String,
+    pub max: String,
+    pub preview: Vec<(i32, i32)>,
+    pub touched: bool,
+}
+
+impl SensorRangeInput {
+    /// Creates empty inputs for the sensor
+    pub fn new(sensor: Sensortypes) -> Self {
+        SensorRangeInput {
+            sensor,
+            min: String::new(),
+            max: String::new(),
+            preview: vec![],
+            touched: false,
+        }
+    }
+
+    /// Returns min and max in the unit of the server, `None` if both inputs are empty
+    ///
+    /// The minimum has to be below the maximum, see `SensorRange::bounds`.
+    pub fn range(&self) -> Result<Option<(i32, i32)>, String> {
+        match (self.min.trim().is_empty(), self.max.trim().is_empty()) {
+            (true, true) => return Ok(None),
+            (false, false) => {}
+            _ => return Err("Bitte Minimum und Maximum eingeben".to_string()),
+        }
+        let min = parse_input(self.sensor, &self.min)?;
+        let max = parse_input(self.sensor, &self.max)?;
+        if min >= max {
+            return Err("Das Maximum muss größer als das Minimum sein".to_string());
+        }
+        Ok(Some((min, max)))
+    }
+
+    /// Returns the lowest and highest value of the slider and the preview
+    ///
+    /// The usual scale of the sensor is widened to contain the readings and the entered range.
+    pub fn bounds(&self) -> (i32, i32) {
+        let (low, high) = default_scale(self.sensor);
+        let entered = [&self.min, &self.max]
+            .into_iter()
+            .filter_map(|input| parse_input(self.sensor, input).ok());
+        self.preview
+            .iter()
+            .map(|(_, value)| *value)
+            .chain(entered)
+            .fold((low, high), |(low, high), value| {
+                (low.min(value), high.max(value))
+            })
+    }
+
+    /// Returns the positions of the handles, the ends of the slider if the range is not set
+    pub fn handles(&self) -> (i32, i32) {
+        let (low, high) = self.bounds();
+        let min = parse_input(self.sensor, &self.min).unwrap_or(low);
+        let max = parse_input(self.sensor, &self.max).unwrap_or(high);
+        (min, max)
+    }
+
+    /// Moves a handle, it can not be moved past the other one
+    pub fn drag(&mut self, handle: RangeHandle, value: i32) {
+        let (min, max) = self.handles();
+        let (min, max) = match handle {
+            RangeHandle::Min => (value.min(max), max),
+            RangeHandle::Max => (min, value.max(min)),
+        };
+        self.min = format_number(self.sensor, min);
+        self.max = format_number(self.sensor, max);
+        self.touched = true;
+    }
+
+    /// Returns the text below the preview
+    fn preview_label(&self) -> String {
+        match (self.range(), share_inside(&self.preview, self.handles())) {
+            (_, None) => "Keine Messwerte der letzten Woche".to_string(),
+            (Ok(None), _) => "Kein Bereich gesetzt".to_string(),
+            (_, Some(share)) => format!(
+                "{:.0} % der Messwerte der letzten Woche liegen im Bereich",
+                share * 100.0
+            ),
+        }
+    }
+
+    /// Returns the inputs, the slider, the preview and the error of the sensor
+    fn view(&self) -> Element<'_, RangeEditorMessage> {
+        let sensor = self.sensor;
+        let unit = unit_symbol(sensor);
+        let mut column = Column::new()
+            .spacing(5)
+            .push(Text::new(format!("{} ({})", sensor, unit)).size(TEXT_SIZE))
+            .push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        TextInput::new("Min", &self.min)
+                            .size(TEXT_SIZE)
+                            .on_input(move |input| RangeEditorMessage::Min(sensor, input)),
+                    )
+                    .push(
+                        TextInput::new("Max", &self.max)
+                            .size(TEXT_SIZE)
+                            .on_input(move |input| RangeEditorMessage::Max(sensor, input)),
+                    ),
+            )
+            .push(
+                Canvas::new(RangeSlider {
+                    sensor,
+                    bounds: self.bounds(),
+                    handles: self.handles(),
+                    set: matches!(self.range(), Ok(Some(_))),
+                })
+                .width(Length::Fill)
+                .height(Length::from(SLIDER_HEIGHT)),
+            )
+            .push(
+                Canvas::new(RangePreview {
+                    points: &self.preview,
+                    bounds: self.bounds(),
+                    range: self.range().ok().flatten(),
+                })
+                .width(Length::Fill)
+                .height(Length::from(PREVIEW_HEIGHT)),
+            )
+            .push(Text::new(self.preview_label()).size(TEXT_SIZE - 10));
+        if let (true, Err(error)) = (self.touched, self.range()) {
+            column = column.push(Text::new(error).size(TEXT_SIZE - 8).style(URGENT_COLOR));
+        }
+        column.into()
+    }
+}
+
+/// Edits the ranges of all sensors of a group
+///
+/// Fields:
+/// * `inputs` - The inputs of the sensors, in the order of `Sensortypes::iter`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeEditor {
+    pub inputs: Vec<SensorRangeInput>,
+}
+
+impl Default for RangeEditor {
+    fn default() -> Self {
+        RangeEditor {
+            inputs: Sensortypes::iter().map(SensorRangeInput::new).collect(),
+        }
+    }
+}
+
+impl RangeEditor {
+    /// Creates an editor with empty ranges
+    pub fn new() -> Self {
+        RangeEditor::default()
+    }
+
+    /// Creates the editor filled with the ranges of a group, unset ranges stay empty
+    pub fn from_group(group: &PlantGroupMetadata) -> Self {
+        let mut editor = RangeEditor::new();
+        for range in &group.sensorRanges {
+            let (min, max) = match range.bounds() {
+                Some(bounds) => bounds,
+                None => continue,
+            };
+            if let Some(input) = Sensortypes::from_name(&range.sensorType.name)
+                .and_then(|sensor| editor.input_mut(sensor))
+            {
+                input.min = format_number(input.sensor, min);
+                input.max = format_number(input.sensor, max);
+            }
+        }
+        editor
+    }
+
+    /// Returns the inputs of the sensor
+    fn input_mut(&mut self, sensor: Sensortypes) -> Option<&mut SensorRangeInput> {
+        self.inputs.iter_mut().find(|input| input.sensor == sensor)
+    }
+
+    /// Sets the readings of the last week shown in the preview of the sensor
+    pub fn set_preview(&mut self, sensor: Sensortypes, graph_data: &[GraphData]) {
+        if let Some(input) = self.input_mut(sensor) {
+            input.preview = graph_points(graph_data);
+        }
+    }
+
+    /// Returns if the ranges of all sensors are valid
+    pub fn is_valid(&self) -> bool {
+        self.inputs.iter().all(|input| input.range().is_ok())
+    }
+
+    /// Shows the errors of all sensors, e.g. after a rejected submit
+    pub fn touch_all(&mut self) {
+        self.inputs
+            .iter_mut()
+            .for_each(|input| input.touched = true);
+    }
+
+    /// Writes the ranges into the metadata of a group, empty ranges are stored unset, see
+    /// `SensorRange::bounds`
+    pub fn apply_to(&self, group: &mut PlantGroupMetadata) {
+        for input in &self.inputs {
+            let (min, max) = input.range().ok().flatten().unwrap_or((0, 0));
+            match group
+                .sensorRanges
+                .iter_mut()
+                .find(|range| range.sensorType.name == input.sensor.get_name())
+            {
+                Some(range) => {
+                    range.min = min;
+                    range.max = max;
+                }
+                None => group.sensorRanges.push(SensorRange {
+                    sensorType: SensorType {
+                        name: input.sensor.get_name(),
+                        unit: input.sensor.get_unit(),
+                    },
+                    min,
+                    max,
+                }),
+            }
+        }
+    }
+
+    /// Handles the messages of the range editor
+    pub fn update(&mut self, message: RangeEditorMessage) {
+        match message {
+            RangeEditorMessage::Min(sensor, value) => {
+                if let Some(input) = self.input_mut(sensor) {
+                    input.min = value;
+                    input.touched = true;
+                }
+            }
+            RangeEditorMessage::Max(sensor, value) => {
+                if let Some(input) = self.input_mut(sensor) {
+                    input.max = value;
+                    input.touched = true;
+                }
+            }
+            RangeEditorMessage::Dragged(sensor, handle, value) => {
+                if let Some(input) = self.input_mut(sensor) {
+                    input.drag(handle, value);
+                }
+            }
+        }
+    }
+
+    /// Returns the editors of all sensors below each other in a scrollable list
+    pub fn view(&self) -> Element<'_, RangeEditorMessage> {
+        scrollable(
+            self.inputs
+                .iter()
+                .fold(Column::new().spacing(15), |column, input| {
+                    column.push(input.view())
+                }),
+        )
+        .height(Length::from(EDITOR_HEIGHT))
+        .into()
+    }
+}
+
+/// Returns the x position of a value on a scale drawn across the width
+fn to_x((low, high): (i32, i32), value: i32, width: f32) -> f32 {
+    let part = (value - low) as f32 / (high - low).max(1) as f32;
+    HANDLE_RADIUS + part.clamp(0.0, 1.0) * (width - 2.0 * HANDLE_RADIUS)
+}
+
+/// Returns the value at the x position on a scale drawn across the width
+fn from_x((low, high): (i32, i32), x: f32, width: f32) -> i32 {
+    let part = ((x - HANDLE_RADIUS) / (width - 2.0 * HANDLE_RADIUS)).clamp(0.0, 1.0);
+    low + (part * (high - low) as f32).round() as i32
+}
+
+/// A slider with a handle for the minimum and one for the maximum
+///
+/// Fields:
+/// * `sensor` - The sensor of the range
+/// * `bounds` - The lowest and highest value of the slider
+/// * `handles` - The values of the handles
+/// * `set` - Indicates if a valid range is entered, otherwise the range is drawn grey
+struct RangeSlider {
+    sensor: Sensortypes,
+    bounds: (i32, i32),
+    handles: (i32, i32),
+    set: bool,
+}
+
+impl RangeSlider {
+    /// Returns the value at the position, rounded to whole units shown to the user
+    fn value_at(&self, x: f32, width: f32) -> i32 {
+        let factor = factor(self.sensor);
+        let value = from_x(self.bounds, x, width) as f64 / factor as f64;
+        value.round() as i32 * factor
+    }
+}
+
+impl canvas::Program<RangeEditorMessage> for RangeSlider {
+    type State = Option<RangeHandle>;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<RangeEditorMessage>) {
+        let dragged = |handle, position: Point| {
+            Some(RangeEditorMessage::Dragged(
+                self.sensor,
+                handle,
+                self.value_at(position.x - bounds.x, bounds.width),
+            ))
+        };
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let position = match cursor.position_in(&bounds) {
+                    Some(position) => position,
+                    None => return (event::Status::Ignored, None),
+                };
+                // The closer handle is moved, the maximum if both are at the same place
+                let min_x = to_x(self.bounds, self.handles.0, bounds.width);
+                let max_x = to_x(self.bounds, self.handles.1, bounds.width);
+                let handle = if (position.x - min_x).abs() < (position.x - max_x).abs() {
+                    RangeHandle::Min
+                } else {
+                    RangeHandle::Max
+                };
+                *state = Some(handle);
+                (
+                    event::Status::Captured,
+                    dragged(handle, Point::new(position.x + bounds.x, 0.0)),
+                )
+            }
+            canvas::Event::Mouse(mouse::Event::CursorMoved { position }) => match *state {
+                Some(handle) => (event::Status::Captured, dragged(handle, position)),
+                None => (event::Status::Ignored, None),
+            },
+            canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                if state.is_some() =>
+            {
+                *state = None;
+                (event::Status::Captured, None)
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let y = bounds.height / 2.0;
+        let min_x = to_x(self.bounds, self.handles.0, bounds.width);
+        let max_x = to_x(self.bounds, self.handles.1, bounds.width);
+        frame.stroke(
+            &Path::line(
+                Point::new(HANDLE_RADIUS, y),
+                Point::new(bounds.width - HANDLE_RADIUS, y),
+            ),
+            Stroke::default()
+                .with_color(Color::from_rgb(0.85, 0.85, 0.85))
+                .with_width(4.0),
+        );
+        let color = if self.set {
+            RANGE_COLOR
+        } else {
+            Color::from_rgb(0.6, 0.6, 0.6)
+        };
+        frame.stroke(
+            &Path::line(Point::new(min_x, y), Point::new(max_x, y)),
+            Stroke::default().with_color(color).with_width(4.0),
+        );
+        for x in [min_x, max_x] {
+            frame.fill(&Path::circle(Point::new(x, y), HANDLE_RADIUS), color);
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+/// The readings of the last week with the range drawn as a band
+///
+/// Fields:
+/// * `points` - The readings as minutes and values of the server
+/// * `bounds` - The lowest and highest value of the y axis
+/// * `range` - The entered range, `None` if it is not set or invalid
+struct RangePreview<'a> {
+    points: &'a [(i32, i32)],
+    bounds: (i32, i32),
+    range: Option<(i32, i32)>,
+}
+
+impl<'a, M> canvas::Program<M> for RangePreview<'a> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let mut frame = Frame::new(bounds.size());
+        let (low, high) = self.bounds;
+        let to_y = |value: i32| {
+            let part = (value - low) as f32 / (high - low).max(1) as f32;
+            bounds.height * (1.0 - part.clamp(0.0, 1.0))
+        };
+        frame.fill_rectangle(
+            Point::ORIGIN,
+            bounds.size(),
+            Color::from_rgb(0.95, 0.95, 0.95),
+        );
+        if let Some((min, max)) = self.range {
+            frame.fill_rectangle(
+                Point::new(0.0, to_y(max)),
+                Size::new(bounds.width, (to_y(min) - to_y(max)).max(1.0)),
+                Color {
+                    a: 0.3,
+                    ..RANGE_COLOR
+                },
+            );
+        }
+        let (first, last) = match (self.points.first(), self.points.last()) {
+            (Some(first), Some(last)) => (first.0, last.0),
+            _ => {
+                frame.fill_text(canvas::Text {
+                    content: "-".to_string(),
+                    position: frame.center(),
+                    horizontal_alignment: Horizontal::Center,
+                    vertical_alignment: Vertical::Center,
+                    ..canvas::Text::default()
+                });
+                return vec![frame.into_geometry()];
+            }
+        };
+        let to_x =
+            |minutes: i32| (minutes - first) as f32 / (last - first).max(1) as f32 * bounds.width;
+        let line = Path::new(|builder| {
+            for (index, (minutes, value)) in self.points.iter().enumerate() {
+                let point = Point::new(to_x(*minutes), to_y(*value));
+                if index == 0 {
+                    builder.move_to(point);
+                } else {
+                    builder.line_to(point);
+                }
+            }
+        });
+        frame.stroke(
+            &line,
+            Stroke::default()
+                .with_color(Color::from_rgb(0.3, 0.3, 0.3))
+                .with_width(1.5),
+        );
+        if let Some((min, max)) = self.range {
+            for (minutes, value) in self.points {
+                if !(min..=max).contains(value) {
+                    frame.fill(
+                        &Path::circle(Point::new(to_x(*minutes), to_y(*value)), 2.0),
+                        URGENT_COLOR,
+                    );
+                }
+            }
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+
+    fn input(sensor: Sensortypes, min: &str, max: &str) -> SensorRangeInput {
+        SensorRangeInput {
+            min: min.to_string(),
+            max: max.to_string(),
+            ..SensorRangeInput::new(sensor)
+        }
+    }
+
+    #[test]
+    fn test_parse_input() {
+        assert_eq!(parse_input(Sensortypes::Feuchtigkeit, "60"), Ok(6000));
+        assert_eq!(parse_input(Sensortypes::Feuchtigkeit, " 42,5 "), Ok(4250));
+        assert_eq!(parse_input(Sensortypes::Temperatur, "-5"), Ok(-5));
+        assert_eq!(
+            parse_input(Sensortypes::Licht, "hell"),
+            Err("hell ist keine Zahl".to_string())
+        );
+    }
+
+    #[test]
+    fn test_range() {
+        assert_eq!(input(Sensortypes::Temperatur, "", "").range(), Ok(None));
+        assert_eq!(
+            input(Sensortypes::Temperatur, "15", "25").range(),
+            Ok(Some((15, 25)))
+        );
+        assert_eq!(
+            input(Sensortypes::Feuchtigkeit, "30", "60").range(),
+            Ok(Some((3000, 6000)))
+        );
+        assert!(input(Sensortypes::Temperatur, "15", "").range().is_err());
+        assert!(input(Sensortypes::Temperatur, "abc", "25").range().is_err());
+        assert_eq!(
+            input(Sensortypes::Temperatur, "25", "15").range(),
+            Err("Das Maximum muss größer als das Minimum sein".to_string())
+        );
+        assert_eq!(
+            input(Sensortypes::Temperatur, "20", "20").range(),
+            Err("Das Maximum muss größer als das Minimum sein".to_string())
+        );
+    }
+
+    #[test]
+    fn test_drag_keeps_min_below_max() {
+        let mut input = input(Sensortypes::Temperatur, "15", "25");
+        input.drag(RangeHandle::Min, 30);
+        assert_eq!((input.min.as_str(), input.max.as_str()), ("25", "25"));
+        input.drag(RangeHandle::Max, 40);
+        assert_eq!(input.range(), Ok(Some((25, 40))));
+        // Without a range the handles start at the ends of the slider
+        let mut unset = SensorRangeInput::new(Sensortypes::Feuchtigkeit);
+        unset.drag(RangeHandle::Min, 2000);
+        assert_eq!(unset.range(), Ok(Some((2000, 10_000))));
+        assert_eq!(from_x((0, 100), to_x((0, 100), 40, 200.0), 200.0), 40);
+    }
+
+    #[test]
+    fn test_editor_with_group() {
+        let mut group = PlantGroupMetadata::default();
+        group.sensorRanges[0].min = 3000;
+        group.sensorRanges[0].max = 6000;
+        let mut editor = RangeEditor::from_group(&group);
+        assert_eq!(editor.inputs[0].min, "30");
+        assert_eq!(editor.inputs[0].max, "60");
+        assert_eq!(editor.inputs[3].min, "");
+        assert!(editor.is_valid());
+
+        editor.update(RangeEditorMessage::Min(
+            Sensortypes::Temperatur,
+            "28".to_string(),
+        ));
+        editor.update(RangeEditorMessage::Max(
+            Sensortypes::Temperatur,
+            "18".to_string(),
+        ));
+        assert!(!editor.is_valid());
+        editor.update(RangeEditorMessage::Min(
+            Sensortypes::Temperatur,
+            "12".to_string(),
+        ));
+        editor.update(RangeEditorMessage::Min(
+            Sensortypes::Feuchtigkeit,
+            String::new(),
+        ));
+        editor.update(RangeEditorMessage::Max(
+            Sensortypes::Feuchtigkeit,
+            String::new(),
+        ));
+        editor.apply_to(&mut group);
+        assert_eq!(
+            (group.sensorRanges[0].min, group.sensorRanges[0].max),
+            (0, 0)
+        );
+        assert_eq!(
+            (group.sensorRanges[2].min, group.sensorRanges[2].max),
+            (12, 18)
+        );
+    }
+
+    #[test]
+    fn test_preview() {
+        let mut editor = RangeEditor::new();
+        editor.set_preview(
+            Sensortypes::Temperatur,
+            &[GraphData {
+                timestamps: vec![
+                    "2023-06-01T12:00:00.000Z".to_string(),
+                    "2023-06-01T11:00:00.000Z".to_string(),
+                    "2023-06-01T13:00:00.000Z".to_string(),
+                    "2023-06-01T14:00:00.000Z".to_string(),
+                ],
+                values: vec![20, 18, 26, 60],
+            }],
+        );
+        let input = &mut editor.inputs[2];
+        assert_eq!(
+            input.preview.iter().map(|point| point.1).collect_vec(),
+            vec![18, 20, 26, 60]
+        );
+        // The slider contains the readings
+        assert_eq!(input.bounds(), (-10, 60));
+        assert_eq!(input.preview_label(), "Kein Bereich gesetzt");
+        input.min = "15".to_string();
+        input.max = "25".to_string();
+        assert_eq!(share_inside(&input.preview, (15, 25)), Some(0.5));
+        assert_eq!(
+            input.preview_label(),
+            "50 % der Messwerte der letzten Woche liegen im Bereich"
+        );
+        assert_eq!(share_inside(&[], (15, 25)), None);
+    }
+}
@@ -0,0 +1,403 @@
+use crate::timezone::TimeZoneChoice;
+use crate::TEXT_SIZE;
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc,
+};
+use iced::widget::{Button, Column, Row, Text, TextInput};
+use iced::{theme, Element, Length};
+use iced_core::Alignment::Center;
+use std::fmt::{Display, Formatter};
+
+/// The format of the from and to inputs
+pub const INPUT_FORMAT: &str = "%d.%m.%Y %H:%M";
+
+/// Contains all predefined timeranges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangePreset {
+    /// The last 24 hours
+    Tag,
+    /// The last 7 days
+    Woche,
+    /// The last 30 days
+    Monat,
+    /// Since the start of the current meteorological season
+    Saison,
+    /// All readings
+    Gesamt,
+}
+
+impl RangePreset {
+    pub const ALL: [RangePreset; 5] = [
+        RangePreset::Tag,
+        RangePreset::Woche,
+        RangePreset::Monat,
+        RangePreset::Saison,
+        RangePreset::Gesamt,
+    ];
+
+    /// Returns the start of the preset relative to `now`
+    ///
+    /// The season starts at midnight in the given time zone.
+    pub fn start(&self, now: DateTime<Utc>, time_zone: TimeZoneChoice) -> DateTime<Utc> {
+        match self {
+            RangePreset::Tag => now - Duration::hours(24),
+            RangePreset::Woche => now - Duration::days(7),
+            RangePreset::Monat => now - Duration::days(30),
+            RangePreset::Saison => season_start(now, time_zone),
+            RangePreset::Gesamt => Utc.with_ymd_and_hms(2019, 1, 1, 0, 0, 0).unwrap(),
+        }
+    }
+}
+
+impl Display for RangePreset {
+    /// Returns the label of the preset
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RangePreset::Tag => write!(f, "24 Stunden"),
+            RangePreset::Woche => write!(f, "7 Tage"),
+            RangePreset::Monat => write!(f, "30 Tage"),
+            RangePreset::Saison => write!(f, "Diese Saison"),
+            RangePreset::Gesamt => write!(f, "Gesamt"),
+        }
+    }
+}
+
+/// Contains all messages of the range selector
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeMessage {
+    /// Selects a predefined timerange ending now
+    Preset(RangePreset),
+    /// Updates the input of the start
+    FromChanged(String),
+    /// Updates the input of the end
+    ToChanged(String),
+    /// Applies the from and to inputs
+    Apply,
+    /// Steps to the window before the current one
+    Previous,
+    /// Steps to the window after the current one
+    Next,
+    /// Shows the inputs in the newly selected time zone
+    TimeZoneChanged,
+}
+
+/// A selector for the timerange of the charts
+///
+/// Fields:
+/// * `from` - The start of the selected timerange in UTC
+/// * `to` - The end of the selected timerange in UTC
+/// * `preset` - The selected preset, `None` if the range was entered or stepped
+/// * `from_input` - The text of the start input in the selected time zone
+/// * `to_input` - The text of the end input in the selected time zone
+/// * `error` - The error of the last input, empty if the input was valid
+#[derive(Debug, Clone)]
+pub struct RangeSelector {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub preset: Option<RangePreset>,
+    pub from_input: String,
+    pub to_input: String,
+    pub error: String,
+}
+
+impl RangeSelector {
+    /// Creates a new range selector with the given preset
+    pub fn new(preset: RangePreset) -> Self {
+        let now = Utc::now();
+        let mut selector = RangeSelector {
+            from: preset.start(now, TimeZoneChoice::current()),
+            to: now,
+            preset: Some(preset),
+            from_input: String::new(),
+            to_input: String::new(),
+            error: String::new(),
+        };
+        selector.sync_inputs();
+        selector
+    }
+
+    /// Returns the selected timerange as UTC timestamps for the server
+    pub fn timerange(&self) -> (String, String) {
+        (
+            self.from.to_rfc3339_opts(SecondsFormat::Millis, true),
+            self.to.to_rfc3339_opts(SecondsFormat::Millis, true),
+        )
+    }
+
+    /// Writes the selected timerange into the inputs in the selected time zone
+    fn sync_inputs(&mut self) {
+        let time_zone = TimeZoneChoice::current();
+        self.from_input = time_zone.format(&self.from, INPUT_FORMAT);
+        self.to_input = time_zone.format(&self.to, INPUT_FORMAT);
+    }
+
+    /// Moves the window by its own length, the end never lies in the future
+    fn step(&mut self, forward: bool, now: DateTime<Utc>) {
+        let span = self.to - self.from;
+        if forward {
+            let to = (self.to + span).min(now);
+            self.from = to - span;
+            self.to = to;
+        } else {
+            self.to = self.from;
+            self.from -= span;
+        }
+        self.preset = None;
+    }
+
+    /// Returns if the window can be moved forward
+    pub fn has_next(&self) -> bool {
+        self.to < Utc::now() - Duration::minutes(1)
+    }
+
+    /// Handles the messages of the range selector
+    ///
+    /// Returns `true` if the timerange changed and the charts have to be reloaded.
+    pub fn update(&mut self, message: RangeMessage) -> bool {
+        match message {
+            RangeMessage::Preset(preset) => {
+                let now = Utc::now();
+                self.from = preset.start(now, TimeZoneChoice::current());
+                self.to = now;
+                self.preset = Some(preset);
+            }
+            RangeMessage::FromChanged(value) => {
+                self.from_input = value;
+                return false;
+            }
+            RangeMessage::ToChanged(value) => {
+                self.to_input = value;
+                return false;
+            }
+            RangeMessage::Apply => {
+                let time_zone = TimeZoneChoice::current();
+                let (from, to) = match (
+                    parse_input(&self.from_input, time_zone),
+                    parse_input(&self.to_input, time_zone),
+                ) {
+                    (Some(from), Some(to)) => (from, to),
+                    _ => {
+                        self.error =
+                            "Format: TT.MM.JJJJ HH:MM, die Zeit muss existieren".to_string();
+                        return false;
+                    }
+                };
+                if from >= to {
+                    self.error = "Der Start muss vor dem Ende liegen".to_string();
+                    return false;
+                }
+                self.from = from;
+                self.to = to;
+                self.preset = None;
+            }
+            RangeMessage::Previous => self.step(false, Utc::now()),
+            RangeMessage::Next => self.step(true, Utc::now()),
+            RangeMessage::TimeZoneChanged => {
+                self.sync_inputs();
+                return false;
+            }
+        }
+        self.error = String::new();
+        self.sync_inputs();
+        true
+    }
+
+    /// Returns the view of the range selector
+    pub fn view(&self) -> Element<'_, RangeMessage> {
+        let presets = RangePreset::ALL.iter().fold(Row::new(), |row, preset| {
+            let style = if self.preset == Some(*preset) {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            };
+            row.push(
+                Button::new(Text::new(preset.to_string()).size(TEXT_SIZE))
+                    .style(style)
+                    .on_press(RangeMessage::Preset(*preset)),
+            )
+            .spacing(20)
+        });
+        let next = Button::new(Text::new("Weiter").size(TEXT_SIZE));
+        let next = if self.has_next() {
+            next.on_press(RangeMessage::Next)
+        } else {
+            next
+        };
+        let inputs = Row::new()
+            .push(Button::new(Text::new("Zurück").size(TEXT_SIZE)).on_press(RangeMessage::Previous))
+            .spacing(20)
+            .push(
+                TextInput::new("Von", &self.from_input)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(200))
+                    .on_input(RangeMessage::FromChanged)
+                    .on_submit(RangeMessage::Apply),
+            )
+            .spacing(20)
+            .push(
+                TextInput::new("Bis", &self.to_input)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(200))
+                    .on_input(RangeMessage::ToChanged)
+                    .on_submit(RangeMessage::Apply),
+            )
+            .spacing(20)
+            .push(
+                Button::new(Text::new("Übernehmen").size(TEXT_SIZE)).on_press(RangeMessage::Apply),
+            )
+            .spacing(20)
+            .push(next)
+            .spacing(20)
+            .push(Text::new(&self.error).size(TEXT_SIZE))
+            .align_items(Center);
+        Column::new().push(presets).push(inputs).spacing(10).into()
+    }
+}
+
+/// Parses a date and time of the time zone in the format of the inputs
+///
+/// Returns `None` for times that were skipped by a daylight saving transition.
+pub fn parse_input(input: &str, time_zone: TimeZoneChoice) -> Option<DateTime<Utc>> {
+    let naive = NaiveDateTime::parse_from_str(input.trim(), INPUT_FORMAT).ok()?;
+    time_zone.to_utc(&naive)
+}
+
+/// Returns the start of the meteorological season at the given time in the time zone
+///
+/// Spring starts in march, summer in june, autumn in september and winter in december.
+pub fn season_start(now: DateTime<Utc>, time_zone: TimeZoneChoice) -> DateTime<Utc> {
+    let local = time_zone.to_local(&now);
+    let (year, month) = match local.month() {
+        1 | 2 => (local.year() - 1, 12),
+        month => (local.year(), month - (month % 3)),
+    };
+    let midnight = NaiveDate::from_ymd_opt(year, month, 1)
+        .unwrap()
+        .and_hms_opt(0, 0, 0)
+        .unwrap();
+    time_zone
+        .to_utc(&midnight)
+        .unwrap_or_else(|| Utc.from_utc_datetime(&midnight))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono_tz::Tz;
+
+    /// Returns the UTC time of a wall clock time in the selected time zone
+    fn local(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        let naive = NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap();
+        TimeZoneChoice::current().to_utc(&naive).unwrap()
+    }
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_season_start() {
+        let utc_zone = TimeZoneChoice::Zone(Tz::UTC);
+        assert_eq!(
+            season_start(utc(2023, 7, 14, 12), utc_zone),
+            utc(2023, 6, 1, 0)
+        );
+        assert_eq!(
+            season_start(utc(2023, 12, 24, 12), utc_zone),
+            utc(2023, 12, 1, 0)
+        );
+        assert_eq!(
+            season_start(utc(2024, 2, 10, 12), utc_zone),
+            utc(2023, 12, 1, 0)
+        );
+        assert_eq!(
+            season_start(utc(2023, 3, 1, 12), utc_zone),
+            utc(2023, 3, 1, 0)
+        );
+        // The season starts at the local midnight
+        let berlin = TimeZoneChoice::Zone(Tz::Europe__Berlin);
+        assert_eq!(
+            season_start(utc(2023, 7, 14, 12), berlin),
+            utc(2023, 5, 31, 22)
+        );
+    }
+
+    #[test]
+    fn test_timerange_is_utc() {
+        let mut selector = RangeSelector::new(RangePreset::Tag);
+        selector.from = utc(2023, 5, 1, 6);
+        selector.to = utc(2023, 5, 1, 18);
+        assert_eq!(
+            selector.timerange(),
+            (
+                "2023-05-01T06:00:00.000Z".to_string(),
+                "2023-05-01T18:00:00.000Z".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_input_in_time_zone() {
+        let berlin = TimeZoneChoice::Zone(Tz::Europe__Berlin);
+        assert_eq!(
+            parse_input("01.05.2023 08:00", berlin),
+            Some(utc(2023, 5, 1, 6))
+        );
+        assert_eq!(parse_input("26.03.2023 02:30", berlin), None);
+    }
+
+    #[test]
+    fn test_preset() {
+        let mut selector = RangeSelector::new(RangePreset::Tag);
+        assert!(selector.update(RangeMessage::Preset(RangePreset::Woche)));
+        assert_eq!(selector.to - selector.from, Duration::days(7));
+        assert_eq!(selector.preset, Some(RangePreset::Woche));
+        assert!(!selector.has_next());
+    }
+
+    #[test]
+    fn test_step_previous_and_next() {
+        let mut selector = RangeSelector::new(RangePreset::Tag);
+        let (from, to) = (selector.from, selector.to);
+        selector.update(RangeMessage::Previous);
+        assert_eq!(selector.to, from);
+        assert_eq!(selector.from, from - Duration::hours(24));
+        assert_eq!(selector.preset, None);
+        assert!(selector.has_next());
+        selector.update(RangeMessage::Next);
+        assert_eq!((selector.from, selector.to), (from, to));
+    }
+
+    #[test]
+    fn test_next_stops_at_now() {
+        let now = local(2023, 7, 14, 12);
+        let mut selector = RangeSelector::new(RangePreset::Tag);
+        selector.from = local(2023, 7, 13, 0);
+        selector.to = local(2023, 7, 14, 0);
+        selector.step(true, now);
+        assert_eq!(selector.to, now);
+        assert_eq!(selector.from, local(2023, 7, 13, 12));
+    }
+
+    #[test]
+    fn test_apply_inputs() {
+        let mut selector = RangeSelector::new(RangePreset::Tag);
+        selector.update(RangeMessage::FromChanged("01.05.2023 08:00".to_string()));
+        selector.update(RangeMessage::ToChanged("03.05.2023 20:30".to_string()));
+        assert!(selector.update(RangeMessage::Apply));
+        assert_eq!(selector.from, local(2023, 5, 1, 8));
+        assert_eq!(selector.error, "");
+        assert_eq!(selector.preset, None);
+
+        selector.update(RangeMessage::ToChanged("gestern".to_string()));
+        assert!(!selector.update(RangeMessage::Apply));
+        assert!(!selector.error.is_empty());
+
+        selector.update(RangeMessage::ToChanged("01.04.2023 08:00".to_string()));
+        assert!(!selector.update(RangeMessage::Apply));
+        assert_eq!(selector.from, local(2023, 5, 1, 8));
+    }
+}
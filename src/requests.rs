@@ -1,10 +1,13 @@
+use crate::detail::Sensortypes;
+use crate::group_picker::GroupOption;
+use crate::journal::CareEntry;
 use crate::login::PlantBuddyRole;
 use crate::management::User;
 use base64::{engine::general_purpose, Engine as _};
-use iced::futures::future::join_all;
+use iced::futures::stream::{self, StreamExt};
 use itertools::enumerate;
 use log::info;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::sync::Arc;
@@ -13,10 +16,16 @@ use tokio::sync::Mutex;
 /// The endpoint of our API
 const ENDPOINT: &str = "https://pb.mfloto.com/v1/";
 
+/// The number of plants whose details are requested at the same time
+const PARALLEL_REQUESTS: usize = 8;
+/// The error of a sensor without readings in the requested time range
+const NO_DATA: &str = "No data found";
+
 /// Represents the result of a request.
 pub type RequestResult<T> = Result<T, String>;
 
 ///`PlantMetadata` struct is used to represent the metadata related to a single plant.
+#[allow(non_snake_case)]
 #[derive(Deserialize, Debug, Clone, Default, Serialize, PartialEq)]
 pub struct PlantMetadata {
     /// * `name`: A string that represents the name of the plant.
@@ -35,6 +44,7 @@ pub struct PlantMetadata {
 }
 
 /// `PlantGroupMetadata` struct is used to represent the metadata related to a group of plants.
+#[allow(non_snake_case)]
 #[derive(Deserialize, Debug, Clone, Serialize, PartialEq)]
 pub struct PlantGroupMetadata {
     ///`id`: An integer that represents the id of the plant group. This field is not serialized when the `PlantGroupMetadata` is serialized.
@@ -97,6 +107,7 @@ impl Default for PlantGroupMetadata {
 }
 
 /// Represents a the SensorRagen for a given SensorType
+#[allow(non_snake_case)]
 #[derive(Deserialize, Debug, Clone, Default, Serialize, PartialEq)]
 pub struct SensorRange {
     #[serde(skip_serializing)]
@@ -105,6 +116,21 @@ pub struct SensorRange {
     pub max: i32,
 }
 
+impl SensorRange {
+    /// Returns the min and max of the range, `None` if it is unset
+    ///
+    /// An unset range is stored as 0;0. A range whose minimum is not below its maximum can not
+    /// be checked against either, so it counts as unset as well.
+    pub fn bounds(&self) -> Option<(i32, i32)> {
+        SensorRange::checked((self.min, self.max))
+    }
+
+    /// Returns the given min and max if they form a range, see `bounds`
+    pub fn checked((min, max): (i32, i32)) -> Option<(i32, i32)> {
+        (min < max).then_some((min, max))
+    }
+}
+
 /// Represents a sensor type
 #[derive(Deserialize, Debug, Clone, Default, Serialize, PartialEq)]
 pub struct SensorType {
@@ -113,12 +139,31 @@ pub struct SensorType {
 }
 
 /// Represents Graphs data to display
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Debug, Clone, PartialEq)]
 pub struct GraphData {
     pub values: Vec<i32>,
     pub timestamps: Vec<String>,
 }
 
+/// Represents the recent readings of a plant together with the ranges of its group
+///
+/// Fields:
+/// * `id` - The id of the plant
+/// * `name` - The name of the plant
+/// * `species` - The species of the plant
+/// * `location` - The location of the plant
+/// * `group` - The group of the plant with its sensor ranges
+/// * `readings` - The readings of every sensor, keyed by the sensor name of the server
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlantReadings {
+    pub id: String,
+    pub name: String,
+    pub species: String,
+    pub location: String,
+    pub group: PlantGroupMetadata,
+    pub readings: Vec<(String, GraphData)>,
+}
+
 /// Represents a temporary user returned by the login API.
 #[derive(Deserialize, Debug)]
 struct TempUser {
@@ -189,77 +234,69 @@ impl ApiClient {
         sensor_type: String,
         time_range: (String, String),
     ) -> RequestResult<Vec<(GraphData, String)>> {
-        let client = self.client.lock().await;
-        let mut tasks = vec![];
-
-        for id in ids {
-            let type_clone = sensor_type.clone();
-            let time_range_clone = time_range.clone();
-            info!("Getting time range: {:?}", time_range_clone);
-            let client = client.clone();
-            let mut parameter = String::new();
-            if plant {
-                parameter = format!(
-                    "{}sensor-data?sensor={}&plant={}&from={}&to={}",
-                    ENDPOINT,
-                    type_clone,
-                    id,
-                    time_range_clone.0.clone(),
-                    time_range_clone.1.clone()
-                );
-            } else {
-                parameter = format!(
-                    "{}sensor-data?sensor={}&plantGroup={}&from={}&to={}",
-                    ENDPOINT,
-                    type_clone,
-                    id,
-                    time_range_clone.0.clone(),
-                    time_range_clone.1.clone()
-                );
-            }
-            let task = tokio::spawn(async move {
-                let response = client
-                    .get(parameter)
-                    .send()
-                    .await
-                    .map_err(|e| e.to_string())?;
-
-                let text = response.text().await.map_err(|e| e.to_string())?;
-                if text != "{\"data\":null}" {
-                    let value: Value = serde_json::from_str(&text).unwrap();
-                    let data = value.get("data").unwrap();
-                    let mut values = vec![];
-                    let mut timestamps = vec![];
-                    data.as_array().unwrap().iter().for_each(|x| {
-                        if type_clone == "temperature" {
-                            let value = x.get("value").unwrap();
-                            let timestamp = x.get("timestamp").unwrap();
-                            values.push(value.as_f64().unwrap() as i32);
-                            timestamps.push(timestamp.as_str().unwrap().to_string());
-                        } else {
-                            let value = x.get("value").unwrap();
-                            let timestamp = x.get("timestamp").unwrap();
-                            values.push((value.as_f64().unwrap() * 100.0) as i32);
-                            timestamps.push(timestamp.as_str().unwrap().to_string());
-                        }
-                    });
-                    Ok((GraphData { values, timestamps }, id))
-                } else {
-                    Err("No data found".to_string())
-                }
-            });
-            tasks.push(task);
-        }
-        let results = join_all(tasks).await;
+        let client = self.client.lock().await.clone();
+        Ok(fetch_graphs(client, ids, plant, sensor_type, time_range).await)
+    }
+
+    /// Gets the graphs of every sensor for the given plants in the time range
+    ///
+    /// Returns the graphs with the ids of their plants per sensor, see `fetch_graphs`.
+    pub async fn get_sensor_graphs(
+        self,
+        ids: Vec<String>,
+        time_range: (String, String),
+    ) -> Vec<(Sensortypes, Vec<(GraphData, String)>)> {
+        let client = self.client.lock().await.clone();
         let mut graphs = vec![];
-        for result in results {
-            match result {
-                Ok(Ok(graph_data)) => graphs.push(graph_data),
-                _ => {}
-            }
+        for sensor in Sensortypes::iter() {
+            let data = fetch_graphs(
+                client.clone(),
+                ids.clone(),
+                true,
+                sensor.get_name(),
+                time_range.clone(),
+            )
+            .await;
+            graphs.push((sensor, data));
         }
+        graphs
+    }
 
-        Ok(graphs)
+    /// Gets the group and the readings of every sensor for all plants in the given time range
+    ///
+    /// Returns the readings of every plant of the overview, see `get_plant_readings`.
+    pub async fn get_recent_readings(
+        self,
+        time_range: (String, String),
+    ) -> RequestResult<Vec<(String, String, RequestResult<PlantReadings>)>> {
+        let plants = self.clone().get_plant_overview().await?;
+        Ok(self.get_plant_readings(plants, time_range).await)
+    }
+
+    /// Gets the group and the readings of every sensor of the given plants in the time range
+    ///
+    /// Returns the id, name and readings of every given plant. A few plants are requested at a
+    /// time and returned in the order they finish. A sensor without readings is kept with empty
+    /// data, any other failed request fails the whole plant.
+    pub async fn get_plant_readings(
+        self,
+        plants: Vec<(String, String)>,
+        time_range: (String, String),
+    ) -> Vec<(String, String, RequestResult<PlantReadings>)> {
+        let client = self.client.lock().await.clone();
+        stream::iter(plants)
+            .map(|(id, name)| {
+                let client = client.clone();
+                let time_range = time_range.clone();
+                async move {
+                    let readings =
+                        fetch_plant_readings(client, id.clone(), name.clone(), time_range).await;
+                    (id, name, readings)
+                }
+            })
+            .buffer_unordered(PARALLEL_REQUESTS)
+            .collect()
+            .await
     }
 
     /// Gets all users in the database
@@ -308,29 +345,31 @@ impl ApiClient {
     }
 
     /// Creates or updates a plant
+    ///
+    /// A plant with an id is updated, all of its metadata is replaced.
     pub async fn create_plant(
         self,
         new_plant: PlantMetadata,
         plant_group_id: i32,
         plant_id: Option<String>,
-    ) -> Result<(), reqwest::Error> {
+    ) -> RequestResult<()> {
         let client = self.client.lock().await;
         let mut json = serde_json::to_value(new_plant).unwrap();
         json["plantGroupId"] = json!(plant_group_id);
-        let response = if plant_id.is_none() {
-            let response = client
-                .post(&format!("{}plant", ENDPOINT))
+        let response = if let Some(plant_id) = plant_id {
+            client
+                .put(format!("{}plant/{}", ENDPOINT, plant_id))
                 .json(&json)
                 .send()
-                .await?;
-            response
+                .await
+                .map_err(|e| e.to_string())?
         } else {
-            let response = client
-                .put(&format!("{}plant/{}", ENDPOINT, plant_id.unwrap()))
+            client
+                .post(format!("{}plant", ENDPOINT))
                 .json(&json)
                 .send()
-                .await?;
-            response
+                .await
+                .map_err(|e| e.to_string())?
         };
         let result = response.error_for_status_ref().map(|_| ());
 
@@ -344,9 +383,63 @@ impl ApiClient {
                 Err(e.to_string())
             }
         }
-        .expect("TODO: panic message");
+    }
 
-        Ok(())
+    /// Gets the metadata of a plant
+    pub async fn get_plant(self, plant_id: String) -> RequestResult<PlantMetadata> {
+        let client = self.client.lock().await.clone();
+        fetch_plant(client, plant_id).await
+    }
+
+    /// Gets the metadata of a group with its care tips and sensor ranges
+    pub async fn get_group(self, group_id: i32) -> RequestResult<PlantGroupMetadata> {
+        let client = self.client.lock().await.clone();
+        client
+            .get(format!("{}plant-group/{}", ENDPOINT, group_id))
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Gets the ids and names of all plants
+    pub async fn get_plant_overview(self) -> RequestResult<Vec<(String, String)>> {
+        let client = self.client.lock().await.clone();
+        let text = client
+            .get(ENDPOINT.to_string() + "plants/overview")
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(parse_overview(&text, "plants"))
+    }
+
+    /// Gets the ids, names and metadata of all plants
+    ///
+    /// The metadata is requested for a few plants at a time, in the order of the overview.
+    /// Plants whose metadata can not be loaded are returned with the error.
+    pub async fn get_all_plants(
+        self,
+    ) -> RequestResult<Vec<(String, String, RequestResult<PlantMetadata>)>> {
+        let plants = self.clone().get_plant_overview().await?;
+        let client = self.client.lock().await.clone();
+        Ok(stream::iter(plants)
+            .map(|(id, name)| {
+                let client = client.clone();
+                async move {
+                    let metadata = fetch_plant(client, id.clone()).await;
+                    (id, name, metadata)
+                }
+            })
+            .buffered(PARALLEL_REQUESTS)
+            .collect()
+            .await)
     }
 
     /// Deletes a plant
@@ -354,7 +447,7 @@ impl ApiClient {
         info!("Plant {} deleted", plant_id);
         let client = self.client.lock().await;
         let response = client
-            .delete(&format!("{}plant/{}", ENDPOINT, plant_id))
+            .delete(format!("{}plant/{}", ENDPOINT, plant_id))
             .send()
             .await?;
         let result = response.error_for_status_ref().map(|_| ());
@@ -375,7 +468,7 @@ impl ApiClient {
     pub async fn delete_group(self, group_id: String) -> Result<(), reqwest::Error> {
         let client = self.client.lock().await;
         let response = client
-            .delete(&format!("{}plant-group/{}", ENDPOINT, group_id))
+            .delete(format!("{}plant-group/{}", ENDPOINT, group_id))
             .send()
             .await?;
         let result = response.error_for_status_ref().map(|_| ());
@@ -397,27 +490,28 @@ impl ApiClient {
         self,
         new_group: PlantGroupMetadata,
         group_id: Option<String>,
-    ) -> Result<(), reqwest::Error> {
+    ) -> RequestResult<()> {
         let mut json = serde_json::to_value(new_group.clone()).unwrap();
 
         for (i, sensor) in enumerate(new_group.sensorRanges.iter()) {
             json["sensorRanges"][i]["sensor"] = json!(sensor.sensorType.name);
         }
         info!("Creating group with json: {:?}", json);
-        println!("Creating group with json: {:?}", json);
         let client = self.client.lock().await;
-        let response = if group_id.is_none() {
+        let response = if let Some(group_id) = group_id {
             client
-                .post(&format!("{}plant-group", ENDPOINT))
+                .put(format!("{}plant-group/{}", ENDPOINT, group_id))
                 .json(&json)
                 .send()
-                .await?
+                .await
+                .map_err(|e| e.to_string())?
         } else {
             client
-                .put(&format!("{}plant-group/{}", ENDPOINT, group_id.unwrap()))
+                .post(format!("{}plant-group", ENDPOINT))
                 .json(&json)
                 .send()
-                .await?
+                .await
+                .map_err(|e| e.to_string())?
         };
         let result = response.error_for_status_ref().map(|_| ());
 
@@ -431,51 +525,31 @@ impl ApiClient {
                 Err(e.to_string())
             }
         }
-        .expect("TODO: panic message");
-
-        Ok(())
     }
-    #[tokio::main(flavor = "current_thread")]
-    pub async fn get_all_plant_ids_names(self) -> Result<Vec<(String, String)>, reqwest::Error> {
-        let client = self.client.lock().await;
-        let response = client
-            .get(ENDPOINT.to_string() + "plants/overview")
+    /// Gets the ids, names and descriptions of all groups without blocking
+    pub async fn get_groups(self) -> RequestResult<Vec<GroupOption>> {
+        let client = self.client.lock().await.clone();
+        let text = client
+            .get(ENDPOINT.to_string() + "plant-groups/overview")
             .send()
-            .await?;
-        let text = response.text().await?;
-        let mut ids: Vec<(String, String)> = vec![];
-        if text != "{\"plants\":null}" {
-            let value: Value = serde_json::from_str(&text).unwrap();
-            let data = value.get("plants").unwrap();
-            data.as_array().unwrap().iter().for_each(|plant| {
-                ids.push((
-                    plant.get("id").unwrap().to_string(),
-                    plant.get("name").unwrap().to_string(),
-                ));
-            });
-        }
-        Ok(ids)
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| e.to_string())?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(parse_groups(&text))
     }
+    /// Gets the ids, names and descriptions of all groups
     #[tokio::main(flavor = "current_thread")]
-    pub async fn get_all_group_ids_names(self) -> Result<Vec<(String, String)>, reqwest::Error> {
+    pub async fn get_all_groups(self) -> Result<Vec<GroupOption>, reqwest::Error> {
         let client = self.client.lock().await;
         let response = client
             .get(ENDPOINT.to_string() + "plant-groups/overview")
             .send()
             .await?;
         let text = response.text().await?;
-        let mut ids: Vec<(String, String)> = vec![];
-        if text != "{\"plantGroups\":null}" {
-            let value: Value = serde_json::from_str(&text).unwrap();
-            let data = value.get("plantGroups").unwrap();
-            data.as_array().unwrap().iter().for_each(|plant| {
-                ids.push((
-                    plant.get("id").unwrap().to_string(),
-                    plant.get("name").unwrap().to_string(),
-                ));
-            });
-        }
-        Ok(ids)
+        Ok(parse_groups(&text))
     }
     #[tokio::main(flavor = "current_thread")]
     pub async fn get_plant_details(
@@ -493,6 +567,51 @@ impl ApiClient {
 
         Ok((details, plant_group))
     }
+
+    /// Gets the care journal of a plant
+    ///
+    /// # Returns
+    ///
+    /// Returns the entries of the journal, or `None` if the server does not support care journals.
+    pub async fn get_care_journal(self, plant_id: String) -> RequestResult<Option<Vec<CareEntry>>> {
+        let client = self.client.lock().await.clone();
+        let response = client
+            .get(ENDPOINT.to_string() + &format!("plant/{}/care-journal", plant_id))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if journal_unsupported(response.status()) {
+            return Ok(None);
+        }
+        let entries = response
+            .error_for_status()
+            .map_err(|e| e.to_string())?
+            .json()
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(Some(entries))
+    }
+
+    /// Adds an entry to the care journal of a plant
+    ///
+    /// # Returns
+    ///
+    /// Returns `false` if the server does not support care journals and the entry was not stored.
+    pub async fn add_care_entry(self, plant_id: String, entry: CareEntry) -> RequestResult<bool> {
+        let client = self.client.lock().await.clone();
+        let response = client
+            .post(ENDPOINT.to_string() + &format!("plant/{}/care-journal", plant_id))
+            .json(&entry)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if journal_unsupported(response.status()) {
+            return Ok(false);
+        }
+        response.error_for_status().map_err(|e| e.to_string())?;
+        info!("Care entry added to plant {}", plant_id);
+        Ok(true)
+    }
     /// Creates a new user with the given username, password, and user data.
     ///
     /// # Arguments
@@ -631,7 +750,7 @@ pub async fn login(username: String, password: String) -> RequestResult<TempCrea
             let login_user = TempCreationUser {
                 name: username.clone(),
                 password: password.clone(),
-                role: role_value.clone(),
+                role: role_value,
             };
             info!("Login successful");
             Ok(login_user)
@@ -643,6 +762,210 @@ pub async fn login(username: String, password: String) -> RequestResult<TempCrea
     }
 }
 
+/// Gets the metadata of a plant with the given client
+async fn fetch_plant(client: Client, plant_id: String) -> RequestResult<PlantMetadata> {
+    client
+        .get(ENDPOINT.to_string() + &format!("plant/{}", plant_id))
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| e.to_string())?
+        .json()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Gets the graphs of one sensor for the given plant or group ids in the time range
+///
+/// A few ids are requested at a time. Ids whose request fails or that have no readings are left
+/// out, the others are returned with their id in the given order.
+async fn fetch_graphs(
+    client: Client,
+    ids: Vec<String>,
+    plant: bool,
+    sensor_type: String,
+    time_range: (String, String),
+) -> Vec<(GraphData, String)> {
+    info!("Getting time range: {:?}", time_range);
+    let filter = if plant { "plant" } else { "plantGroup" };
+    stream::iter(ids)
+        .map(|id| {
+            let parameter = format!(
+                "{}sensor-data?sensor={}&{}={}&from={}&to={}",
+                ENDPOINT, sensor_type, filter, id, time_range.0, time_range.1
+            );
+            let graph = fetch_graph(client.clone(), parameter, sensor_type.clone());
+            async move { graph.await.ok().map(|graph_data| (graph_data, id)) }
+        })
+        .buffered(PARALLEL_REQUESTS)
+        .filter_map(|graph| async move { graph })
+        .collect()
+        .await
+}
+
+/// Gets the sensor data of the given url
+///
+/// Values of all sensors except the temperature are multiplied by 100 to keep two decimals.
+async fn fetch_graph(client: Client, url: String, sensor_type: String) -> RequestResult<GraphData> {
+    let response = client.get(url).send().await.map_err(|e| e.to_string())?;
+    let status = response.error_for_status_ref().map(|_| ());
+
+    let text = response.text().await.map_err(|e| e.to_string())?;
+    if text != "{\"data\":null}" {
+        // A failed request is an error, not a sensor without readings
+        status.map_err(|e| e.to_string())?;
+        let value: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+        let data = value
+            .get("data")
+            .ok_or_else(|| format!("Unexpected response: {}", text))?;
+        let mut values = vec![];
+        let mut timestamps = vec![];
+        data.as_array().unwrap().iter().for_each(|x| {
+            if sensor_type == "temperature" {
+                let value = x.get("value").unwrap();
+                let timestamp = x.get("timestamp").unwrap();
+                values.push(value.as_f64().unwrap() as i32);
+                timestamps.push(timestamp.as_str().unwrap().to_string());
+            } else {
+                let value = x.get("value").unwrap();
+                let timestamp = x.get("timestamp").unwrap();
+                values.push((value.as_f64().unwrap() * 100.0) as i32);
+                timestamps.push(timestamp.as_str().unwrap().to_string());
+            }
+        });
+        Ok(GraphData { values, timestamps })
+    } else {
+        Err(NO_DATA.to_string())
+    }
+}
+
+/// Gets the metadata and the readings of every sensor of a plant in the time range
+async fn fetch_plant_readings(
+    client: Client,
+    plant_id: String,
+    name: String,
+    time_range: (String, String),
+) -> RequestResult<PlantReadings> {
+    let metadata = fetch_plant(client.clone(), plant_id.clone()).await?;
+    let readings = fetch_readings(client, plant_id.clone(), time_range).await?;
+    Ok(PlantReadings {
+        id: plant_id,
+        name,
+        species: metadata.species,
+        location: metadata.location,
+        group: metadata.plantGroup,
+        readings,
+    })
+}
+
+/// Gets the readings of every sensor of a plant in the time range, keyed by the sensor name
+///
+/// A sensor without readings is kept with empty data, so a sensor that stopped reporting is
+/// noticed. Any other failed request is returned as the error.
+async fn fetch_readings(
+    client: Client,
+    plant_id: String,
+    time_range: (String, String),
+) -> RequestResult<Vec<(String, GraphData)>> {
+    let mut readings = vec![];
+    for sensor in Sensortypes::iter().map(|sensor| sensor.get_name()) {
+        let parameter = format!(
+            "{}sensor-data?sensor={}&plant={}&from={}&to={}",
+            ENDPOINT, sensor, plant_id, time_range.0, time_range.1
+        );
+        let graph_data = match fetch_graph(client.clone(), parameter, sensor.clone()).await {
+            Ok(graph_data) => graph_data,
+            Err(e) if e == NO_DATA => GraphData {
+                values: vec![],
+                timestamps: vec![],
+            },
+            Err(e) => return Err(e),
+        };
+        readings.push((sensor, graph_data));
+    }
+    Ok(readings)
+}
+
+/// Returns if the status of a care journal request means that the server has no care journals
+fn journal_unsupported(status: StatusCode) -> bool {
+    status == StatusCode::NOT_FOUND
+        || status == StatusCode::METHOD_NOT_ALLOWED
+        || status == StatusCode::NOT_IMPLEMENTED
+}
+
+/// Parses the ids and names of an overview response
+///
+/// # Arguments
+///
+/// * `text` - The body of the response.
+/// * `key` - The key of the list in the response, e.g. `plants`.
+///
+/// # Returns
+///
+/// Returns the ids and names, strings are returned without quotes.
+pub fn parse_overview(text: &str, key: &str) -> Vec<(String, String)> {
+    let value: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(_) => return vec![],
+    };
+    let as_string = |value: Option<&Value>| match value {
+        Some(Value::String(string)) => string.clone(),
+        Some(value) => value.to_string(),
+        None => String::new(),
+    };
+    value
+        .get(key)
+        .and_then(|data| data.as_array())
+        .map(|data| {
+            data.iter()
+                .map(|entry| (as_string(entry.get("id")), as_string(entry.get("name"))))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Parses the groups of the group overview, skipping groups without a numeric id
+///
+/// # Arguments
+///
+/// * `text` - The body of the response.
+///
+/// # Returns
+///
+/// Returns the groups, with an empty description if the server sent none.
+pub fn parse_groups(text: &str) -> Vec<GroupOption> {
+    let value: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(_) => return vec![],
+    };
+    value
+        .get("plantGroups")
+        .and_then(|data| data.as_array())
+        .map(|data| {
+            data.iter()
+                .filter_map(|entry| {
+                    let id = match entry.get("id")? {
+                        Value::String(id) => id.parse().ok()?,
+                        id => i32::try_from(id.as_i64()?).ok()?,
+                    };
+                    let text = |key: &str| {
+                        entry
+                            .get(key)
+                            .and_then(|value| value.as_str())
+                            .unwrap_or_default()
+                            .to_string()
+                    };
+                    Some(GroupOption {
+                        id,
+                        name: text("name"),
+                        description: text("description"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 /// Encodes the given username and password as a Base64-encoded string.
 ///
 /// # Arguments
@@ -655,8 +978,8 @@ pub async fn login(username: String, password: String) -> RequestResult<TempCrea
 /// Returns a string representing the encoded credentials.
 pub fn encode_credentials(username: String, password: String) -> String {
     let combined = format!("{}:{}", username, password);
-    let encoded = general_purpose::STANDARD.encode(combined);
-    encoded
+
+    general_purpose::STANDARD.encode(combined)
 }
 
 #[cfg(test)]
@@ -704,6 +1027,39 @@ mod tests {
         assert_eq!(result, "dGVzdHVzZXI6dGVzdHBhc3N3b3Jk");
     }
 
+    #[test]
+    fn test_parse_overview() {
+        let text = r#"{"plants":[{"id":1,"name":"Basilikum"},{"id":"2","name":"Minze"}]}"#;
+        assert_eq!(
+            parse_overview(text, "plants"),
+            vec![
+                ("1".to_string(), "Basilikum".to_string()),
+                ("2".to_string(), "Minze".to_string())
+            ]
+        );
+        assert!(parse_overview(r#"{"plants":null}"#, "plants").is_empty());
+    }
+
+    #[test]
+    fn test_parse_groups() {
+        let text = r#"{"plantGroups":[{"id":1,"name":"Kräuter","description":"Küche"},{"id":"2","name":"Kakteen"},{"id":"x","name":"Kaputt"}]}"#;
+        assert_eq!(
+            parse_groups(text),
+            vec![
+                GroupOption {
+                    id: 1,
+                    name: "Kräuter".to_string(),
+                    description: "Küche".to_string(),
+                },
+                GroupOption {
+                    id: 2,
+                    name: "Kakteen".to_string(),
+                    description: String::new(),
+                },
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_create_plant() {
         let username = "testuser".to_string();
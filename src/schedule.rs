@@ -0,0 +1,1082 @@
+use crate::journal::CareType;
+use crate::range_selector::{parse_input, INPUT_FORMAT};
+use crate::storage;
+use crate::timezone::TimeZoneChoice;
+use crate::{Icon, Message, Tab, TEXT_SIZE};
+use chrono::{
+    DateTime, Datelike, Duration, Months, NaiveDateTime, NaiveTime, SecondsFormat, Utc, Weekday,
+};
+use iced::widget::{pick_list, scrollable, Button, Column, Container, Row, Rule, Text, TextInput};
+use iced::Alignment::Center;
+use iced::{Color, Element, Length};
+use iced_aw::TabLabel;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+
+/// The name of the file that contains the care tasks
+const LOCAL_FILE: &str = "tasks.json";
+/// The maximum number of occurrences of one task that are listed in the agenda
+const MAX_OCCURRENCES: usize = 100;
+/// The color of overdue tasks, matches the danger color of the theme
+const OVERDUE_COLOR: Color = Color {
+    r: 214.0 / 255.0,
+    g: 73.0 / 255.0,
+    b: 51.0 / 255.0,
+    a: 1.0,
+};
+
+/// Contains the units of the interval of a care task
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum IntervalUnit {
+    /// The task repeats every n days
+    #[default]
+    Tage,
+    /// The task repeats every n weeks
+    Wochen,
+    /// The task repeats every n months
+    Monate,
+}
+
+impl IntervalUnit {
+    pub const ALL: [IntervalUnit; 3] = [
+        IntervalUnit::Tage,
+        IntervalUnit::Wochen,
+        IntervalUnit::Monate,
+    ];
+
+    /// Returns the frequency of the unit in an iCalendar recurrence rule
+    fn frequency(&self) -> &'static str {
+        match self {
+            IntervalUnit::Tage => "DAILY",
+            IntervalUnit::Wochen => "WEEKLY",
+            IntervalUnit::Monate => "MONTHLY",
+        }
+    }
+}
+
+impl Display for IntervalUnit {
+    /// Returns the name of the unit
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntervalUnit::Tage => write!(f, "Tage"),
+            IntervalUnit::Wochen => write!(f, "Wochen"),
+            IntervalUnit::Monate => write!(f, "Monate"),
+        }
+    }
+}
+
+/// Contains what a care task belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskTarget {
+    /// A single plant with the given id
+    Plant(String),
+    /// All plants of the group with the given id
+    Group(String),
+}
+
+/// A recurring care task of a plant or group
+///
+/// Fields:
+/// * `id` - The unique id of the task
+/// * `kind` - The kind of care
+/// * `target` - The plant or group the task belongs to
+/// * `target_name` - The name of the plant or group
+/// * `every` - The number of units between two occurrences
+/// * `unit` - The unit of the interval
+/// * `next_due` - The time the task is due next, in UTC formatted as RFC 3339
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CareTask {
+    pub id: u64,
+    pub kind: CareType,
+    pub target: TaskTarget,
+    pub target_name: String,
+    pub every: u32,
+    pub unit: IntervalUnit,
+    pub next_due: String,
+}
+
+impl CareTask {
+    /// Returns the time the task is due next
+    pub fn due(&self) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(&self.next_due)
+            .map(|time| time.with_timezone(&Utc))
+            .unwrap_or_default()
+    }
+
+    /// Returns if the task was due before the given time
+    pub fn is_overdue(&self, now: DateTime<Utc>) -> bool {
+        self.due() < now
+    }
+
+    /// Returns the time one interval after the given time
+    ///
+    /// The interval is added to the wall clock time of the time zone, so a task keeps its time
+    /// of day when the clocks are changed.
+    pub fn advance(&self, time: DateTime<Utc>, time_zone: TimeZoneChoice) -> DateTime<Utc> {
+        self.after(time, 1, time_zone)
+    }
+
+    /// Returns the time the given number of intervals after the given time
+    ///
+    /// All intervals are added at once, so a monthly task that falls on the 28th of February
+    /// because of the 31st of January is back on the 31st in March.
+    pub fn after(
+        &self,
+        time: DateTime<Utc>,
+        count: u32,
+        time_zone: TimeZoneChoice,
+    ) -> DateTime<Utc> {
+        let local = time_zone.to_local(&time);
+        let every = self.every.saturating_mul(count);
+        let next = match self.unit {
+            IntervalUnit::Tage => local + Duration::days(every as i64),
+            IntervalUnit::Wochen => local + Duration::weeks(every as i64),
+            IntervalUnit::Monate => local
+                .checked_add_months(Months::new(every))
+                .unwrap_or(local),
+        };
+        time_zone
+            .to_utc(&next)
+            .unwrap_or_else(|| time + (next - local))
+    }
+
+    /// Returns the number of intervals after `time` of the first occurrence at or after `from`
+    ///
+    /// The count is estimated with the longest possible interval, a month of 31 days and a day
+    /// with an hour more, and then moved forward, so no occurrence is skipped.
+    fn first_count(
+        &self,
+        time: DateTime<Utc>,
+        from: DateTime<Utc>,
+        time_zone: TimeZoneChoice,
+    ) -> u32 {
+        if from <= time || self.every == 0 {
+            return 0;
+        }
+        let longest = match self.unit {
+            IntervalUnit::Tage => Duration::days(1),
+            IntervalUnit::Wochen => Duration::weeks(1),
+            IntervalUnit::Monate => Duration::days(31),
+        } * self.every as i32
+            + Duration::hours(1);
+        let mut count = ((from - time).num_seconds() / longest.num_seconds()) as u32;
+        while self.after(time, count, time_zone) < from {
+            count += 1;
+        }
+        count
+    }
+
+    /// Marks the task as done at the given time and schedules the next occurrence
+    pub fn done(&mut self, now: DateTime<Utc>, time_zone: TimeZoneChoice) {
+        self.next_due = self
+            .advance(now, time_zone)
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+    }
+
+    /// Returns the description of the interval, e.g. "alle 3 Tage"
+    pub fn interval_text(&self) -> String {
+        match (self.every, self.unit) {
+            (1, IntervalUnit::Tage) => "täglich".to_string(),
+            (1, IntervalUnit::Wochen) => "wöchentlich".to_string(),
+            (1, IntervalUnit::Monate) => "monatlich".to_string(),
+            (every, unit) => format!("alle {} {}", every, unit),
+        }
+    }
+
+    /// Returns the title of the task, e.g. "Gießen: Basilikum"
+    pub fn title(&self) -> String {
+        match self.target {
+            TaskTarget::Plant(_) => format!("{}: {}", self.kind, self.target_name),
+            TaskTarget::Group(_) => format!("{}: Gruppe {}", self.kind, self.target_name),
+        }
+    }
+}
+
+/// All care tasks, stored locally
+///
+/// Fields:
+/// * `tasks` - The tasks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Schedule {
+    pub tasks: Vec<CareTask>,
+}
+
+impl Schedule {
+    /// Adds the task with a new id
+    pub fn add(&mut self, mut task: CareTask) {
+        task.id = self.tasks.iter().map(|task| task.id).max().unwrap_or(0) + 1;
+        self.tasks.push(task);
+    }
+
+    /// Removes the task with the given id
+    pub fn remove(&mut self, id: u64) {
+        self.tasks.retain(|task| task.id != id);
+    }
+
+    /// Marks the task with the given id as done and schedules the next occurrence
+    pub fn done(&mut self, id: u64, now: DateTime<Utc>, time_zone: TimeZoneChoice) {
+        if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
+            task.done(now, time_zone);
+        }
+    }
+
+    /// Returns the tasks of the plant or group, the next due first
+    pub fn tasks_for(&self, target: &TaskTarget) -> Vec<CareTask> {
+        let mut tasks: Vec<CareTask> = self
+            .tasks
+            .iter()
+            .filter(|task| task.target == *target)
+            .cloned()
+            .collect();
+        tasks.sort_by_key(|task| task.due());
+        tasks
+    }
+
+    /// Returns the tasks that were due before the given time, the oldest first
+    pub fn overdue(&self, now: DateTime<Utc>) -> Vec<&CareTask> {
+        let mut tasks: Vec<&CareTask> = self
+            .tasks
+            .iter()
+            .filter(|task| task.is_overdue(now))
+            .collect();
+        tasks.sort_by_key(|task| task.due());
+        tasks
+    }
+
+    /// Returns all occurrences of all tasks between `from` and `to`, sorted by time
+    ///
+    /// Every task occurs at its next due time and then once per interval, counted from the
+    /// next due time. The occurrences before `from` are skipped without being listed, so a week
+    /// far in the future shows the same occurrences as the current one.
+    pub fn occurrences(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        time_zone: TimeZoneChoice,
+    ) -> Vec<(DateTime<Utc>, &CareTask)> {
+        let mut occurrences = vec![];
+        for task in &self.tasks {
+            let due = task.due();
+            let first = task.first_count(due, from, time_zone);
+            let mut previous = None;
+            for count in first..first.saturating_add(MAX_OCCURRENCES as u32) {
+                let time = match count {
+                    0 => due,
+                    count => task.after(due, count, time_zone),
+                };
+                if time >= to || matches!(previous, Some(previous) if time <= previous) {
+                    break;
+                }
+                if time >= from {
+                    occurrences.push((time, task));
+                }
+                previous = Some(time);
+            }
+        }
+        occurrences.sort_by_key(|(time, _)| *time);
+        occurrences
+    }
+
+    /// Returns the tasks as iCalendar file with one recurring event per task
+    ///
+    /// The events start at the wall clock time of the time zone, so the calendar keeps their
+    /// time of day when the clocks are changed. The system time zone has no name, its events
+    /// use the floating local time of the calendar.
+    pub fn to_ics(&self, now: DateTime<Utc>, time_zone: TimeZoneChoice) -> String {
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//Plantbuddy//Pflegeplan//DE".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+        for task in &self.tasks {
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:task-{}@plantbuddy", task.id));
+            lines.push(format!("DTSTAMP:{}", ics_time(now)));
+            let start = time_zone.to_local(&task.due());
+            lines.push(match time_zone {
+                TimeZoneChoice::System => format!("DTSTART:{}", ics_local_time(start)),
+                TimeZoneChoice::Zone(tz) => {
+                    format!("DTSTART;TZID={}:{}", tz.name(), ics_local_time(start))
+                }
+            });
+            lines.push("DURATION:PT15M".to_string());
+            let mut rule = format!(
+                "RRULE:FREQ={};INTERVAL={}",
+                task.unit.frequency(),
+                task.every
+            );
+            // Calendars skip the months without the day, the app moves to the last day instead
+            if task.unit == IntervalUnit::Monate && start.day() > 28 {
+                let days: Vec<String> = (28..=start.day()).map(|day| day.to_string()).collect();
+                rule.push_str(&format!(";BYMONTHDAY={};BYSETPOS=-1", days.join(",")));
+            }
+            lines.push(rule);
+            lines.push(format!("SUMMARY:{}", ics_escape(&task.title())));
+            lines.push(format!(
+                "DESCRIPTION:{}",
+                ics_escape(&format!("{} {}", task.kind, task.interval_text()))
+            ));
+            lines.push("END:VEVENT".to_string());
+        }
+        lines.push("END:VCALENDAR".to_string());
+        lines
+            .iter()
+            .map(|line| fold_line(line))
+            .collect::<Vec<_>>()
+            .join("\r\n")
+            + "\r\n"
+    }
+
+    /// Reads the tasks from the file, an empty schedule if it does not exist
+    pub fn load(path: &Path) -> Schedule {
+        storage::load(path)
+    }
+
+    /// Writes the tasks into the file
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        storage::save(path, self)
+    }
+}
+
+/// Formats the time as UTC time of an iCalendar file
+fn ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Formats the wall clock time as local time of an iCalendar file
+fn ics_local_time(time: NaiveDateTime) -> String {
+    time.format("%Y%m%dT%H%M%S").to_string()
+}
+
+/// Escapes the special characters of an iCalendar text
+fn ics_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Folds a line of an iCalendar file into lines of at most 75 bytes
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts to their length
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
+
+/// Returns the start of the week of the given time, monday at midnight in the time zone
+pub fn week_start(now: DateTime<Utc>, time_zone: TimeZoneChoice) -> DateTime<Utc> {
+    let local = time_zone.to_local(&now).date();
+    let monday = local - Duration::days(local.weekday().num_days_from_monday() as i64);
+    let midnight = NaiveDateTime::new(monday, NaiveTime::MIN);
+    time_zone.to_utc(&midnight).unwrap_or_else(|| {
+        time_zone
+            .to_utc(&(midnight + Duration::hours(1)))
+            .unwrap_or(now)
+    })
+}
+
+/// Returns the next midnight in the time zone after the given time
+fn next_midnight(time: DateTime<Utc>, time_zone: TimeZoneChoice) -> DateTime<Utc> {
+    let tomorrow = time_zone.to_local(&time).date() + Duration::days(1);
+    time_zone
+        .to_utc(&NaiveDateTime::new(tomorrow, NaiveTime::MIN))
+        .unwrap_or(time + Duration::days(1))
+}
+
+/// Returns the short german name of the weekday
+fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Mo",
+        Weekday::Tue => "Di",
+        Weekday::Wed => "Mi",
+        Weekday::Thu => "Do",
+        Weekday::Fri => "Fr",
+        Weekday::Sat => "Sa",
+        Weekday::Sun => "So",
+    }
+}
+
+/// Contains all messages that change the schedule
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScheduleMessage {
+    /// Adds a new task
+    Add(CareTask),
+    /// Marks the task with the given id as done
+    Done(u64),
+    /// Removes the task with the given id
+    Remove(u64),
+    /// Shows the previous week in the agenda
+    PreviousWeek,
+    /// Shows the current week in the agenda
+    ThisWeek,
+    /// Shows the next week in the agenda
+    NextWeek,
+    /// Exports the schedule as iCalendar file
+    ExportIcs,
+    /// Opens the plant on the detail page
+    OpenPlant(String),
+}
+
+/// Contains all messages of the task form
+#[derive(Debug, Clone, PartialEq)]
+pub enum TaskFormMessage {
+    /// Selects the plant or group and its name the tasks are edited for
+    Select(TaskTarget, String),
+    /// Selects the kind of the new task
+    KindSelected(CareType),
+    /// Updates the interval of the new task
+    EveryChanged(String),
+    /// Selects the unit of the interval of the new task
+    UnitSelected(IntervalUnit),
+    /// Updates the first due time of the new task
+    FirstDueChanged(String),
+    /// Creates the new task
+    Create,
+    /// Marks the task with the given id as done
+    Done(u64),
+    /// Removes the task with the given id
+    Remove(u64),
+}
+
+/// The form to list and add the care tasks of one plant or group
+///
+/// Fields:
+/// * `target` - The plant or group and its name, `None` if nothing is selected
+/// * `tasks` - The tasks of the target, copied from the schedule
+/// * `kind` - The kind of the new task
+/// * `every_input` - The input of the interval of the new task
+/// * `unit` - The unit of the interval of the new task
+/// * `first_due_input` - The input of the first due time of the new task, empty for now
+/// * `error` - The error of the inputs
+pub struct TaskForm {
+    pub target: Option<(TaskTarget, String)>,
+    pub tasks: Vec<CareTask>,
+    pub kind: CareType,
+    pub every_input: String,
+    pub unit: IntervalUnit,
+    pub first_due_input: String,
+    pub error: String,
+}
+
+impl TaskForm {
+    /// Creates an empty form without target
+    pub fn new() -> Self {
+        TaskForm {
+            target: None,
+            tasks: Vec::new(),
+            kind: CareType::default(),
+            every_input: "3".to_string(),
+            unit: IntervalUnit::default(),
+            first_due_input: String::new(),
+            error: String::new(),
+        }
+    }
+
+    /// Copies the tasks of the target from the schedule
+    pub fn refresh(&mut self, schedule: &Schedule) {
+        self.tasks = match &self.target {
+            Some((target, _)) => schedule.tasks_for(target),
+            None => Vec::new(),
+        };
+    }
+
+    /// Creates the new task from the inputs
+    pub fn task_from_inputs(&self) -> Result<CareTask, String> {
+        let (target, name) = self.target.clone().ok_or("Keine Pflanze ausgewählt")?;
+        let every = match self.every_input.trim().parse::<u32>() {
+            Ok(every) if every > 0 => every,
+            _ => return Err("Intervall muss eine positive Zahl sein".to_string()),
+        };
+        let first_due = if self.first_due_input.trim().is_empty() {
+            Utc::now()
+        } else {
+            parse_input(&self.first_due_input, TimeZoneChoice::current())
+                .ok_or("Zeit im Format TT.MM.JJJJ HH:MM eingeben")?
+        };
+        Ok(CareTask {
+            id: 0,
+            kind: self.kind,
+            target,
+            target_name: name,
+            every,
+            unit: self.unit,
+            next_due: first_due.to_rfc3339_opts(SecondsFormat::Secs, true),
+        })
+    }
+
+    /// Handles the messages of the form
+    ///
+    /// Returns the change of the schedule, which is owned by the schedule tab.
+    pub fn update(&mut self, message: TaskFormMessage) -> Option<ScheduleMessage> {
+        match message {
+            TaskFormMessage::Select(target, name) => {
+                self.target = Some((target, name));
+                self.error = String::new();
+            }
+            TaskFormMessage::KindSelected(kind) => self.kind = kind,
+            TaskFormMessage::EveryChanged(value) => self.every_input = value,
+            TaskFormMessage::UnitSelected(unit) => self.unit = unit,
+            TaskFormMessage::FirstDueChanged(value) => self.first_due_input = value,
+            TaskFormMessage::Create => match self.task_from_inputs() {
+                Ok(task) => {
+                    self.error = String::new();
+                    self.first_due_input = String::new();
+                    return Some(ScheduleMessage::Add(task));
+                }
+                Err(e) => self.error = e,
+            },
+            TaskFormMessage::Done(id) => return Some(ScheduleMessage::Done(id)),
+            TaskFormMessage::Remove(id) => return Some(ScheduleMessage::Remove(id)),
+        }
+        None
+    }
+
+    /// Returns the tasks of the target and the inputs of a new task
+    pub fn view(&self) -> Element<'_, TaskFormMessage> {
+        let title = match &self.target {
+            Some((TaskTarget::Plant(_), name)) => format!("Pflegeplan von {}:", name),
+            Some((TaskTarget::Group(_), name)) => format!("Pflegeplan der Gruppe {}:", name),
+            None => "Pflegeplan:".to_string(),
+        };
+        let now = Utc::now();
+        let time_zone = TimeZoneChoice::current();
+        let mut column = Column::new()
+            .spacing(10)
+            .push(Text::new(title).size(TEXT_SIZE));
+        for task in &self.tasks {
+            let due = Text::new(format!(
+                "{} ({}), fällig {}",
+                task.kind,
+                task.interval_text(),
+                time_zone.format(&task.due(), INPUT_FORMAT)
+            ))
+            .size(TEXT_SIZE);
+            let due = if task.is_overdue(now) {
+                due.style(OVERDUE_COLOR)
+            } else {
+                due
+            };
+            column = column.push(
+                Row::new()
+                    .spacing(10)
+                    .align_items(Center)
+                    .push(due)
+                    .push(
+                        Button::new(Text::new("Erledigt").size(TEXT_SIZE))
+                            .on_press(TaskFormMessage::Done(task.id)),
+                    )
+                    .push(
+                        Button::new(Text::new("Entfernen").size(TEXT_SIZE))
+                            .on_press(TaskFormMessage::Remove(task.id)),
+                    ),
+            );
+        }
+        let inputs = Row::new()
+            .spacing(10)
+            .align_items(Center)
+            .push(
+                pick_list(
+                    &CareType::ALL[..],
+                    Some(self.kind),
+                    TaskFormMessage::KindSelected,
+                )
+                .text_size(TEXT_SIZE),
+            )
+            .push(Text::new("alle").size(TEXT_SIZE))
+            .push(
+                TextInput::new("3", &self.every_input)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(70))
+                    .on_input(TaskFormMessage::EveryChanged),
+            )
+            .push(
+                pick_list(
+                    &IntervalUnit::ALL[..],
+                    Some(self.unit),
+                    TaskFormMessage::UnitSelected,
+                )
+                .text_size(TEXT_SIZE),
+            );
+        let start = Row::new()
+            .spacing(10)
+            .align_items(Center)
+            .push(
+                TextInput::new("Ab jetzt", &self.first_due_input)
+                    .size(TEXT_SIZE)
+                    .width(Length::from(230))
+                    .on_input(TaskFormMessage::FirstDueChanged),
+            )
+            .push(
+                Button::new(Text::new("Aufgabe anlegen").size(TEXT_SIZE))
+                    .on_press(TaskFormMessage::Create),
+            );
+        column
+            .push(inputs)
+            .push(start)
+            .push(
+                Text::new(&self.error)
+                    .size(TEXT_SIZE)
+                    .style(Color::from_rgb(1.0, 0.0, 0.0)),
+            )
+            .into()
+    }
+}
+
+/// The tab with the weekly agenda of all care tasks
+///
+/// Fields:
+/// * `schedule` - All care tasks
+/// * `week_offset` - The displayed week relative to the current week
+/// * `status` - The result of the last export or save
+/// * `path` - The file the tasks are stored in
+pub struct ScheduleTab {
+    pub schedule: Schedule,
+    pub week_offset: i64,
+    status: String,
+    path: PathBuf,
+}
+
+impl ScheduleTab {
+    /// Creates the tab with the tasks stored in the local data directory
+    pub fn new() -> Self {
+        ScheduleTab::with_path(storage::data_file(LOCAL_FILE))
+    }
+
+    /// Creates the tab with the tasks stored in the given file
+    pub fn with_path(path: PathBuf) -> Self {
+        ScheduleTab {
+            schedule: Schedule::load(&path),
+            week_offset: 0,
+            status: String::new(),
+            path,
+        }
+    }
+
+    /// Stores the tasks after a change
+    fn save(&mut self) {
+        if let Err(e) = self.schedule.save(&self.path) {
+            self.status = format!("Speichern fehlgeschlagen: {}", e);
+        }
+    }
+
+    /// Returns the start of the displayed week
+    pub fn displayed_week(&self) -> DateTime<Utc> {
+        let time_zone = TimeZoneChoice::current();
+        let start = week_start(Utc::now(), time_zone);
+        // Start again at midnight, a week with a clock change is not exactly seven days long
+        week_start(
+            start + Duration::weeks(self.week_offset) + Duration::hours(12),
+            time_zone,
+        )
+    }
+
+    /// Handles the messages of the schedule
+    pub fn update(&mut self, message: ScheduleMessage) {
+        match message {
+            ScheduleMessage::Add(task) => {
+                self.schedule.add(task);
+                self.save();
+            }
+            ScheduleMessage::Done(id) => {
+                self.schedule
+                    .done(id, Utc::now(), TimeZoneChoice::current());
+                self.save();
+            }
+            ScheduleMessage::Remove(id) => {
+                self.schedule.remove(id);
+                self.save();
+            }
+            ScheduleMessage::PreviousWeek => self.week_offset -= 1,
+            ScheduleMessage::ThisWeek => self.week_offset = 0,
+            ScheduleMessage::NextWeek => self.week_offset += 1,
+            ScheduleMessage::ExportIcs => {
+                self.status = match self.export_ics() {
+                    Ok(path) => format!("Exportiert nach {}", path.display()),
+                    Err(e) => format!("Export fehlgeschlagen: {}", e),
+                }
+            }
+            // Handled by the application, which switches to the detail page
+            ScheduleMessage::OpenPlant(_) => {}
+        }
+    }
+
+    /// Writes the schedule as iCalendar file into the home directory
+    ///
+    /// Returns the path of the written file.
+    fn export_ics(&self) -> std::io::Result<PathBuf> {
+        let directory = std::env::var("HOME")
+            .map(PathBuf::from)
+            .or_else(|_| std::env::current_dir())?;
+        let file_name = format!(
+            "plantbuddy_pflegeplan_{}.ics",
+            TimeZoneChoice::current().format(&Utc::now(), "%Y%m%d_%H%M%S")
+        );
+        let path = directory.join(file_name);
+        std::fs::write(
+            &path,
+            self.schedule.to_ics(Utc::now(), TimeZoneChoice::current()),
+        )?;
+        Ok(path)
+    }
+
+    /// Returns a row of the agenda for one task
+    fn task_row<'a>(&self, time: &str, task: &CareTask, overdue: bool) -> Row<'a, ScheduleMessage> {
+        let title = Text::new(format!("{}  {}", time, task.title())).size(TEXT_SIZE);
+        let title = if overdue {
+            title.style(OVERDUE_COLOR)
+        } else {
+            title
+        };
+        let mut row = Row::new()
+            .spacing(10)
+            .align_items(Center)
+            .push(Container::new(title).width(Length::FillPortion(3)))
+            .push(Text::new(task.interval_text()).size(TEXT_SIZE))
+            .push(
+                Button::new(Text::new("Erledigt").size(TEXT_SIZE))
+                    .on_press(ScheduleMessage::Done(task.id)),
+            );
+        if let TaskTarget::Plant(id) = &task.target {
+            row = row.push(
+                Button::new(Text::new("Öffnen").size(TEXT_SIZE))
+                    .on_press(ScheduleMessage::OpenPlant(id.clone())),
+            );
+        }
+        row
+    }
+}
+
+impl Tab for ScheduleTab {
+    type Message = Message;
+
+    fn title(&self) -> String {
+        String::from("Pflegeplan")
+    }
+
+    fn tab_label(&self) -> TabLabel {
+        TabLabel::IconText(Icon::Schedule.into(), self.title())
+    }
+
+    fn content(&self) -> Element<'_, Self::Message> {
+        let time_zone = TimeZoneChoice::current();
+        let now = Utc::now();
+        let start = self.displayed_week();
+        let end = week_start(start + Duration::days(7) + Duration::hours(12), time_zone);
+        let header = Row::new()
+            .spacing(20)
+            .align_items(Center)
+            .push(
+                Button::new(Text::new("Vorherige Woche").size(TEXT_SIZE))
+                    .on_press(ScheduleMessage::PreviousWeek),
+            )
+            .push(
+                Button::new(Text::new("Diese Woche").size(TEXT_SIZE))
+                    .on_press(ScheduleMessage::ThisWeek),
+            )
+            .push(
+                Button::new(Text::new("Nächste Woche").size(TEXT_SIZE))
+                    .on_press(ScheduleMessage::NextWeek),
+            )
+            .push(
+                Text::new(format!(
+                    "Woche vom {} bis {}",
+                    time_zone.format(&start, "%d.%m.%Y"),
+                    time_zone.format(&(end - Duration::minutes(1)), "%d.%m.%Y")
+                ))
+                .size(TEXT_SIZE),
+            )
+            .push(
+                Button::new(Text::new("Als .ics exportieren").size(TEXT_SIZE))
+                    .on_press(ScheduleMessage::ExportIcs),
+            )
+            .push(Text::new(&self.status).size(TEXT_SIZE));
+
+        let mut agenda = Column::new().spacing(10).width(Length::Fill);
+        let overdue = self.schedule.overdue(now);
+        if !overdue.is_empty() {
+            agenda = agenda.push(Text::new("Überfällig").size(TEXT_SIZE).style(OVERDUE_COLOR));
+            for task in overdue {
+                let time = time_zone.format(&task.due(), INPUT_FORMAT);
+                agenda = agenda.push(self.task_row(&time, task, true));
+            }
+            agenda = agenda.push(Rule::horizontal(10));
+        }
+        let occurrences = self.schedule.occurrences(start, end, time_zone);
+        let mut day = start;
+        while day < end {
+            let next_day = next_midnight(day, time_zone).min(end);
+            let local = time_zone.to_local(&day);
+            agenda = agenda.push(
+                Text::new(format!(
+                    "{} {}",
+                    weekday_name(local.weekday()),
+                    local.format("%d.%m.")
+                ))
+                .size(TEXT_SIZE),
+            );
+            let mut empty = true;
+            for (time, task) in occurrences
+                .iter()
+                .filter(|(time, _)| *time >= day && *time < next_day)
+            {
+                // Overdue tasks are already listed above
+                if *time < now {
+                    continue;
+                }
+                empty = false;
+                agenda = agenda.push(self.task_row(&time_zone.format(time, "%H:%M"), task, false));
+            }
+            if empty {
+                agenda = agenda.push(Text::new("-").size(TEXT_SIZE));
+            }
+            agenda = agenda.push(Rule::horizontal(10));
+            day = next_day;
+        }
+        if self.schedule.tasks.is_empty() {
+            agenda = agenda.push(
+                Text::new("Aufgaben werden auf der Detailseite oder bei den Gruppen angelegt")
+                    .size(TEXT_SIZE),
+            );
+        }
+
+        let content: Element<'_, ScheduleMessage> = Column::new()
+            .spacing(20)
+            .push(header)
+            .push(
+                scrollable::Scrollable::new(agenda)
+                    .width(Length::Fill)
+                    .height(Length::Fill),
+            )
+            .into();
+        content.map(Message::Schedule)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use chrono_tz::Tz;
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn task(every: u32, unit: IntervalUnit, due: DateTime<Utc>) -> CareTask {
+        CareTask {
+            id: 0,
+            kind: CareType::Giessen,
+            target: TaskTarget::Plant("1".to_string()),
+            target_name: "Basilikum".to_string(),
+            every,
+            unit,
+            next_due: due.to_rfc3339_opts(SecondsFormat::Secs, true),
+        }
+    }
+
+    #[test]
+    fn test_advance_keeps_wall_clock_time() {
+        let berlin = TimeZoneChoice::Zone(Tz::Europe__Berlin);
+        // 08:00 in Berlin before the clocks are turned forward
+        let task = task(3, IntervalUnit::Tage, utc(2023, 3, 24, 7, 0));
+        assert_eq!(task.advance(task.due(), berlin), utc(2023, 3, 27, 6, 0));
+        let monthly = CareTask {
+            unit: IntervalUnit::Monate,
+            every: 1,
+            ..task.clone()
+        };
+        assert_eq!(
+            monthly.advance(utc(2023, 1, 31, 7, 0), berlin),
+            utc(2023, 2, 28, 7, 0)
+        );
+        assert_eq!(monthly.interval_text(), "monatlich");
+        assert_eq!(task.interval_text(), "alle 3 Tage");
+    }
+
+    #[test]
+    fn test_done_reschedules() {
+        let mut schedule = Schedule::default();
+        schedule.add(task(1, IntervalUnit::Wochen, utc(2023, 5, 1, 8, 0)));
+        schedule.add(task(2, IntervalUnit::Tage, utc(2023, 5, 3, 8, 0)));
+        assert_eq!(schedule.tasks[1].id, 2);
+        let now = utc(2023, 5, 2, 10, 0);
+        assert_eq!(schedule.overdue(now).len(), 1);
+        schedule.done(1, now, TimeZoneChoice::Zone(Tz::UTC));
+        assert_eq!(schedule.tasks[0].due(), utc(2023, 5, 9, 10, 0));
+        assert!(schedule.overdue(now).is_empty());
+        schedule.remove(2);
+        assert_eq!(schedule.tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_occurrences_in_week() {
+        let mut schedule = Schedule::default();
+        schedule.add(task(3, IntervalUnit::Tage, utc(2023, 4, 28, 8, 0)));
+        let utc_zone = TimeZoneChoice::Zone(Tz::UTC);
+        let start = week_start(utc(2023, 5, 3, 12, 0), utc_zone);
+        assert_eq!(start, utc(2023, 5, 1, 0, 0));
+        let occurrences = schedule.occurrences(start, start + Duration::days(7), utc_zone);
+        let times: Vec<DateTime<Utc>> = occurrences.iter().map(|(time, _)| *time).collect();
+        assert_eq!(
+            times,
+            vec![
+                utc(2023, 5, 1, 8, 0),
+                utc(2023, 5, 4, 8, 0),
+                utc(2023, 5, 7, 8, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_occurrences_far_ahead() {
+        let mut schedule = Schedule::default();
+        schedule.add(task(1, IntervalUnit::Tage, utc(2023, 5, 1, 8, 0)));
+        schedule.add(task(1, IntervalUnit::Monate, utc(2023, 1, 31, 8, 0)));
+        let berlin = TimeZoneChoice::Zone(Tz::Europe__Berlin);
+        // Two years after the due time, far more than the listed occurrences of one task
+        let start = utc(2025, 3, 31, 0, 0);
+        let times: Vec<DateTime<Utc>> = schedule
+            .occurrences(start, start + Duration::days(7), berlin)
+            .iter()
+            .map(|(time, _)| *time)
+            .collect();
+        assert_eq!(times.len(), 8);
+        // The monthly task keeps its day and its wall clock time of 9:00 in the winter
+        assert_eq!(times[0], utc(2025, 3, 31, 7, 0));
+        // The daily task occurs every day of the week at 10:00 summer time
+        assert_eq!(times[1], utc(2025, 3, 31, 8, 0));
+        assert_eq!(times[7], utc(2025, 4, 6, 8, 0));
+    }
+
+    #[test]
+    fn test_tasks_for_target() {
+        let mut schedule = Schedule::default();
+        schedule.add(task(3, IntervalUnit::Tage, utc(2023, 5, 5, 8, 0)));
+        schedule.add(CareTask {
+            target: TaskTarget::Group("2".to_string()),
+            ..task(1, IntervalUnit::Monate, utc(2023, 5, 1, 8, 0))
+        });
+        schedule.add(task(1, IntervalUnit::Wochen, utc(2023, 5, 2, 8, 0)));
+        let tasks = schedule.tasks_for(&TaskTarget::Plant("1".to_string()));
+        assert_eq!(
+            tasks.iter().map(|task| task.id).collect::<Vec<_>>(),
+            vec![3, 1]
+        );
+        assert_eq!(
+            schedule
+                .tasks_for(&TaskTarget::Group("2".to_string()))
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_to_ics() {
+        let mut schedule = Schedule::default();
+        schedule.add(CareTask {
+            target_name: "Basilikum, Topf 1".to_string(),
+            ..task(3, IntervalUnit::Tage, utc(2023, 5, 1, 8, 0))
+        });
+        let ics = schedule.to_ics(
+            utc(2023, 4, 30, 12, 0),
+            TimeZoneChoice::Zone(Tz::Europe__Berlin),
+        );
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("UID:task-1@plantbuddy\r\n"));
+        assert!(ics.contains("DTSTAMP:20230430T120000Z\r\n"));
+        assert!(ics.contains("DTSTART;TZID=Europe/Berlin:20230501T100000\r\n"));
+        assert!(ics.contains("RRULE:FREQ=DAILY;INTERVAL=3\r\n"));
+        assert!(ics.contains("SUMMARY:Gießen: Basilikum\\, Topf 1\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    }
+
+    #[test]
+    fn test_monthly_keeps_day() {
+        let mut schedule = Schedule::default();
+        schedule.add(task(1, IntervalUnit::Monate, utc(2023, 1, 31, 8, 0)));
+        let utc_zone = TimeZoneChoice::Zone(Tz::UTC);
+        let times: Vec<DateTime<Utc>> = schedule
+            .occurrences(utc(2023, 1, 1, 0, 0), utc(2023, 5, 1, 0, 0), utc_zone)
+            .iter()
+            .map(|(time, _)| *time)
+            .collect();
+        assert_eq!(
+            times,
+            vec![
+                utc(2023, 1, 31, 8, 0),
+                utc(2023, 2, 28, 8, 0),
+                utc(2023, 3, 31, 8, 0),
+                utc(2023, 4, 30, 8, 0)
+            ]
+        );
+        let ics = schedule.to_ics(utc(2023, 1, 1, 0, 0), utc_zone);
+        assert!(ics.contains("DTSTART;TZID=UTC:20230131T080000\r\n"));
+        assert!(
+            ics.contains("RRULE:FREQ=MONTHLY;INTERVAL=1;BYMONTHDAY=28,29,30,31;BYSETPOS=-1\r\n")
+        );
+        let floating = schedule.to_ics(utc(2023, 1, 1, 0, 0), TimeZoneChoice::System);
+        assert!(floating.contains(&format!(
+            "DTSTART:{}\r\n",
+            ics_local_time(TimeZoneChoice::System.to_local(&utc(2023, 1, 31, 8, 0)))
+        )));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = "X".repeat(100);
+        let folded = fold_line(&line);
+        assert_eq!(folded, format!("{}\r\n {}", "X".repeat(75), "X".repeat(25)));
+    }
+
+    #[test]
+    fn test_task_form() {
+        let mut form = TaskForm::new();
+        assert_eq!(form.update(TaskFormMessage::Create), None);
+        assert_eq!(form.error, "Keine Pflanze ausgewählt");
+        form.update(TaskFormMessage::Select(
+            TaskTarget::Group("4".to_string()),
+            "Kräuter".to_string(),
+        ));
+        form.update(TaskFormMessage::EveryChanged("0".to_string()));
+        assert_eq!(form.update(TaskFormMessage::Create), None);
+        form.update(TaskFormMessage::EveryChanged("2".to_string()));
+        form.update(TaskFormMessage::UnitSelected(IntervalUnit::Wochen));
+        match form.update(TaskFormMessage::Create) {
+            Some(ScheduleMessage::Add(task)) => {
+                assert_eq!(task.target, TaskTarget::Group("4".to_string()));
+                assert_eq!(task.title(), "Gießen: Gruppe Kräuter");
+                assert_eq!(task.interval_text(), "alle 2 Wochen");
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_schedule_tab_persists_tasks() {
+        let path = std::env::temp_dir()
+            .join("plantbuddy_schedule_test")
+            .join("tasks.json");
+        let _ = std::fs::remove_file(&path);
+        let mut tab = ScheduleTab::with_path(path.clone());
+        tab.update(ScheduleMessage::Add(task(
+            3,
+            IntervalUnit::Tage,
+            utc(2023, 5, 1, 8, 0),
+        )));
+        let reloaded = ScheduleTab::with_path(path);
+        assert_eq!(reloaded.schedule.tasks.len(), 1);
+        assert_eq!(reloaded.schedule.tasks[0].id, 1);
+    }
+}
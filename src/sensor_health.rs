@@ -0,0 +1,384 @@
+use crate::detail::Sensortypes;
+use crate::graphs::{factor, graph_points};
+use crate::requests::{GraphData, PlantReadings};
+use crate::timezone::{from_minutes, TimeZoneChoice};
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+/// A gap is reported if it is this many times longer than the usual interval of the readings
+const GAP_FACTOR: i32 = 3;
+/// Gaps shorter than this many minutes are never reported
+const MIN_GAP: i32 = 60;
+/// A sensor is stuck if its readings do not change for this many minutes
+const STUCK_MINUTES: i32 = 6 * 60;
+/// A sensor is only stuck if at least this many readings are equal
+const STUCK_READINGS: usize = 6;
+
+/// Contains the causes of a sensor issue
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IssueKind {
+    /// No readings arrived for the given number of minutes
+    Gap(i32),
+    /// No readings arrived in the whole checked time range
+    Silent,
+    /// The sensor reported the same value for the given number of minutes
+    Stuck(i32),
+    /// The sensor reported the given number of impossible values
+    Spike(usize),
+}
+
+impl Display for IssueKind {
+    /// Returns the explanation of the cause
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IssueKind::Gap(minutes) => {
+                write!(f, "keine Messwerte für {}", format_duration(*minutes))
+            }
+            IssueKind::Silent => write!(f, "keine Messwerte im Zeitraum"),
+            IssueKind::Stuck(minutes) => {
+                write!(f, "unveränderter Wert seit {}", format_duration(*minutes))
+            }
+            IssueKind::Spike(1) => write!(f, "ein unmöglicher Messwert"),
+            IssueKind::Spike(count) => write!(f, "{} unmögliche Messwerte", count),
+        }
+    }
+}
+
+/// A problem of a sensor found in its readings
+///
+/// Fields:
+/// * `sensor` - The affected sensor
+/// * `kind` - The cause of the issue
+/// * `last_valid` - The time of the last valid reading in minutes since the unix epoch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SensorIssue {
+    pub sensor: Sensortypes,
+    pub kind: IssueKind,
+    pub last_valid: Option<i32>,
+}
+
+impl SensorIssue {
+    /// Returns the explanation of the issue with the time of the last valid reading
+    pub fn description(&self) -> String {
+        let last_valid = match self.last_valid {
+            Some(minutes) => {
+                TimeZoneChoice::current().format(&from_minutes(minutes), "%d.%m.%Y %H:%M")
+            }
+            None => "-".to_string(),
+        };
+        format!(
+            "{}: {}, letzter gültiger Wert {}",
+            self.sensor, self.kind, last_valid
+        )
+    }
+}
+
+/// Returns the range of values a sensor can physically report, in the unit of the server
+fn physical_range(sensor: Sensortypes) -> (i32, i32) {
+    let (min, max) = match sensor {
+        Sensortypes::Feuchtigkeit | Sensortypes::Luftfeuchtigkeit => (0, 100),
+        Sensortypes::Temperatur => (-40, 80),
+        Sensortypes::Licht => (0, 200_000),
+    };
+    (min * factor(sensor), max * factor(sensor))
+}
+
+/// Returns the largest jump away from both neighbours that is still possible
+///
+/// The light changes too quickly to detect spikes, only its physical range is checked.
+fn max_jump(sensor: Sensortypes) -> Option<i32> {
+    match sensor {
+        Sensortypes::Feuchtigkeit | Sensortypes::Luftfeuchtigkeit => Some(30 * factor(sensor)),
+        Sensortypes::Temperatur => Some(15),
+        Sensortypes::Licht => None,
+    }
+}
+
+/// Returns the indices of the readings that are physically impossible
+///
+/// A reading is impossible if it is outside the physical range of the sensor, or if it jumps
+/// away from both neighbours in the same direction while the neighbours stay close together.
+fn impossible_readings(sensor: Sensortypes, points: &[(i32, i32)]) -> Vec<usize> {
+    let (min, max) = physical_range(sensor);
+    (0..points.len())
+        .filter(|index| {
+            let value = points[*index].1;
+            if value < min || value > max {
+                return true;
+            }
+            match (
+                max_jump(sensor),
+                index.checked_sub(1),
+                points.get(index + 1),
+            ) {
+                (Some(jump), Some(previous), Some(next)) => {
+                    let previous = points[previous].1;
+                    let up = value - previous > jump && value - next.1 > jump;
+                    let down = previous - value > jump && next.1 - value > jump;
+                    (up || down) && (previous - next.1).abs() <= jump
+                }
+                _ => false,
+            }
+        })
+        .collect()
+}
+
+/// Checks the readings of a sensor for gaps, stuck values and impossible spikes
+///
+/// `now` is the end of the checked time range, a gap before it counts like a gap between
+/// two readings. The readings must be sorted by time.
+pub fn check_series(sensor: Sensortypes, points: &[(i32, i32)], now: i32) -> Vec<SensorIssue> {
+    let mut issues = vec![];
+    let spikes = impossible_readings(sensor, points);
+    let valid = points
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| !spikes.contains(index))
+        .map(|(_, point)| *point)
+        .collect_vec();
+    let last_valid = valid.last().map(|point| point.0);
+    if !spikes.is_empty() {
+        issues.push(SensorIssue {
+            sensor,
+            kind: IssueKind::Spike(spikes.len()),
+            last_valid,
+        });
+    }
+    match valid.as_slice() {
+        [] => {
+            issues.push(SensorIssue {
+                sensor,
+                kind: IssueKind::Silent,
+                last_valid: None,
+            });
+            return issues;
+        }
+        // Without an interval only the time since the single reading can be checked
+        [(time, _)] => {
+            if now - time > MIN_GAP {
+                issues.push(SensorIssue {
+                    sensor,
+                    kind: IssueKind::Gap(now - time),
+                    last_valid: Some(*time),
+                });
+            }
+            return issues;
+        }
+        _ => {}
+    }
+
+    // The usual interval is the median distance between two readings
+    let intervals = valid
+        .iter()
+        .tuple_windows()
+        .map(|(a, b)| b.0 - a.0)
+        .sorted()
+        .collect_vec();
+    let expected = (intervals[intervals.len() / 2] * GAP_FACTOR).max(MIN_GAP);
+    let ends = valid
+        .iter()
+        .map(|point| point.0)
+        .chain(std::iter::once(now));
+    // The most recent gap is reported, it is the one that still needs attention
+    if let Some((start, end)) = ends
+        .tuple_windows()
+        .filter(|(start, end)| end - start > expected)
+        .last()
+    {
+        issues.push(SensorIssue {
+            sensor,
+            kind: IssueKind::Gap(end - start),
+            last_valid: Some(start),
+        });
+    }
+
+    let (last_time, last_value) = valid[valid.len() - 1];
+    let run = valid
+        .iter()
+        .rev()
+        .take_while(|point| point.1 == last_value)
+        .count();
+    let run_start = valid[valid.len() - run].0;
+    // The light is 0 lux through every night, which is no stuck sensor
+    let dark = sensor == Sensortypes::Licht && last_value == 0;
+    if !dark && run >= STUCK_READINGS && last_time - run_start >= STUCK_MINUTES {
+        // The last reading that still changed is the last one that can be trusted
+        issues.push(SensorIssue {
+            sensor,
+            kind: IssueKind::Stuck(now.max(last_time) - run_start),
+            last_valid: Some(run_start),
+        });
+    }
+    issues
+}
+
+/// Checks the readings of the server for one sensor
+pub fn check_graph_data(
+    sensor: Sensortypes,
+    graph_data: &[GraphData],
+    now: i32,
+) -> Vec<SensorIssue> {
+    check_series(sensor, &graph_points(graph_data), now)
+}
+
+/// Checks all sensors of a plant, a sensor with empty readings is reported as silent
+pub fn check_plant(plant: &PlantReadings, now: i32) -> Vec<SensorIssue> {
+    plant
+        .readings
+        .iter()
+        .filter_map(|(name, graph_data)| Some((Sensortypes::from_name(name)?, graph_data)))
+        .flat_map(|(sensor, graph_data)| {
+            check_graph_data(sensor, std::slice::from_ref(graph_data), now)
+        })
+        .collect()
+}
+
+/// Formats a duration in minutes, e.g. "3 Std." or "2 Tage"
+fn format_duration(minutes: i32) -> String {
+    match minutes {
+        minutes if minutes < 60 => format!("{} Min.", minutes),
+        minutes if minutes < 48 * 60 => format!("{} Std.", minutes / 60),
+        minutes => format!("{} Tage", minutes / (24 * 60)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::timestamp_to_minutes;
+
+    /// Returns readings every 10 minutes
+    fn series(values: &[i32]) -> Vec<(i32, i32)> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (index as i32 * 10, *value))
+            .collect()
+    }
+
+    #[test]
+    fn test_healthy_series() {
+        let points = series(&[4000, 3990, 3980, 3970, 3960]);
+        assert!(check_series(Sensortypes::Feuchtigkeit, &points, 45).is_empty());
+    }
+
+    #[test]
+    fn test_gap() {
+        let mut points = series(&[20, 21, 22, 21]);
+        points.push((200, 20));
+        points.push((210, 21));
+        let issues = check_series(Sensortypes::Temperatur, &points, 215);
+        assert_eq!(
+            issues,
+            vec![SensorIssue {
+                sensor: Sensortypes::Temperatur,
+                kind: IssueKind::Gap(170),
+                last_valid: Some(30),
+            }]
+        );
+        // The sensor stopped reporting
+        let issues = check_series(Sensortypes::Temperatur, &series(&[20, 21, 22]), 500);
+        assert_eq!(issues[0].kind, IssueKind::Gap(480));
+        assert_eq!(issues[0].last_valid, Some(20));
+    }
+
+    #[test]
+    fn test_stuck() {
+        let mut values = vec![5000, 5100];
+        values.extend(std::iter::repeat_n(5200, 40));
+        let points = series(&values);
+        let issues = check_series(Sensortypes::Luftfeuchtigkeit, &points, 415);
+        assert_eq!(
+            issues,
+            vec![SensorIssue {
+                sensor: Sensortypes::Luftfeuchtigkeit,
+                kind: IssueKind::Stuck(395),
+                last_valid: Some(20),
+            }]
+        );
+        assert!(check_series(Sensortypes::Luftfeuchtigkeit, &points[..20], 195).is_empty());
+    }
+
+    #[test]
+    fn test_dark_night_is_not_stuck() {
+        let mut values = vec![30000, 12000];
+        values.extend(std::iter::repeat_n(0, 60));
+        let points = series(&values);
+        assert!(check_series(Sensortypes::Licht, &points, 615).is_empty());
+        // A light sensor stuck at a bright value is still reported
+        let mut values = vec![0, 12000];
+        values.extend(std::iter::repeat_n(30000, 60));
+        let issues = check_series(Sensortypes::Licht, &series(&values), 615);
+        assert_eq!(issues[0].kind, IssueKind::Stuck(595));
+    }
+
+    #[test]
+    fn test_no_data() {
+        let issues = check_series(Sensortypes::Feuchtigkeit, &[], 600);
+        assert_eq!(
+            issues,
+            vec![SensorIssue {
+                sensor: Sensortypes::Feuchtigkeit,
+                kind: IssueKind::Silent,
+                last_valid: None,
+            }]
+        );
+        let issues = check_series(Sensortypes::Feuchtigkeit, &[(0, 4000)], 600);
+        assert_eq!(issues[0].kind, IssueKind::Gap(600));
+        assert!(check_series(Sensortypes::Feuchtigkeit, &[(590, 4000)], 600).is_empty());
+        let plant = PlantReadings {
+            readings: vec![(
+                "light".to_string(),
+                GraphData {
+                    values: vec![],
+                    timestamps: vec![],
+                },
+            )],
+            ..PlantReadings::default()
+        };
+        assert_eq!(check_plant(&plant, 600)[0].kind, IssueKind::Silent);
+    }
+
+    #[test]
+    fn test_spikes() {
+        let points = series(&[2000, 2010, 9500, 2020, 2030, -100]);
+        let issues = check_series(Sensortypes::Feuchtigkeit, &points, 55);
+        assert_eq!(
+            issues,
+            vec![SensorIssue {
+                sensor: Sensortypes::Feuchtigkeit,
+                kind: IssueKind::Spike(2),
+                last_valid: Some(40),
+            }]
+        );
+        // A watering is a step and no spike
+        let step = series(&[2000, 2010, 6000, 6000, 5990]);
+        assert!(check_series(Sensortypes::Feuchtigkeit, &step, 45).is_empty());
+    }
+
+    #[test]
+    fn test_check_plant() {
+        let plant = PlantReadings {
+            readings: vec![(
+                "temperature".to_string(),
+                GraphData {
+                    values: vec![20, 21, 22],
+                    timestamps: vec![
+                        "2023-05-01T08:00:00Z".to_string(),
+                        "2023-05-01T08:10:00Z".to_string(),
+                        "2023-05-01T08:20:00Z".to_string(),
+                    ],
+                },
+            )],
+            ..PlantReadings::default()
+        };
+        let start = timestamp_to_minutes("2023-05-01T08:00:00Z").unwrap();
+        assert!(check_plant(&plant, start + 25).is_empty());
+        let issues = check_plant(&plant, start + 24 * 60);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].sensor, Sensortypes::Temperatur);
+        assert!(issues[0]
+            .description()
+            .starts_with("Temperatur: keine Messwerte für 23 Std."));
+    }
+}
@@ -1,6 +1,7 @@
+use crate::timezone::TimeZoneChoice;
 use crate::{Icon, Message, Tab};
 use iced::{
-    widget::{Column, Container, Radio, Text},
+    widget::{pick_list, Column, Container, Radio, Text},
     Element,
 };
 use iced_aw::style::TabBarStyles;
@@ -29,6 +30,7 @@ impl From<TabBarPosition> for String {
 pub struct TabSettings {
     pub tab_bar_position: Option<TabBarPosition>,
     pub tab_bar_theme: Option<TabBarStyles>,
+    pub time_zone: TimeZoneChoice,
 }
 
 impl TabSettings {
@@ -36,24 +38,29 @@ impl TabSettings {
         TabSettings {
             tab_bar_position: Some(TabBarPosition::Top),
             tab_bar_theme: Some(TabBarStyles::Green),
+            time_zone: TimeZoneChoice::System,
         }
     }
 }
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Clone)]
 pub enum SettingsMessage {
     PositionSelected(TabBarPosition),
     ThemeSelected(TabBarStyles),
+    TimeZoneSelected(TimeZoneChoice),
 }
 
 pub struct SettingsTab {
     settings: TabSettings,
+    time_zones: Vec<TimeZoneChoice>,
 }
 
 impl SettingsTab {
     pub fn new() -> Self {
         SettingsTab {
             settings: TabSettings::new(),
+            time_zones: TimeZoneChoice::all(),
         }
     }
 
@@ -67,6 +74,7 @@ impl SettingsTab {
                 self.settings.tab_bar_position = Some(position)
             }
             SettingsMessage::ThemeSelected(theme) => self.settings.tab_bar_theme = Some(theme),
+            SettingsMessage::TimeZoneSelected(time_zone) => self.settings.time_zone = time_zone,
         }
     }
 }
@@ -113,6 +121,17 @@ impl Tab for SettingsTab {
                             .size(35),
                         )
                     }),
+                )
+                .push(Text::new("Time zone:").size(35))
+                .push(
+                    Column::new().padding(10).push(
+                        pick_list(
+                            &self.time_zones[..],
+                            Some(self.settings().time_zone),
+                            SettingsMessage::TimeZoneSelected,
+                        )
+                        .text_size(35),
+                    ),
                 ),
         )
         .into();
@@ -176,6 +195,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_settings_tab_update_time_zone() {
+        let mut settings_tab = SettingsTab::new();
+        let berlin = TimeZoneChoice::Zone(chrono_tz::Tz::Europe__Berlin);
+        settings_tab.update(SettingsMessage::TimeZoneSelected(berlin));
+        assert_eq!(settings_tab.settings().time_zone, berlin);
+    }
+
     #[test]
     fn test_settings_tab_title() {
         let settings_tab = SettingsTab::new();
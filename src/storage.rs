@@ -0,0 +1,61 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// The name of the directory in the home directory that contains the local data
+const DIRECTORY: &str = ".plantbuddy";
+
+/// Returns the path of a file of the local data
+///
+/// The files are stored in the home directory, or the working directory if there is none.
+pub fn data_file(name: &str) -> PathBuf {
+    std::env::var("HOME")
+        .map(PathBuf::from)
+        .or_else(|_| std::env::current_dir())
+        .unwrap_or_default()
+        .join(DIRECTORY)
+        .join(name)
+}
+
+/// Reads a JSON file, returns the default value if the file is missing or can not be read
+pub fn load<T: DeserializeOwned + Default>(path: &Path) -> T {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// Writes the value as JSON file, creating the directory if needed
+pub fn save<T: Serialize>(path: &Path, value: &T) -> std::io::Result<()> {
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    let text = serde_json::to_string_pretty(value)?;
+    std::fs::write(path, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir()
+            .join("plantbuddy_storage_test")
+            .join("values.json");
+        let mut values = HashMap::new();
+        values.insert("1".to_string(), vec![1, 2, 3]);
+        save(&path, &values).unwrap();
+        let loaded: HashMap<String, Vec<i32>> = load(&path);
+        assert_eq!(loaded, values);
+        std::fs::remove_file(&path).unwrap();
+        let missing: HashMap<String, Vec<i32>> = load(&path);
+        assert!(missing.is_empty());
+    }
+
+    #[test]
+    fn test_data_file() {
+        assert!(data_file("journal.json").ends_with(".plantbuddy/journal.json"));
+    }
+}
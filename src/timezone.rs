@@ -0,0 +1,170 @@
+use chrono::{DateTime, Local, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::{Tz, TZ_VARIANTS};
+use std::fmt::{Display, Formatter};
+use std::sync::RwLock;
+
+/// The time zone all times are displayed in, set from the settings tab
+static TIME_ZONE: RwLock<TimeZoneChoice> = RwLock::new(TimeZoneChoice::System);
+
+/// Contains the time zones the times can be displayed in
+///
+/// All times are stored and sent to the server in UTC, the time zone is only used to display
+/// times and to read times entered by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeZoneChoice {
+    /// The time zone of the operating system
+    #[default]
+    System,
+    /// A time zone of the IANA database
+    Zone(Tz),
+}
+
+impl TimeZoneChoice {
+    /// Returns the time zone that is currently selected
+    pub fn current() -> TimeZoneChoice {
+        *TIME_ZONE.read().unwrap()
+    }
+
+    /// Selects the time zone used to display all times
+    pub fn select(self) {
+        *TIME_ZONE.write().unwrap() = self;
+    }
+
+    /// Returns the system time zone followed by all time zones of the IANA database
+    pub fn all() -> Vec<TimeZoneChoice> {
+        std::iter::once(TimeZoneChoice::System)
+            .chain(TZ_VARIANTS.iter().map(|tz| TimeZoneChoice::Zone(*tz)))
+            .collect()
+    }
+
+    /// Returns the offset to UTC in minutes at the given time
+    pub fn offset_minutes(&self, time: &DateTime<Utc>) -> i32 {
+        let seconds = match self {
+            TimeZoneChoice::System => Local
+                .offset_from_utc_datetime(&time.naive_utc())
+                .fix()
+                .local_minus_utc(),
+            TimeZoneChoice::Zone(tz) => tz
+                .offset_from_utc_datetime(&time.naive_utc())
+                .fix()
+                .local_minus_utc(),
+        };
+        seconds / 60
+    }
+
+    /// Returns the wall clock time of the time zone at the given time
+    pub fn to_local(self, time: &DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            TimeZoneChoice::System => time.with_timezone(&Local).naive_local(),
+            TimeZoneChoice::Zone(tz) => time.with_timezone(&tz).naive_local(),
+        }
+    }
+
+    /// Returns the UTC time of a wall clock time of the time zone
+    ///
+    /// If the wall clock time occurs twice because the clocks were turned back, the earlier time
+    /// is used. Returns `None` if the wall clock time was skipped because the clocks were turned
+    /// forward.
+    pub fn to_utc(self, local: &NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            TimeZoneChoice::System => Local
+                .from_local_datetime(local)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc)),
+            TimeZoneChoice::Zone(tz) => tz
+                .from_local_datetime(local)
+                .earliest()
+                .map(|time| time.with_timezone(&Utc)),
+        }
+    }
+
+    /// Formats the time in the time zone
+    pub fn format(&self, time: &DateTime<Utc>, format: &str) -> String {
+        self.to_local(time).format(format).to_string()
+    }
+}
+
+impl Display for TimeZoneChoice {
+    /// Returns the name of the time zone
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeZoneChoice::System => write!(f, "Systemzeitzone"),
+            TimeZoneChoice::Zone(tz) => write!(f, "{}", tz.name()),
+        }
+    }
+}
+
+/// Returns the UTC time of the given minutes since the unix epoch
+pub fn from_minutes(minutes: i32) -> DateTime<Utc> {
+    Utc.timestamp_opt(minutes as i64 * 60, 0)
+        .single()
+        .unwrap_or_default()
+}
+
+/// Returns the minutes since the unix epoch of the given UTC time
+pub fn to_minutes(time: &DateTime<Utc>) -> i32 {
+    (time.timestamp() / 60) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn utc(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(year, month, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn naive(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_offset_minutes() {
+        let berlin = TimeZoneChoice::Zone(Tz::Europe__Berlin);
+        assert_eq!(berlin.offset_minutes(&utc(2023, 1, 15, 12, 0)), 60);
+        assert_eq!(berlin.offset_minutes(&utc(2023, 7, 15, 12, 0)), 120);
+        let kolkata = TimeZoneChoice::Zone(Tz::Asia__Kolkata);
+        assert_eq!(kolkata.offset_minutes(&utc(2023, 7, 15, 12, 0)), 330);
+    }
+
+    #[test]
+    fn test_to_local_and_back() {
+        let berlin = TimeZoneChoice::Zone(Tz::Europe__Berlin);
+        let time = utc(2023, 7, 15, 12, 30);
+        assert_eq!(berlin.to_local(&time), naive(2023, 7, 15, 14, 30));
+        assert_eq!(berlin.to_utc(&naive(2023, 7, 15, 14, 30)), Some(time));
+        assert_eq!(berlin.format(&time, "%H:%M"), "14:30");
+    }
+
+    #[test]
+    fn test_daylight_saving_transitions() {
+        let berlin = TimeZoneChoice::Zone(Tz::Europe__Berlin);
+        // 02:30 does not exist on the last sunday of march
+        assert_eq!(berlin.to_utc(&naive(2023, 3, 26, 2, 30)), None);
+        // 02:30 exists twice on the last sunday of october, the earlier one is used
+        assert_eq!(
+            berlin.to_utc(&naive(2023, 10, 29, 2, 30)),
+            Some(utc(2023, 10, 29, 0, 30))
+        );
+    }
+
+    #[test]
+    fn test_minutes() {
+        let time = utc(2023, 7, 15, 12, 30);
+        assert_eq!(from_minutes(to_minutes(&time)), time);
+        assert_eq!(to_minutes(&utc(1970, 1, 1, 1, 0)), 60);
+    }
+
+    #[test]
+    fn test_all_time_zones() {
+        let all = TimeZoneChoice::all();
+        assert_eq!(all[0], TimeZoneChoice::System);
+        assert!(all.contains(&TimeZoneChoice::Zone(Tz::UTC)));
+        assert_eq!(TimeZoneChoice::Zone(Tz::UTC).to_string(), "UTC");
+    }
+}
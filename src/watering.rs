@@ -0,0 +1,258 @@
+use crate::detail::Sensortypes;
+use crate::graphs::{factor, graph_points};
+use crate::journal::{CareEntry, CareType};
+use crate::requests::GraphData;
+use crate::timezone::from_minutes;
+use itertools::Itertools;
+
+/// The minimal rise of the soil moisture in percentage points that counts as watering
+pub const MIN_RISE: f64 = 8.0;
+/// The maximal duration of a rise in minutes, slower rises are caused by the weather
+pub const MAX_RISE_DURATION: i32 = 120;
+/// Rises that start less than this many minutes after the previous watering belong to it
+const MERGE_GAP: i32 = 180;
+/// A journal entry this many minutes before or after a detection already records it
+pub const JOURNAL_TOLERANCE: i32 = 180;
+
+/// A watering detected from a sudden rise of the soil moisture
+///
+/// Fields:
+/// * `minutes` - The time of the first raised reading in minutes since the unix epoch
+/// * `before` - The soil moisture before the watering in percent
+/// * `after` - The highest soil moisture after the watering in percent
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WateringEvent {
+    pub minutes: i32,
+    pub before: f64,
+    pub after: f64,
+}
+
+impl WateringEvent {
+    /// Returns the event as suggested entry of the care journal
+    pub fn to_entry(self) -> CareEntry {
+        CareEntry::new(
+            CareType::Giessen,
+            from_minutes(self.minutes),
+            None,
+            format!(
+                "Automatisch erkannt: Feuchtigkeit {:.1} % auf {:.1} %",
+                self.before, self.after
+            ),
+        )
+    }
+}
+
+/// The waterings detected in the soil moisture of a plant
+///
+/// Fields:
+/// * `events` - The detected waterings, the oldest first
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Waterings {
+    pub events: Vec<WateringEvent>,
+}
+
+impl Waterings {
+    /// Detects the waterings in the soil moisture series of the server
+    pub fn from_graph_data(graph_data: &[GraphData]) -> Self {
+        let factor = factor(Sensortypes::Feuchtigkeit) as f64;
+        let points = graph_points(graph_data)
+            .into_iter()
+            .map(|(minutes, value)| (minutes, value as f64 / factor))
+            .collect_vec();
+        Waterings {
+            events: detect_waterings(&points),
+        }
+    }
+
+    /// Returns the most recent watering
+    pub fn last(&self) -> Option<&WateringEvent> {
+        self.events.last()
+    }
+
+    /// Returns the average time between two waterings in minutes
+    pub fn average_interval(&self) -> Option<i32> {
+        match (self.events.first(), self.events.last()) {
+            (Some(first), Some(last)) if self.events.len() > 1 => {
+                Some((last.minutes - first.minutes) / (self.events.len() as i32 - 1))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the waterings that are not yet recorded in the care journal
+    pub fn suggestions(&self, journal: &[CareEntry]) -> Vec<WateringEvent> {
+        let logged = journal
+            .iter()
+            .filter(|entry| entry.kind == CareType::Giessen)
+            .filter_map(|entry| entry.minutes())
+            .collect_vec();
+        self.events
+            .iter()
+            .filter(|event| {
+                !logged
+                    .iter()
+                    .any(|minutes| (minutes - event.minutes).abs() <= JOURNAL_TOLERANCE)
+            })
+            .copied()
+            .collect()
+    }
+}
+
+/// Detects waterings in the soil moisture readings, sorted by time
+///
+/// A watering is a rise of at least `MIN_RISE` percentage points within `MAX_RISE_DURATION`
+/// minutes. The rise starts at the lowest reading before it and ends at the highest reading
+/// after it, so a watering measured over several readings is detected once.
+pub fn detect_waterings(points: &[(i32, f64)]) -> Vec<WateringEvent> {
+    let mut events: Vec<WateringEvent> = vec![];
+    let mut start = 0;
+    while start + 1 < points.len() {
+        // The highest reading within the duration of a rise
+        let peak = (start + 1..points.len())
+            .take_while(|index| points[*index].0 - points[start].0 <= MAX_RISE_DURATION)
+            .max_by(|a, b| points[*a].1.total_cmp(&points[*b].1));
+        let peak = match peak {
+            Some(peak) if points[peak].1 - points[start].1 >= MIN_RISE => peak,
+            _ => {
+                start += 1;
+                continue;
+            }
+        };
+        // Start at the lowest reading and follow the rise to its top
+        let mut low = start;
+        while low + 1 < peak && points[low + 1].1 <= points[low].1 {
+            low += 1;
+        }
+        let mut top = peak;
+        while top + 1 < points.len() && points[top + 1].1 >= points[top].1 {
+            top += 1;
+        }
+        let event = WateringEvent {
+            minutes: points[low + 1].0,
+            before: points[low].1,
+            after: points[top].1,
+        };
+        match events.last_mut() {
+            Some(last) if event.minutes - last.minutes <= MERGE_GAP => {
+                last.after = last.after.max(event.after)
+            }
+            _ => events.push(event),
+        }
+        start = top + 1;
+    }
+    events
+}
+
+/// Formats a duration in minutes as days and hours, e.g. "3 Tage 4 Std."
+pub fn format_interval(minutes: i32) -> String {
+    let days = minutes / (24 * 60);
+    let hours = (minutes % (24 * 60)) / 60;
+    match (days, hours) {
+        (0, hours) => format!("{} Std.", hours),
+        (1, 0) => "1 Tag".to_string(),
+        (1, hours) => format!("1 Tag {} Std.", hours),
+        (days, 0) => format!("{} Tage", days),
+        (days, hours) => format!("{} Tage {} Std.", days, hours),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graphs::timestamp_to_minutes;
+
+    /// Returns readings every 30 minutes that dry out and jump up at the given indices
+    fn drying_series(count: usize, waterings: &[usize]) -> Vec<(i32, f64)> {
+        let mut value = 40.0;
+        (0..count)
+            .map(|index| {
+                if waterings.contains(&index) {
+                    value = 60.0;
+                } else {
+                    value -= 0.2;
+                }
+                (index as i32 * 30, value)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_waterings() {
+        let points = drying_series(200, &[50, 150]);
+        let events = detect_waterings(&points);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].minutes, 50 * 30);
+        assert!((events[0].before - 30.0).abs() < 1e-9);
+        assert!((events[0].after - 60.0).abs() < 1e-9);
+        assert_eq!(events[1].minutes, 150 * 30);
+    }
+
+    #[test]
+    fn test_slow_rise_and_noise_are_ignored() {
+        // Rises by 10 percentage points over ten hours
+        let slow: Vec<(i32, f64)> = (0..20).map(|i| (i * 30, 40.0 + i as f64 * 0.5)).collect();
+        assert!(detect_waterings(&slow).is_empty());
+        let noise: Vec<(i32, f64)> = (0..20)
+            .map(|i| (i * 30, if i % 2 == 0 { 40.0 } else { 43.0 }))
+            .collect();
+        assert!(detect_waterings(&noise).is_empty());
+    }
+
+    #[test]
+    fn test_gradual_watering_is_detected_once() {
+        let points = vec![
+            (0, 35.0),
+            (30, 34.0),
+            (60, 40.0),
+            (90, 47.0),
+            (120, 52.0),
+            (200, 56.0),
+            (500, 50.0),
+        ];
+        let events = detect_waterings(&points);
+        assert_eq!(
+            events,
+            vec![WateringEvent {
+                minutes: 60,
+                before: 34.0,
+                after: 56.0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_waterings_summary() {
+        let graph_data = GraphData {
+            values: vec![3000, 2900, 5500, 5400, 3000, 6000],
+            timestamps: vec![
+                "2023-05-01T08:00:00Z".to_string(),
+                "2023-05-02T08:00:00Z".to_string(),
+                "2023-05-02T08:30:00Z".to_string(),
+                "2023-05-04T08:00:00Z".to_string(),
+                "2023-05-05T20:00:00Z".to_string(),
+                "2023-05-05T20:30:00Z".to_string(),
+            ],
+        };
+        let waterings = Waterings::from_graph_data(&[graph_data]);
+        assert_eq!(waterings.events.len(), 2);
+        assert_eq!(
+            waterings.last().unwrap().minutes,
+            timestamp_to_minutes("2023-05-05T20:30:00Z").unwrap()
+        );
+        assert_eq!(waterings.average_interval(), Some(3 * 24 * 60 + 12 * 60));
+        assert_eq!(format_interval(3 * 24 * 60 + 12 * 60), "3 Tage 12 Std.");
+        assert_eq!(format_interval(90), "1 Std.");
+
+        let logged = CareEntry::new(
+            CareType::Giessen,
+            from_minutes(waterings.events[0].minutes + 60),
+            Some(250.0),
+            String::new(),
+        );
+        let suggestions = waterings.suggestions(&[logged]);
+        assert_eq!(suggestions, vec![waterings.events[1]]);
+        let entry = suggestions[0].to_entry();
+        assert_eq!(entry.kind, CareType::Giessen);
+        assert_eq!(entry.timestamp, "2023-05-05T20:30:00Z");
+    }
+}